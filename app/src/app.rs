@@ -6,36 +6,108 @@ mod platform;
 mod window;
 
 use consts::*;
-use dendros::parse_trees;
 
-use config::AppConfig;
+use config::{AppConfig, Session, SessionDocument};
 
 use menu::*;
 use riced::{
     Clr, Element, Font, IcedAppSettings, Key, KeyboardEvent, Length, Modifiers,
-    PADDING, Pixels, Subscription, Task, Theme, ThemeStyle, WindowEvent,
-    WindowId, allow_automatic_tabbing, close_window, error_container, exit,
-    keyboard_events, modal_element, open_window, settings_container,
+    PADDING, Pixels, Subscription, Task, TaskHandle, Theme, ThemeStyle,
+    WindowEvent, WindowId, allow_automatic_tabbing, close_window,
+    error_container, exit, keyboard_events, modal_element, open_window,
+    progress_row, settings_container, sty_cont_tool_bar, time_every,
     window_events,
 };
 
 use thiserror::Error;
 
-use std::path::PathBuf;
-use treeview::{TreeView, TreeViewConfig, TvContextMenuSpecification, TvMsg};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use treeview::{
+    DISTANCE_MODEL_OPTS, DistanceModel, PreparedTree,
+    TREE_BUILDING_METHOD_OPTS, TreeBuildingMethod, TreeView, TreeViewConfig,
+    TvContextMenuSpecification, TvMsg, TvViewport,
+};
 use window::window_settings;
 
 pub struct App {
     winid: Option<WindowId>,
-    treeview: Option<TreeView>,
+    docs: Vec<Document>,
+    active_doc_id: Option<usize>,
+    doc_count: usize,
     menu: Option<AppMenu>,
     #[cfg(feature = "menu-custom")]
     active_context_menu: Option<ContextMenu>,
-    title: Option<String>,
     error: Option<AppError>,
     settings_visible: bool,
     settings: AppConfig,
     explain: bool,
+    tree_load_count: usize,
+    tree_load_options: ops::TreeLoadOptions,
+    tree_build_path: Option<PathBuf>,
+    tree_build_method: TreeBuildingMethod,
+    tree_build_model: DistanceModel,
+    tree_build_count: usize,
+    unsaved_prompt: Option<UnsavedPrompt>,
+    /// Sent once the save started from the unsaved changes prompt succeeds.
+    after_save: Option<AppMsg>,
+}
+
+/// Trees open in a tab, with the work being done on them in the background.
+struct Document {
+    id: usize,
+    treeview: TreeView,
+    title: Option<String>,
+    /// The file the trees were opened from or last saved to.
+    path_buf: Option<PathBuf>,
+    /// Some trees in the file were skipped when it was opened (burn-in or
+    /// thinning), so saving must not overwrite it.
+    trees_skipped: bool,
+    has_subtree_view: bool,
+    tree_load: Option<TreeLoad>,
+    tree_build: Option<TreeBuild>,
+    /// Applied once the trees being loaded are all in.
+    restore_viewport: Option<TvViewport>,
+    /// Modification time of the file when the trees in view were read.
+    modified: Option<SystemTime>,
+    /// A newer modification time seen by the last check for changes. The
+    /// file is reloaded if it is still the same at the next check.
+    modified_pending: Option<SystemTime>,
+}
+
+/// Unsaved changes to a document that are to be saved or discarded before
+/// the document is closed or reloaded.
+#[derive(Debug, Clone, Copy)]
+struct UnsavedPrompt {
+    doc_id: usize,
+    then: AfterPrompt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AfterPrompt {
+    CloseTab,
+    CloseWindow,
+    Reload,
+}
+
+/// A tree file being loaded in the background.
+struct TreeLoad {
+    id: usize,
+    path_buf: PathBuf,
+    handle: TaskHandle,
+    progress: Option<ops::TreeLoadProgress>,
+    tree_count: usize,
+    /// Trees read so far when the file is being reloaded. They replace the
+    /// trees in view only once all of them are in.
+    reloaded: Option<Vec<PreparedTree>>,
+}
+
+/// A tree being built from distances in the background.
+struct TreeBuild {
+    id: usize,
+    path_buf: PathBuf,
+    handle: TaskHandle,
+    fraction: Option<f32>,
 }
 
 #[derive(Debug, Error, Clone)]
@@ -55,6 +127,21 @@ pub enum AppError {
         tree_parse_error: dendros::TreeParseError,
         file_path: Option<PathBuf>,
     },
+
+    #[error("{}",
+        match file_path {
+            Some(file_path) => format!(
+                "{message}\n{}", file_path.as_os_str().to_string_lossy()
+            ),
+            None => message.to_string(),
+        }
+    )]
+    DocumentParseError { message: String, file_path: Option<PathBuf> },
+
+    #[error("Could not write file.\n{message}\n{}",
+        file_path.as_os_str().to_string_lossy()
+    )]
+    FileWriteError { message: String, file_path: PathBuf },
 }
 
 impl From<&AppError> for String {
@@ -81,13 +168,47 @@ pub enum AppMsg {
     HideContextMenu,
     // -------------------------------------------------------------------------
     TvMsg(TvMsg),
+    DocTvMsg(usize, TvMsg),
+    // -------------------------------------------------------------------------
+    TabSelected(usize),
+    TabClosed(usize),
+    CloseTab,
     // -------------------------------------------------------------------------
     OpenFile,
+    OpenRecentFile(usize),
+    ToggleRecentFilePin,
+    ClearRecentFiles,
+    ToggleReopenLastSession,
+    RestoreSession(Session),
+    ImportMetadata,
+    ImportAlignment,
+    Save,
     SaveAs,
+    UnsavedSave,
+    UnsavedDiscard,
+    UnsavedCancel,
     ExportPdf,
+    ExportTanglegramPdf,
     ExportSubtree,
+    ExportStatistics,
     PathToOpen(Option<PathBuf>),
+    TreeLoadEvent(usize, ops::TreeLoadEvent),
+    TreeLoadFinished(usize, Result<usize, AppError>),
+    CancelTreeLoad,
+    FileWatchTick,
+    MetadataPathToOpen(usize, Option<PathBuf>),
+    AlignmentPathToOpen(usize, Option<PathBuf>),
+    BuildTree,
+    BuildTreePathToOpen(Option<PathBuf>),
+    TreeBuildMethodChanged(TreeBuildingMethod),
+    TreeBuildModelChanged(DistanceModel),
+    BuildTreeConfirmed,
+    BuildTreeDialogClosed,
+    TreeBuildProgress(usize, f32),
+    TreeBuildFinished(usize, Result<Box<PreparedTree>, AppError>),
+    CancelTreeBuild,
     PathToSave {
+        doc_id: usize,
         path: Option<PathBuf>,
         subtree: bool,
     },
@@ -111,20 +232,527 @@ pub enum AppMsg {
     RegisterFileTypes,
     #[cfg(target_os = "windows")]
     UnregisterFileTypes,
+    #[cfg(target_os = "linux")]
+    InstanceActivated(Vec<PathBuf>),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileType {
     Newick,
     Nexus,
+    PhyloXml,
+    NeXml,
     Pdf,
     Other,
 }
 
+impl FileType {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("newick" | "tre") => FileType::Newick,
+            Some("tree" | "trees" | "nexus" | "nex") => FileType::Nexus,
+            Some("phyloxml" | "xml") => FileType::PhyloXml,
+            Some("nexml") => FileType::NeXml,
+            Some("pdf") => FileType::Pdf,
+            _ => FileType::Other,
+        }
+    }
+
+    /// Whether trees can be written in this format.
+    fn is_writable(self) -> bool {
+        matches!(self, FileType::Newick | FileType::PhyloXml | FileType::NeXml)
+    }
+}
+
+impl Document {
+    /// Whether the document has no trees and none are on the way, so that a
+    /// file can be opened in it instead of in a new tab.
+    fn is_empty(&self) -> bool {
+        !self.treeview.are_any_trees_loaded()
+            && self.tree_load.is_none()
+            && self.tree_build.is_none()
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.treeview.has_unsaved_edits()
+    }
+
+    fn mark_saved(&mut self) {
+        self.treeview.mark_saved();
+    }
+
+    /// The title, marked with an asterisk when there are unsaved changes.
+    fn label(&self) -> String {
+        let title = self.title.as_deref().unwrap_or("Untitled");
+        match self.is_dirty() {
+            true => format!("{title}*"),
+            false => title.to_string(),
+        }
+    }
+
+    fn abort_background_work(&mut self) {
+        if let Some(tree_load) = self.tree_load.take() {
+            tree_load.handle.abort();
+        }
+        if let Some(tree_build) = self.tree_build.take() {
+            tree_build.handle.abort();
+        }
+    }
+
+    fn view(&self) -> Element<'_, AppMsg> {
+        let progress_row =
+            self.tree_load.as_ref().map(TreeLoad::progress_row).or_else(|| {
+                self.tree_build.as_ref().map(TreeBuild::progress_row)
+            });
+        if !self.treeview.are_any_trees_loaded() {
+            let content: Element<'_, AppMsg> = match progress_row {
+                Some(progress_row) => {
+                    progress_row.width(riced::BTN_H1 * 20e0).into()
+                }
+                None => {
+                    riced::btn_txt("Open a Tree File", Some(AppMsg::OpenFile))
+                        .width(riced::BTN_H1 * 5e0)
+                        .into()
+                }
+            };
+            return riced::container(content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center(Length::Fill)
+                .into();
+        }
+
+        let id = self.id;
+        let v = self
+            .treeview
+            .view()
+            .map(move |tv_msg| AppMsg::DocTvMsg(id, tv_msg));
+        match progress_row {
+            Some(progress_row) => riced::iced_col![
+                v,
+                riced::container(progress_row)
+                    .padding(PADDING)
+                    .style(sty_cont_tool_bar)
+            ]
+            .into(),
+            None => v,
+        }
+    }
+}
+
+impl TreeLoad {
+    fn progress_row(&self) -> riced::Row<'_, AppMsg> {
+        let (message, fraction) = match &self.progress {
+            Some(progress) => (progress.message(), progress.fraction()),
+            None => match self.reloaded.is_some() {
+                true => (String::from("Reloading..."), None),
+                false => (String::from("Opening..."), None),
+            },
+        };
+        progress_row(
+            format!("{} - {message}", ops::input_name(&self.path_buf)),
+            fraction,
+            AppMsg::CancelTreeLoad,
+        )
+    }
+}
+
+impl TreeBuild {
+    fn progress_row(&self) -> riced::Row<'_, AppMsg> {
+        progress_row(
+            format!("Building a tree from {}", ops::input_name(&self.path_buf)),
+            self.fraction,
+            AppMsg::CancelTreeBuild,
+        )
+    }
+}
+
 impl App {
+    fn tree_build_dialog(&self) -> Element<'_, AppMsg> {
+        let content = riced::iced_col![
+            riced::iced_row![
+                riced::txt("Method").width(Length::FillPortion(7)),
+                riced::pick_list_common(riced::PickList::new(
+                    TREE_BUILDING_METHOD_OPTS,
+                    Some(self.tree_build_method),
+                    AppMsg::TreeBuildMethodChanged,
+                )),
+            ]
+            .spacing(PADDING)
+            .align_y(riced::Vertical::Center),
+            riced::iced_row![
+                riced::txt("Alignment distances").width(Length::FillPortion(7)),
+                riced::pick_list_common(riced::PickList::new(
+                    DISTANCE_MODEL_OPTS,
+                    Some(self.tree_build_model),
+                    AppMsg::TreeBuildModelChanged,
+                )),
+            ]
+            .spacing(PADDING)
+            .align_y(riced::Vertical::Center),
+        ]
+        .spacing(PADDING);
+        riced::dialog_container(
+            "Build Tree from Distances",
+            content,
+            "Build",
+            Some(AppMsg::BuildTreeConfirmed),
+            None,
+            AppMsg::BuildTreeDialogClosed,
+            PADDING * 3e0,
+            PADDING * 8e0,
+        )
+    }
+
+    fn unsaved_dialog(&self, prompt: UnsavedPrompt) -> Element<'_, AppMsg> {
+        let name = self
+            .docs
+            .iter()
+            .find(|doc| doc.id == prompt.doc_id)
+            .and_then(|doc| doc.title.clone())
+            .unwrap_or_else(|| String::from("Untitled"));
+        match prompt.then {
+            AfterPrompt::Reload => riced::dialog_container(
+                format!("{name} changed on disk"),
+                riced::txt("Reload it and discard the unsaved changes?"),
+                "Reload",
+                Some(AppMsg::UnsavedDiscard),
+                None,
+                AppMsg::UnsavedCancel,
+                PADDING * 3e0,
+                PADDING * 8e0,
+            ),
+            AfterPrompt::CloseTab | AfterPrompt::CloseWindow => {
+                riced::dialog_container(
+                    format!("Save changes to {name}?"),
+                    riced::txt(
+                        "The changes will be lost if they are not saved.",
+                    ),
+                    "Save",
+                    Some(AppMsg::UnsavedSave),
+                    Some(("Don't Save", AppMsg::UnsavedDiscard)),
+                    AppMsg::UnsavedCancel,
+                    PADDING * 3e0,
+                    PADDING * 8e0,
+                )
+            }
+        }
+    }
+
+    fn tab_bar(&self) -> Element<'_, AppMsg> {
+        let mut r = riced::iced_row![].spacing(PADDING);
+        for doc in &self.docs {
+            r = r.push(riced::tab(
+                doc.label(),
+                Some(doc.id) == self.active_doc_id,
+                AppMsg::TabSelected(doc.id),
+                AppMsg::TabClosed(doc.id),
+            ));
+        }
+        riced::container(r)
+            .width(Length::Fill)
+            .padding(PADDING)
+            .style(sty_cont_tool_bar)
+            .into()
+    }
+
     fn toggle_explain(&mut self) {
         self.explain = !self.explain;
     }
 
+    fn doc(&self) -> Option<&Document> {
+        self.docs.iter().find(|doc| Some(doc.id) == self.active_doc_id)
+    }
+
+    fn doc_mut(&mut self) -> Option<&mut Document> {
+        self.docs.iter_mut().find(|doc| Some(doc.id) == self.active_doc_id)
+    }
+
+    /// Adds an empty document in a new tab and makes it the active one. It
+    /// starts with the settings of the document that was active.
+    fn new_doc(&mut self) -> usize {
+        let cfg: TreeViewConfig = match self.doc() {
+            Some(doc) => doc.treeview.config(),
+            None => self.settings.clone().into(),
+        };
+        self.doc_count += 1;
+        let id = self.doc_count;
+        self.docs.push(Document {
+            id,
+            treeview: TreeView::new(cfg),
+            title: None,
+            path_buf: None,
+            trees_skipped: false,
+            has_subtree_view: false,
+            tree_load: None,
+            tree_build: None,
+            restore_viewport: None,
+            modified: None,
+            modified_pending: None,
+        });
+        self.active_doc_id = Some(id);
+        self.update_menu();
+        self.update_recent_files_menu();
+        id
+    }
+
+    /// The document to open new trees in: the active one if it is empty,
+    /// otherwise a new one.
+    fn doc_to_open_in(&mut self) -> usize {
+        match self.doc().filter(|doc| doc.is_empty()).map(|doc| doc.id) {
+            Some(doc_id) => doc_id,
+            None => self.new_doc(),
+        }
+    }
+
+    fn close_doc(&mut self, id: usize) {
+        if let Some(idx) = self.docs.iter().position(|doc| doc.id == id) {
+            let mut doc = self.docs.remove(idx);
+            doc.abort_background_work();
+            self.settings.tv_config = doc.treeview.config();
+            if self.active_doc_id == Some(id) {
+                self.active_doc_id = self
+                    .docs
+                    .get(idx.min(self.docs.len().saturating_sub(1)))
+                    .map(|doc| doc.id);
+            }
+            self.update_menu();
+            self.update_recent_files_menu();
+        }
+    }
+
+    /// Starts loading the trees from a file in the background, in the active
+    /// document if it is empty or in a new one otherwise.
+    fn open_path(&mut self, path_buf: PathBuf) -> (usize, Task<AppMsg>) {
+        let doc_id = self.doc_to_open_in();
+        (doc_id, self.load_into_doc(doc_id, path_buf, false))
+    }
+
+    /// Starts loading the trees from a file into a document. When reloading,
+    /// the trees in view are replaced once the new ones are all read.
+    fn load_into_doc(
+        &mut self,
+        doc_id: usize,
+        path_buf: PathBuf,
+        reload: bool,
+    ) -> Task<AppMsg> {
+        self.tree_load_count += 1;
+        let id = self.tree_load_count;
+        let (load_task, handle) = Task::sip(
+            ops::load_trees(path_buf.clone(), self.tree_load_options),
+            move |event| AppMsg::TreeLoadEvent(id, event),
+            move |result| AppMsg::TreeLoadFinished(id, result),
+        )
+        .abortable();
+        if let Some(doc) = self.docs.iter_mut().find(|doc| doc.id == doc_id) {
+            doc.modified = ops::modified_time(&path_buf);
+            doc.modified_pending = None;
+            doc.trees_skipped = !self.tree_load_options.keeps_all();
+            doc.tree_load = Some(TreeLoad {
+                id,
+                path_buf,
+                handle,
+                progress: None,
+                tree_count: 0,
+                reloaded: match reload {
+                    true => Some(Vec::new()),
+                    false => None,
+                },
+            });
+        }
+        load_task
+    }
+
+    /// Reloads the files of the documents that changed on disk since they
+    /// were read.
+    fn reload_changed_files(&mut self) -> Task<AppMsg> {
+        let mut to_reload: Vec<(usize, PathBuf)> = Vec::new();
+        for doc in &mut self.docs {
+            if doc.tree_load.is_some() {
+                continue;
+            }
+            let Some(path_buf) = &doc.path_buf else { continue };
+            let modified = ops::modified_time(path_buf);
+            if modified.is_none() || modified == doc.modified {
+                doc.modified_pending = None;
+            } else if modified != doc.modified_pending {
+                doc.modified_pending = modified;
+            } else if !doc.is_dirty() {
+                to_reload.push((doc.id, path_buf.clone()));
+            } else if self.unsaved_prompt.is_none() {
+                self.unsaved_prompt = Some(UnsavedPrompt {
+                    doc_id: doc.id,
+                    then: AfterPrompt::Reload,
+                });
+            }
+        }
+        Task::batch(
+            to_reload
+                .into_iter()
+                .map(|(doc_id, path_buf)| {
+                    self.load_into_doc(doc_id, path_buf, true)
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Moves a file that was opened or saved to the top of the recent files.
+    fn add_recent_file(&mut self, path: &Path) {
+        self.settings.add_recent_file(path);
+        self.settings.store();
+        #[cfg(target_os = "linux")]
+        platform::add_to_recently_used(path);
+        self.update_recent_files_menu();
+    }
+
+    fn update_recent_files_menu(&mut self) {
+        let current_pinned = self
+            .doc()
+            .and_then(|doc| doc.path_buf.as_deref())
+            .map(|path| self.settings.is_recent_file_pinned(path));
+        let items = recent_files_items(
+            &self.settings.recent_files, current_pinned,
+            self.settings.reopen_last_session,
+        );
+        if let Some(menu) = &mut self.menu {
+            menu.set_submenu_items(AppMenuItemId::RecentFiles, items);
+        }
+    }
+
+    /// Enables the menu items that apply to the trees in the active document.
+    fn update_menu(&mut self) {
+        let (has_trees, has_subtree_view, can_undo, tanglegram_shown) =
+            match self.doc() {
+                Some(doc) if doc.treeview.are_any_trees_loaded() => (
+                    true,
+                    doc.has_subtree_view,
+                    doc.treeview.can_undo(),
+                    doc.treeview.is_tanglegram_shown(),
+                ),
+                _ => (0, false, false, false),
+            };
+        if let Some(menu) = &mut self.menu {
+            for id in [
+                AppMenuItemId::Save,
+                AppMenuItemId::SaveAs,
+                AppMenuItemId::ExportPdf,
+                AppMenuItemId::ImportMetadata,
+                AppMenuItemId::ImportAlignment,
+                AppMenuItemId::Find,
+                AppMenuItemId::ExportStatistics,
+            ] {
+                match has_trees {
+                    true => menu.enable(id),
+                    false => menu.disable(id),
+                }
+            }
+            match can_undo {
+                true => menu.enable(AppMenuItemId::Undo),
+                false => menu.disable(AppMenuItemId::Undo),
+            }
+            match has_subtree_view {
+                true => menu.enable(AppMenuItemId::ExportSubtree),
+                false => menu.disable(AppMenuItemId::ExportSubtree),
+            }
+            match tanglegram_shown {
+                true => menu.enable(AppMenuItemId::ExportTanglegramPdf),
+                false => menu.disable(AppMenuItemId::ExportTanglegramPdf),
+            }
+            match self.docs.len() > 1 {
+                true => menu.enable(AppMenuItemId::CloseTab),
+                false => menu.disable(AppMenuItemId::CloseTab),
+            }
+        };
+    }
+
+    /// Passes a message to the tree view of a document. Messages produced by
+    /// the tree view are returned to the same document.
+    fn update_doc_tv(
+        &mut self,
+        doc_id: usize,
+        tv_msg: TvMsg,
+    ) -> Option<Task<AppMsg>> {
+        let doc = self.docs.iter_mut().find(|doc| doc.id == doc_id)?;
+        // The tree view does nothing with these, so they are handled here.
+        // Other messages are moved into the tree view rather than cloned, as
+        // some carry whole batches of trees.
+        let tv_msg = match tv_msg {
+            TvMsg::ExportDataRequested(data) => {
+                return Some(Task::future(ops::choose_file_to_data_export(
+                    doc_id, data,
+                )));
+            }
+            TvMsg::ExportFailed(file_path, message) => {
+                return Some(Task::done(AppMsg::ErrorSet(
+                    AppError::FileWriteError { message, file_path },
+                )));
+            }
+            TvMsg::MetadataParseFailed => {
+                return Some(Task::done(AppMsg::ErrorSet(
+                    AppError::DocumentParseError {
+                        message: String::from(
+                            "Could not read the metadata. The file needs a \
+                             header row and tip labels in the first column.",
+                        ),
+                        file_path: None,
+                    },
+                )));
+            }
+            tv_msg => tv_msg,
+        };
+        let follow_up = match &tv_msg {
+            TvMsg::ContextMenuInteractionBegin(specification) => Some(
+                Task::done(AppMsg::ShowTvContextMenu(specification.clone())),
+            ),
+            #[cfg(feature = "menu-custom")]
+            TvMsg::ContextMenuChosenIdx(_) => {
+                Some(Task::done(AppMsg::HideContextMenu))
+            }
+            _ => None,
+        };
+        let has_subtree_view = match &tv_msg {
+            TvMsg::SetSubtreeView(_node_id) => Some(true),
+            TvMsg::ClearSubtreeView => Some(false),
+            _ => None,
+        };
+        let menu_state =
+            |tv: &TreeView| (tv.can_undo(), tv.is_tanglegram_shown());
+        let menu_state_before = menu_state(&doc.treeview);
+        let mut task = doc
+            .treeview
+            .update(tv_msg)
+            .map(move |tv_msg| AppMsg::DocTvMsg(doc_id, tv_msg));
+        if let Some(follow_up) = follow_up {
+            task = task.chain(follow_up);
+        }
+        let mut menu_state_changed =
+            menu_state(&doc.treeview) != menu_state_before;
+        if let Some(has_subtree_view) = has_subtree_view {
+            doc.has_subtree_view = has_subtree_view;
+            menu_state_changed = true;
+        }
+        if menu_state_changed && self.active_doc_id == Some(doc_id) {
+            self.update_menu();
+        }
+        Some(task)
+    }
+
+    /// Hands the files given on the command line over to an already running
+    /// instance. `--new-instance`, reading from stdin and the tree loading
+    /// options all apply to this launch only, so they start a new instance.
+    #[cfg(target_os = "linux")]
+    pub fn forward_to_running_instance() -> bool {
+        let args = ops::CmdLineArgs::parse(std::env::args());
+        if args.new_instance
+            || args.burn_in.is_some()
+            || args.thinning.is_some()
+            || args.paths.iter().any(|path| ops::is_stdin(path))
+        {
+            return false;
+        }
+        platform::forward_to_running_instance(&args.paths)
+    }
+
     pub fn boot() -> (Self, Task<AppMsg>) {
         #[cfg(target_os = "macos")]
         platform::register_ns_application_delegate_handlers();
@@ -136,16 +764,32 @@ impl App {
             }
         }
 
+        let mut settings = AppConfig::load();
+        settings.remove_missing_recent_files();
+        let tree_load_options = ops::TreeLoadOptions {
+            burn_in: settings.tree_burn_in,
+            thinning: settings.tree_thinning,
+        };
+
         (
             App {
                 winid: None,
-                treeview: None,
+                docs: Vec::new(),
+                active_doc_id: None,
+                doc_count: 0,
                 menu: None,
-                title: None,
                 error: None,
                 settings_visible: false,
-                settings: AppConfig::load(),
+                settings,
                 explain: false,
+                tree_load_count: 0,
+                tree_load_options,
+                tree_build_path: None,
+                tree_build_method: TreeBuildingMethod::default(),
+                tree_build_model: DistanceModel::default(),
+                tree_build_count: 0,
+                unsaved_prompt: None,
+                after_save: None,
                 #[cfg(feature = "menu-custom")]
                 active_context_menu: None,
             },
@@ -156,22 +800,14 @@ impl App {
 
     pub fn view(&'_ self, _: WindowId) -> Element<'_, AppMsg> {
         let mut v: Element<'_, AppMsg>;
-        if let Some(treeview) = &self.treeview {
-            if !treeview.are_any_trees_loaded() {
-                v = riced::container(
-                    riced::btn_txt("Open a Tree File", Some(AppMsg::OpenFile))
-                        .width(riced::BTN_H1 * 5e0),
-                )
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .center(Length::Fill)
-                .into();
-            } else {
-                v = treeview.view().map(AppMsg::TvMsg);
-                #[cfg(feature = "menu-custom")]
-                if let Some(context_menu) = &self.active_context_menu {
-                    v = context_menu.element(v);
-                }
+        if let Some(doc) = self.doc() {
+            v = doc.view();
+            if self.docs.len() > 1 {
+                v = riced::iced_col![self.tab_bar(), v].into();
+            }
+            #[cfg(feature = "menu-custom")]
+            if let Some(context_menu) = &self.active_context_menu {
+                v = context_menu.element(v);
             }
         } else {
             v = riced::container(riced::txt("App::view"))
@@ -199,6 +835,14 @@ impl App {
             );
         }
 
+        if self.tree_build_path.is_some() {
+            v = modal_element(v, self.tree_build_dialog());
+        }
+
+        if let Some(prompt) = self.unsaved_prompt {
+            v = modal_element(v, self.unsaved_dialog(prompt));
+        }
+
         if let Some(error) = &self.error {
             v = modal_element(
                 v,
@@ -271,8 +915,8 @@ impl App {
                         {
                             match k {
                                 "d" => {
-                                    if let Some(treeview) = &mut self.treeview {
-                                        treeview.toggle_draw_debug();
+                                    if let Some(doc) = self.doc_mut() {
+                                        doc.treeview.toggle_draw_debug();
                                     }
                                 }
                                 "e" => {
@@ -305,7 +949,7 @@ impl App {
                             match k {
                                 "w" => {
                                     task = Some(Task::done(AppMsg::MenuEvent(
-                                        AppMenuItemId::CloseWindow,
+                                        AppMenuItemId::CloseTab,
                                     )));
                                 }
                                 _ => {}
@@ -389,44 +1033,44 @@ impl App {
             }
 
             AppMsg::TvMsg(tv_msg) => {
-                if let Some(treeview) = &mut self.treeview {
-                    task = Some(
-                        treeview.update(tv_msg.clone()).map(AppMsg::TvMsg),
-                    );
-                    match tv_msg {
-                        TvMsg::ContextMenuInteractionBegin(specification) => {
-                            task = Some(Task::done(AppMsg::ShowTvContextMenu(
-                                specification,
-                            )));
-                        }
+                if let Some(doc_id) = self.active_doc_id {
+                    task = self.update_doc_tv(doc_id, tv_msg);
+                }
+            }
 
-                        #[cfg(feature = "menu-custom")]
-                        TvMsg::ContextMenuChosenIdx(_) => {
-                            if let Some(current_task) = task {
-                                task = Some(current_task.chain(Task::done(
-                                    AppMsg::HideContextMenu,
-                                )));
-                            } else {
-                                task =
-                                    Some(Task::done(AppMsg::HideContextMenu));
-                            }
-                        }
+            AppMsg::DocTvMsg(doc_id, tv_msg) => {
+                task = self.update_doc_tv(doc_id, tv_msg);
+            }
 
-                        TvMsg::SetSubtreeView(_node_id) => {
-                            if let Some(menu) = &mut self.menu {
-                                menu.enable(AppMenuItemId::ExportSubtree);
-                            }
-                        }
+            AppMsg::TabSelected(doc_id) => {
+                self.active_doc_id = Some(doc_id);
+                self.update_menu();
+                self.update_recent_files_menu();
+            }
 
-                        TvMsg::ClearSubtreeView => {
-                            if let Some(menu) = &mut self.menu {
-                                menu.disable(AppMenuItemId::ExportSubtree);
-                            }
+            AppMsg::TabClosed(doc_id) => match self.docs.len() > 1 {
+                true => {
+                    match self
+                        .docs
+                        .iter()
+                        .any(|doc| doc.id == doc_id && doc.is_dirty())
+                    {
+                        true => {
+                            self.unsaved_prompt = Some(UnsavedPrompt {
+                                doc_id,
+                                then: AfterPrompt::CloseTab,
+                            });
                         }
-
-                        _ => {}
+                        false => self.close_doc(doc_id),
                     }
                 }
+                false => task = Some(Task::done(AppMsg::WinCloseRequested)),
+            },
+
+            AppMsg::CloseTab => {
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::done(AppMsg::TabClosed(doc_id)));
+                }
             }
 
             AppMsg::OpenFile => {
@@ -447,120 +1091,550 @@ impl App {
                 }
 
                 if let Some(path_buf) = path_buf_opt {
-                    let parsed_data = ops::read_text_file(path_buf.clone());
-                    match parsed_data {
-                        Ok(trees_string) => {
-                            let trees_result = parse_trees(trees_string);
-                            match trees_result {
-                                Ok(trees) => {
-                                    self.title = Some(
-                                        path_buf
-                                            .file_name()
-                                            .unwrap_or_default()
-                                            .to_string_lossy()
-                                            .to_string(),
-                                    );
-                                    if let Some(menu) = &mut self.menu {
-                                        menu.enable(AppMenuItemId::SaveAs);
-                                        menu.enable(AppMenuItemId::ExportPdf);
-                                        menu.enable(AppMenuItemId::Find);
-                                        menu.disable(
-                                            AppMenuItemId::ExportSubtree,
-                                        );
-                                    };
-                                    task = Some(Task::done(AppMsg::TvMsg(
-                                        TvMsg::TreesLoaded(trees),
-                                    )));
-                                }
-                                Err(tree_parse_error) => {
-                                    if let Some(menu) = &mut self.menu {
-                                        menu.disable(AppMenuItemId::OpenFile);
-                                        menu.disable(AppMenuItemId::SaveAs);
-                                        menu.disable(AppMenuItemId::ExportPdf);
-                                    };
-                                    task = Some(Task::done(AppMsg::ErrorSet(
-                                        AppError::TreeParseError {
-                                            tree_parse_error,
-                                            file_path: Some(path_buf),
-                                        },
-                                    )));
-                                }
-                            }
+                    let (_, load_task) = self.open_path(path_buf);
+                    task = Some(load_task);
+                }
+            }
+
+            AppMsg::OpenRecentFile(idx) => {
+                if let Some(path_buf) = self
+                    .settings
+                    .recent_files
+                    .get(idx)
+                    .map(|recent_file| recent_file.path.clone())
+                {
+                    match path_buf.exists() {
+                        true => {
+                            task = Some(Task::done(AppMsg::PathToOpen(Some(
+                                path_buf,
+                            ))));
                         }
-                        Err(file_read_error) => {
-                            if let Some(menu) = &mut self.menu {
-                                menu.disable(AppMenuItemId::OpenFile);
-                                menu.disable(AppMenuItemId::SaveAs);
-                                menu.disable(AppMenuItemId::ExportPdf);
-                            };
+                        false => {
+                            self.settings.remove_missing_recent_files();
+                            self.settings.store();
+                            self.update_recent_files_menu();
                             task = Some(Task::done(AppMsg::ErrorSet(
-                                AppError::FileReadError { file_read_error },
+                                AppError::FileReadError {
+                                    file_read_error:
+                                        ops::FileReadError::InputError {
+                                            file_path: path_buf,
+                                        },
+                                },
                             )));
                         }
                     }
                 }
             }
 
+            AppMsg::ToggleRecentFilePin => {
+                if let Some(path_buf) =
+                    self.doc().and_then(|doc| doc.path_buf.clone())
+                {
+                    self.settings.toggle_recent_file_pin(&path_buf);
+                    self.settings.store();
+                    self.update_recent_files_menu();
+                }
+            }
+
+            AppMsg::ClearRecentFiles => {
+                self.settings.clear_recent_files();
+                self.settings.store();
+                self.update_recent_files_menu();
+            }
+
+            AppMsg::ToggleReopenLastSession => {
+                self.settings.reopen_last_session =
+                    !self.settings.reopen_last_session;
+                self.settings.store();
+                self.update_recent_files_menu();
+            }
+
+            AppMsg::RestoreSession(Session { documents, active_idx }) => {
+                if self.winid.is_some() {
+                    let mut load_tasks: Vec<Task<AppMsg>> = Vec::new();
+                    let mut active_doc_id: Option<usize> = None;
+                    for (idx, SessionDocument { path, viewport }) in
+                        documents.into_iter().enumerate()
+                    {
+                        if !path.exists() {
+                            continue;
+                        }
+                        let (doc_id, load_task) = self.open_path(path);
+                        if let Some(doc) =
+                            self.docs.iter_mut().find(|doc| doc.id == doc_id)
+                        {
+                            doc.restore_viewport = Some(viewport);
+                        }
+                        if idx <= active_idx {
+                            active_doc_id = Some(doc_id);
+                        }
+                        load_tasks.push(load_task);
+                    }
+                    if active_doc_id.is_some() {
+                        self.active_doc_id = active_doc_id;
+                        self.update_menu();
+                        self.update_recent_files_menu();
+                    }
+                    task = Some(Task::batch(load_tasks));
+                }
+            }
+
+            AppMsg::TreeLoadEvent(id, event) => {
+                if let Some(doc) = self.docs.iter_mut().find(|doc| {
+                    doc.tree_load.as_ref().is_some_and(|tl| tl.id == id)
+                }) && let Some(tree_load) = &mut doc.tree_load
+                {
+                    match (event, &mut tree_load.reloaded) {
+                        (ops::TreeLoadEvent::Progress(progress), _) => {
+                            tree_load.progress = Some(progress);
+                        }
+                        (ops::TreeLoadEvent::Trees(trees), Some(reloaded)) => {
+                            reloaded.extend(trees);
+                        }
+                        (ops::TreeLoadEvent::Trees(trees), None) => {
+                            let is_first_batch = tree_load.tree_count == 0;
+                            tree_load.tree_count += trees.len();
+                            let mut opened_path: Option<PathBuf> = None;
+                            if is_first_batch {
+                                doc.title =
+                                    Some(ops::input_name(&tree_load.path_buf));
+                                doc.has_subtree_view = false;
+                                doc.path_buf =
+                                    match ops::is_stdin(&tree_load.path_buf) {
+                                        true => None,
+                                        false => {
+                                            Some(tree_load.path_buf.clone())
+                                        }
+                                    };
+                                opened_path.clone_from(&doc.path_buf);
+                            }
+                            let doc_id = doc.id;
+                            let tv_msg = match is_first_batch {
+                                true => TvMsg::PreparedTreesLoaded(trees),
+                                false => TvMsg::PreparedTreesAppended(trees),
+                            };
+                            task = self.update_doc_tv(doc_id, tv_msg);
+                            self.update_menu();
+                            if let Some(path_buf) = opened_path {
+                                self.add_recent_file(&path_buf);
+                            }
+                        }
+                    }
+                }
+            }
+
+            AppMsg::TreeLoadFinished(id, result) => {
+                if let Some(doc) = self.docs.iter_mut().find(|doc| {
+                    doc.tree_load.as_ref().is_some_and(|tl| tl.id == id)
+                }) {
+                    let (reloaded, tree_count) = doc
+                        .tree_load
+                        .take()
+                        .map(|tl| (tl.reloaded, tl.tree_count))
+                        .unwrap_or_default();
+                    let restore_viewport = doc.restore_viewport.take();
+                    if reloaded.is_none() && tree_count > 0 {
+                        _ = doc
+                            .treeview
+                            .update(TvMsg::PreparedTreesAppendFinished);
+                    }
+                    if let Some(trees) = reloaded {
+                        // A file that is being written may not parse yet; the
+                        // trees in view are then kept until it changes again.
+                        if result.is_ok() && !trees.is_empty() {
+                            let doc_id = doc.id;
+                            task = self.update_doc_tv(
+                                doc_id,
+                                TvMsg::PreparedTreesReloaded(trees),
+                            );
+                            if let Some(doc) = self
+                                .docs
+                                .iter_mut()
+                                .find(|doc| doc.id == doc_id)
+                            {
+                                doc.mark_saved();
+                            }
+                            self.update_menu();
+                        }
+                    } else if result.is_ok()
+                        && let Some(viewport) = restore_viewport
+                    {
+                        let doc_id = doc.id;
+                        task = self.update_doc_tv(
+                            doc_id,
+                            TvMsg::ViewportRestored(viewport),
+                        );
+                    } else if let Err(app_error) = result {
+                        let doc_id = doc.id;
+                        if doc.is_empty() && self.docs.len() > 1 {
+                            self.close_doc(doc_id);
+                        }
+                        self.update_menu();
+                        if let Some(menu) = &mut self.menu {
+                            menu.disable(AppMenuItemId::OpenFile);
+                        };
+                        task = Some(Task::done(AppMsg::ErrorSet(app_error)));
+                    }
+                }
+            }
+
+            AppMsg::CancelTreeLoad => {
+                if let Some(doc) = self.doc_mut()
+                    && let Some(tree_load) = doc.tree_load.take()
+                {
+                    tree_load.handle.abort();
+                    if tree_load.reloaded.is_none() && tree_load.tree_count > 0
+                    {
+                        _ = doc
+                            .treeview
+                            .update(TvMsg::PreparedTreesAppendFinished);
+                    }
+                }
+            }
+
+            AppMsg::FileWatchTick => {
+                task = Some(self.reload_changed_files());
+            }
+
+            AppMsg::ImportMetadata => {
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::future(
+                        ops::choose_metadata_file_to_open(doc_id),
+                    ));
+                }
+            }
+
+            AppMsg::MetadataPathToOpen(doc_id, path_buf_opt) => {
+                if let Some(path_buf) = path_buf_opt {
+                    task =
+                        Some(Task::done(match ops::read_text_file(path_buf) {
+                            Ok(text) => AppMsg::DocTvMsg(
+                                doc_id,
+                                TvMsg::MetadataLoaded(text),
+                            ),
+                            Err(file_read_error) => {
+                                AppMsg::ErrorSet(AppError::FileReadError {
+                                    file_read_error,
+                                })
+                            }
+                        }));
+                }
+            }
+
+            AppMsg::ImportAlignment => {
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::future(
+                        ops::choose_alignment_file_to_open(doc_id),
+                    ));
+                }
+            }
+
+            AppMsg::AlignmentPathToOpen(doc_id, path_buf_opt) => {
+                if let Some(path_buf) = path_buf_opt {
+                    task =
+                        Some(Task::done(match ops::read_text_file(path_buf) {
+                            Ok(text) => AppMsg::DocTvMsg(
+                                doc_id,
+                                TvMsg::AlignmentLoaded(text),
+                            ),
+                            Err(file_read_error) => {
+                                AppMsg::ErrorSet(AppError::FileReadError {
+                                    file_read_error,
+                                })
+                            }
+                        }));
+                }
+            }
+
+            AppMsg::BuildTree => {
+                task = Some(Task::future(ops::choose_distance_file_to_open()));
+            }
+
+            AppMsg::BuildTreePathToOpen(path_buf_opt) => {
+                if self.winid.is_none() {
+                    return Task::done(AppMsg::WinOpen).chain(Task::done(
+                        AppMsg::BuildTreePathToOpen(path_buf_opt),
+                    ));
+                }
+                self.tree_build_path = path_buf_opt;
+            }
+
+            AppMsg::TreeBuildMethodChanged(method) => {
+                self.tree_build_method = method;
+            }
+
+            AppMsg::TreeBuildModelChanged(model) => {
+                self.tree_build_model = model;
+            }
+
+            AppMsg::BuildTreeDialogClosed => {
+                self.tree_build_path = None;
+            }
+
+            AppMsg::BuildTreeConfirmed => {
+                if let Some(path_buf) = self.tree_build_path.take()
+                    && let Some(doc_id) = self.active_doc_id
+                {
+                    self.tree_build_count += 1;
+                    let id = self.tree_build_count;
+                    let (build_task, handle) = Task::sip(
+                        ops::build_tree(
+                            path_buf.clone(),
+                            self.tree_build_method,
+                            self.tree_build_model,
+                        ),
+                        move |fraction| AppMsg::TreeBuildProgress(id, fraction),
+                        move |result| {
+                            AppMsg::TreeBuildFinished(id, result.map(Box::new))
+                        },
+                    )
+                    .abortable();
+                    if let Some(doc) =
+                        self.docs.iter_mut().find(|doc| doc.id == doc_id)
+                    {
+                        if let Some(tree_build) = doc.tree_build.take() {
+                            tree_build.handle.abort();
+                        }
+                        doc.tree_build = Some(TreeBuild {
+                            id,
+                            path_buf,
+                            handle,
+                            fraction: None,
+                        });
+                    }
+                    task = Some(build_task);
+                }
+            }
+
+            AppMsg::TreeBuildProgress(id, fraction) => {
+                if let Some(tree_build) = self
+                    .docs
+                    .iter_mut()
+                    .filter_map(|doc| doc.tree_build.as_mut())
+                    .find(|tree_build| tree_build.id == id)
+                {
+                    tree_build.fraction = Some(fraction);
+                }
+            }
+
+            AppMsg::TreeBuildFinished(id, result) => {
+                if let Some(doc) = self.docs.iter_mut().find(|doc| {
+                    doc.tree_build.as_ref().is_some_and(|tb| tb.id == id)
+                }) && let Some(tree_build) = doc.tree_build.take()
+                {
+                    match result {
+                        Ok(tre) => {
+                            if !doc.treeview.are_any_trees_loaded() {
+                                doc.title =
+                                    Some(ops::input_name(&tree_build.path_buf));
+                            }
+                            let doc_id = doc.id;
+                            task = self.update_doc_tv(
+                                doc_id,
+                                TvMsg::DerivedTreeAdded(tre),
+                            );
+                            self.update_menu();
+                        }
+                        Err(app_error) => {
+                            task =
+                                Some(Task::done(AppMsg::ErrorSet(app_error)));
+                        }
+                    }
+                }
+            }
+
+            AppMsg::CancelTreeBuild => {
+                if let Some(doc) = self.doc_mut()
+                    && let Some(tree_build) = doc.tree_build.take()
+                {
+                    tree_build.handle.abort();
+                }
+            }
+
+            AppMsg::Save => {
+                if let Some(doc) = self.doc() {
+                    let doc_id = doc.id;
+                    match doc.path_buf.clone().filter(|path| {
+                        FileType::from_path(path).is_writable()
+                            && !doc.trees_skipped
+                    }) {
+                        Some(path_buf) => {
+                            task = Some(Task::done(AppMsg::PathToSave {
+                                doc_id,
+                                path: Some(path_buf),
+                                subtree: false,
+                            }));
+                        }
+                        None => {
+                            task = Some(Task::future(
+                                ops::choose_file_to_save(doc_id, false),
+                            ));
+                        }
+                    }
+                }
+            }
+
             AppMsg::SaveAs => {
-                task = Some(Task::future(ops::choose_file_to_save(false)));
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::future(ops::choose_file_to_save(
+                        doc_id, false,
+                    )));
+                }
+            }
+
+            AppMsg::UnsavedSave => {
+                if let Some(UnsavedPrompt { doc_id, then }) =
+                    self.unsaved_prompt.take()
+                {
+                    self.active_doc_id = Some(doc_id);
+                    self.update_menu();
+                    self.update_recent_files_menu();
+                    self.after_save = match then {
+                        AfterPrompt::CloseTab => {
+                            Some(AppMsg::TabClosed(doc_id))
+                        }
+                        AfterPrompt::CloseWindow => {
+                            Some(AppMsg::WinCloseRequested)
+                        }
+                        AfterPrompt::Reload => None,
+                    };
+                    task = Some(Task::done(AppMsg::Save));
+                }
+            }
+
+            AppMsg::UnsavedDiscard => {
+                if let Some(UnsavedPrompt { doc_id, then }) =
+                    self.unsaved_prompt.take()
+                    && let Some(doc) =
+                        self.docs.iter_mut().find(|doc| doc.id == doc_id)
+                {
+                    doc.mark_saved();
+                    match then {
+                        AfterPrompt::CloseTab => {
+                            task = Some(Task::done(AppMsg::TabClosed(doc_id)));
+                        }
+                        AfterPrompt::CloseWindow => {
+                            task = Some(Task::done(AppMsg::WinCloseRequested));
+                        }
+                        AfterPrompt::Reload => {
+                            if let Some(path_buf) = doc.path_buf.clone() {
+                                task = Some(
+                                    self.load_into_doc(doc_id, path_buf, true),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+
+            AppMsg::UnsavedCancel => {
+                // A file that changed on disk is not offered for reloading
+                // again until it changes once more.
+                if let Some(UnsavedPrompt { doc_id, then }) =
+                    self.unsaved_prompt.take()
+                    && then == AfterPrompt::Reload
+                    && let Some(doc) =
+                        self.docs.iter_mut().find(|doc| doc.id == doc_id)
+                {
+                    doc.modified =
+                        doc.path_buf.as_deref().and_then(ops::modified_time);
+                    doc.modified_pending = None;
+                }
             }
 
             AppMsg::ExportSubtree => {
-                task = Some(Task::future(ops::choose_file_to_save(true)));
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::future(ops::choose_file_to_save(
+                        doc_id, true,
+                    )));
+                }
+            }
+
+            AppMsg::ExportStatistics => {
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::future(
+                        ops::choose_file_to_stats_export(doc_id),
+                    ));
+                }
             }
 
             AppMsg::ExportPdf => {
-                task = Some(Task::future(ops::choose_file_to_pdf_export()));
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::future(ops::choose_file_to_pdf_export(
+                        doc_id,
+                    )));
+                }
+            }
+
+            AppMsg::ExportTanglegramPdf => {
+                if let Some(doc_id) = self.active_doc_id {
+                    task = Some(Task::future(
+                        ops::choose_file_to_tanglegram_pdf_export(doc_id),
+                    ));
+                }
             }
 
-            AppMsg::PathToSave { path: path_buf_opt, subtree } => {
+            AppMsg::PathToSave { doc_id, path: path_buf_opt, subtree } => {
+                // Whatever waits for this save goes ahead only if the trees
+                // were written.
+                let after_save = self.after_save.take();
                 if let Some(path_buf) = path_buf_opt {
                     println!("{path_buf:?}");
-                    let file_type: FileType = match path_buf.extension() {
-                        Some(ext_os_str) => match ext_os_str.to_str() {
-                            Some(ext) => match ext {
-                                "newick" | "tre" => FileType::Newick,
-                                "tree" | "trees" | "nexus" | "nex" => {
-                                    FileType::Nexus
-                                }
-                                "pdf" => FileType::Pdf,
-                                _ => FileType::Other,
-                            },
-                            None => FileType::Other,
-                        },
-                        None => FileType::Other,
-                    };
+                    let file_type = FileType::from_path(&path_buf);
+                    let text = self
+                        .docs
+                        .iter()
+                        .find(|doc| doc.id == doc_id)
+                        .map(|doc| &doc.treeview)
+                        .and_then(|tv| match file_type {
+                            FileType::Newick => Some(match subtree {
+                                true => tv.newick_string_subtree(),
+                                false => tv.newick_string(),
+                            }),
+                            FileType::PhyloXml => {
+                                Some(tv.phyloxml_string(subtree))
+                            }
+                            FileType::NeXml => Some(tv.nexml_string(subtree)),
+                            FileType::Nexus
+                            | FileType::Pdf
+                            | FileType::Other => None,
+                        });
 
-                    match file_type {
-                        FileType::Newick => {
-                            if let Some(tv) = &self.treeview {
-                                let newick_string = match subtree {
-                                    true => &tv.newick_string_subtree(),
-                                    false => &tv.newick_string(),
-                                };
-                                ops::write_text_file(&path_buf, newick_string);
-
-                                match subtree {
-                                    true => {}
-                                    false => {
-                                        self.title = Some(
-                                            path_buf
-                                                .file_name()
-                                                .unwrap_or_default()
-                                                .to_string_lossy()
-                                                .to_string(),
-                                        );
+                    match (file_type, text) {
+                        (FileType::Pdf, _) => {
+                            task = Some(Task::done(AppMsg::DocTvMsg(
+                                doc_id,
+                                TvMsg::ExportPdf(path_buf),
+                            )));
+                        }
+                        (_, Some(text)) => {
+                            match ops::write_text_file(&path_buf, &text) {
+                                Ok(()) => {
+                                    if !subtree
+                                        && let Some(doc) = self
+                                            .docs
+                                            .iter_mut()
+                                            .find(|doc| doc.id == doc_id)
+                                    {
+                                        doc.title =
+                                            Some(ops::input_name(&path_buf));
+                                        doc.path_buf = Some(path_buf.clone());
+                                        doc.trees_skipped = false;
+                                        doc.modified =
+                                            ops::modified_time(&path_buf);
+                                        doc.mark_saved();
+                                        self.add_recent_file(&path_buf);
+                                        task = after_save.map(Task::done);
                                     }
                                 }
+                                Err(app_error) => {
+                                    task = Some(Task::done(AppMsg::ErrorSet(
+                                        app_error,
+                                    )));
+                                }
                             }
                         }
-                        FileType::Nexus => {} // Save Nexus file
-                        FileType::Pdf => {
-                            task = Some(Task::done(AppMsg::TvMsg(
-                                TvMsg::ExportPdf(path_buf),
-                            )));
-                        }
-                        FileType::Other => {}
+                        (_, None) => {} // Save Nexus file
                     }
                 }
             }
@@ -568,9 +1642,12 @@ impl App {
             AppMsg::AppInitialized => {
                 self.menu = Some(AppMenu::new());
                 if let Some(menu) = &mut self.menu {
+                    menu.disable(AppMenuItemId::Save);
                     menu.disable(AppMenuItemId::SaveAs);
                     menu.disable(AppMenuItemId::ExportPdf);
+                    menu.disable(AppMenuItemId::CloseTab);
                 }
+                self.update_recent_files_menu();
                 task = Some(Task::done(AppMsg::WinOpen));
             }
 
@@ -597,8 +1674,7 @@ impl App {
                     let (window_id, open_window_task) =
                         open_window(window_settings());
                     self.winid = Some(window_id);
-                    self.treeview =
-                        Some(TreeView::new(self.settings.clone().into()));
+                    _ = self.new_doc();
                     task = Some(open_window_task.discard());
                 } else {
                     eprintln!("AppMsg::OpenWindow -> Window is already open.");
@@ -610,35 +1686,35 @@ impl App {
                     menu.enable(AppMenuItemId::CloseWindow);
                 }
 
-                #[cfg(any(
-                    debug_assertions,
-                    target_os = "windows",
-                    target_os = "linux"
-                ))]
                 let mut task_to_return = Task::none();
 
-                #[cfg(all(not(debug_assertions), target_os = "macos"))]
-                let task_to_return = Task::none();
-
-                // #[cfg(all(not(debug_assertions), target_os = "linux"))]
-                // let mut task_to_return = Task::none();
-
                 #[cfg(all(target_os = "windows", feature = "menu-muda"))]
                 if let Some(id) = self.winid {
                     task_to_return = riced::get_raw_id::<AppMsg>(id)
                         .map(AppMsg::AddMenuForHwnd);
                 }
 
+                if self.settings.reopen_last_session
+                    && let Some(session) = self.settings.last_session.clone()
+                {
+                    task_to_return = task_to_return
+                        .chain(Task::done(AppMsg::RestoreSession(session)));
+                }
+
                 #[cfg(any(target_os = "windows", target_os = "linux"))]
                 {
-                    let args: Vec<String> = std::env::args().collect();
-                    if args.len() > 1 {
-                        let file_path = &args[1];
+                    let args = ops::CmdLineArgs::parse(std::env::args());
+                    if let Some(burn_in) = args.burn_in {
+                        self.tree_load_options.burn_in = burn_in;
+                    }
+                    if let Some(thinning) = args.thinning {
+                        self.tree_load_options.thinning = thinning;
+                    }
+                    for path_buf in args.paths {
                         task_to_return = task_to_return.chain({
-                            let path_buf = PathBuf::from(file_path);
                             let path: &std::path::Path =
                                 &path_buf.clone().into_boxed_path();
-                            if path.exists() {
+                            if path.exists() || ops::is_stdin(path) {
                                 Task::done(AppMsg::PathToOpen(Some(path_buf)))
                             } else {
                                 Task::none()
@@ -671,7 +1747,19 @@ impl App {
 
             AppMsg::WinCloseRequested => {
                 if self.winid.is_some() {
-                    task = Some(Task::done(AppMsg::WinClose));
+                    match self.docs.iter().find(|doc| doc.is_dirty()) {
+                        Some(doc) => {
+                            let doc_id = doc.id;
+                            self.active_doc_id = Some(doc_id);
+                            self.update_menu();
+                            self.update_recent_files_menu();
+                            self.unsaved_prompt = Some(UnsavedPrompt {
+                                doc_id,
+                                then: AfterPrompt::CloseWindow,
+                            });
+                        }
+                        None => task = Some(Task::done(AppMsg::WinClose)),
+                    }
                 } else {
                     eprintln!(
                         "AppMsg::CloseWindow -> There is no window to close."
@@ -681,13 +1769,39 @@ impl App {
 
             AppMsg::WinClose => {
                 if let Some(window_id) = self.winid {
-                    if let Some(tv) = &self.treeview {
-                        self.settings.tv_config = tv.config();
-                        self.settings.store();
+                    if let Some(tv_config) =
+                        self.doc().map(|doc| doc.treeview.config())
+                    {
+                        self.settings.tv_config = tv_config;
                     }
-
+                    let mut session = Session::default();
+                    for doc in &self.docs {
+                        let Some(path) = doc.path_buf.clone() else {
+                            continue;
+                        };
+                        if self.active_doc_id == Some(doc.id) {
+                            session.active_idx = session.documents.len();
+                        }
+                        session.documents.push(SessionDocument {
+                            path,
+                            viewport: doc.treeview.viewport(),
+                        });
+                    }
+                    self.settings.last_session =
+                        match session.documents.is_empty() {
+                            true => None,
+                            false => Some(session),
+                        };
+                    self.settings.store();
+
+                    for doc in &mut self.docs {
+                        doc.abort_background_work();
+                    }
+                    #[cfg(target_os = "linux")]
+                    platform::remove_instance_socket();
                     self.winid = None;
-                    self.treeview = None;
+                    self.docs.clear();
+                    self.active_doc_id = None;
                     task = Some(close_window(window_id));
                 }
             }
@@ -695,10 +1809,17 @@ impl App {
             AppMsg::WinClosed => {
                 if let Some(menu) = &mut self.menu {
                     menu.disable(AppMenuItemId::CloseWindow);
+                    menu.disable(AppMenuItemId::CloseTab);
+                    menu.disable(AppMenuItemId::Save);
                     menu.disable(AppMenuItemId::SaveAs);
                     menu.disable(AppMenuItemId::ExportPdf);
+                    menu.disable(AppMenuItemId::ExportTanglegramPdf);
+                    menu.disable(AppMenuItemId::ImportMetadata);
+                    menu.disable(AppMenuItemId::ImportAlignment);
                     menu.disable(AppMenuItemId::ExportSubtree);
+                    menu.disable(AppMenuItemId::ExportStatistics);
                     menu.disable(AppMenuItemId::Find);
+                    menu.disable(AppMenuItemId::Undo);
                 }
                 task = Some(Task::done(AppMsg::Quit));
             }
@@ -735,6 +1856,19 @@ impl App {
                     }
                 }
             }
+
+            #[cfg(target_os = "linux")]
+            AppMsg::InstanceActivated(paths) => {
+                let mut task_to_return = match self.winid {
+                    Some(id) => riced::focus_window(id),
+                    None => Task::done(AppMsg::WinOpen),
+                };
+                for path_buf in paths.into_iter().filter(|path| path.exists()) {
+                    task_to_return = task_to_return
+                        .chain(Task::done(AppMsg::PathToOpen(Some(path_buf))));
+                }
+                task = Some(task_to_return);
+            }
         }
 
         match task {
@@ -747,14 +1881,23 @@ impl App {
     }
 
     pub fn subscription(&self) -> Subscription<AppMsg> {
-        let mut subs: Vec<Subscription<AppMsg>> = Vec::with_capacity(4);
+        let mut subs: Vec<Subscription<AppMsg>> = Vec::with_capacity(6);
         #[cfg(target_os = "macos")]
         {
             subs.push(platform::os_events());
         }
+        #[cfg(target_os = "linux")]
+        {
+            subs.push(platform::instance_events());
+        }
         #[cfg(feature = "menu-muda")]
         subs.push(menu::menu_events());
         subs.push(window_events().map(|(_, e)| AppMsg::WinEvent(e)));
+        if self.docs.iter().any(|doc| doc.path_buf.is_some()) {
+            subs.push(
+                time_every(FILE_WATCH_INTERVAL).map(|_| AppMsg::FileWatchTick),
+            );
+        }
         subs.push(keyboard_events().map(|e| match e {
             KeyboardEvent::KeyPressed { key, modifiers, .. } => {
                 AppMsg::KeysPressed(key, modifiers)
@@ -766,10 +1909,9 @@ impl App {
     }
 
     pub fn title(&self, _: WindowId) -> String {
-        if let Some(title) = &self.title {
-            title.clone()
-        } else {
-            String::from("")
+        match self.doc().filter(|doc| doc.title.is_some()) {
+            Some(doc) => doc.label(),
+            None => String::from(""),
         }
     }
 
@@ -1,6 +1,9 @@
+use std::path::{Path, PathBuf};
+
 use super::APP_ID;
 use super::APP_NAME;
 use super::TreeViewConfig;
+use super::TvViewport;
 
 use confy::ConfigStrategy;
 use confy::change_config_strategy;
@@ -13,6 +16,41 @@ use serde::{Deserialize, Serialize};
 #[serde(default)]
 pub struct AppConfig {
     pub tv_config: TreeViewConfig,
+    /// Number of trees to skip from the start of a file.
+    pub tree_burn_in: usize,
+    /// Keep every n-th tree after the burn-in; `0` and `1` keep all of them.
+    pub tree_thinning: usize,
+    /// Most recently opened or saved first.
+    pub recent_files: Vec<RecentFile>,
+    pub reopen_last_session: bool,
+    pub last_session: Option<Session>,
+}
+
+/// Number of recent files kept in addition to the pinned ones.
+const RECENT_FILES_MAX: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecentFile {
+    pub path: PathBuf,
+    /// Pinned files are listed first and are never dropped from the list.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// The files open in tabs when the app was last closed and which tab was
+/// active.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Session {
+    pub documents: Vec<SessionDocument>,
+    pub active_idx: usize,
+}
+
+/// A file open in a tab and where it was scrolled.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionDocument {
+    pub path: PathBuf,
+    pub viewport: TvViewport,
 }
 
 impl From<AppConfig> for TreeViewConfig {
@@ -29,6 +67,40 @@ impl AppConfig {
     pub fn store(&self) {
         store_config(self);
     }
+
+    pub fn add_recent_file(&mut self, path: &Path) {
+        let pinned = self.is_recent_file_pinned(path);
+        self.recent_files.retain(|rf| rf.path != path);
+        self.recent_files.insert(0, RecentFile { path: path.into(), pinned });
+        let mut unpinned: usize = 0;
+        self.recent_files.retain(|rf| {
+            unpinned += usize::from(!rf.pinned);
+            rf.pinned || unpinned <= RECENT_FILES_MAX
+        });
+    }
+
+    pub fn is_recent_file_pinned(&self, path: &Path) -> bool {
+        self.recent_files.iter().any(|rf| rf.pinned && rf.path == path)
+    }
+
+    pub fn toggle_recent_file_pin(&mut self, path: &Path) {
+        match self.recent_files.iter_mut().find(|rf| rf.path == path) {
+            Some(rf) => rf.pinned = !rf.pinned,
+            None => self
+                .recent_files
+                .insert(0, RecentFile { path: path.into(), pinned: true }),
+        }
+    }
+
+    /// Removes the unpinned files from the list.
+    pub fn clear_recent_files(&mut self) {
+        self.recent_files.retain(|rf| rf.pinned);
+    }
+
+    /// Removes the files that no longer exist from the list.
+    pub fn remove_missing_recent_files(&mut self) {
+        self.recent_files.retain(|rf| rf.path.exists());
+    }
 }
 
 #[cfg(debug_assertions)]
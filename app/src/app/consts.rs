@@ -1,7 +1,19 @@
 pub const APP_NAME: &str = "TreeHouse";
 pub const APP_ID: &str = "is.karol.treehouse";
 
+use std::time::Duration;
+
 use treeview::SF;
 pub use treeview::TXT_SIZE;
 
 pub(crate) const APP_SCALE_FACTOR: f32 = 1e0 / SF;
+
+/// Size of the buffers tree files are read (and decompressed) through.
+pub(crate) const TREE_LOAD_CHUNK_SIZE: usize = 1 << 22;
+/// How often progress and trees prepared in the background are handed over
+/// to the view.
+pub(crate) const TREE_LOAD_BATCH_INTERVAL: Duration =
+    Duration::from_millis(250);
+/// How often open files are checked for changes. A changed file is reloaded
+/// once it has stayed unchanged for one more interval.
+pub(crate) const FILE_WATCH_INTERVAL: Duration = Duration::from_secs(1);
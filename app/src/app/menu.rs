@@ -3,6 +3,7 @@ mod app_menu_item_id;
 mod implementation;
 mod menu_model;
 
+pub(crate) use app_menu_bar::recent_files_items;
 pub(crate) use app_menu_item_id::AppMenuItemId;
 pub(crate) use implementation::AppMenu;
 pub(crate) use implementation::show_tv_context_menu;
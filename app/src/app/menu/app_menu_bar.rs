@@ -1,3 +1,4 @@
+use super::super::config::RecentFile;
 use super::AppMenuItemId;
 use super::menu_model::Accelerator;
 use super::menu_model::KeyCode;
@@ -47,6 +48,7 @@ pub(crate) fn app_menu_bar() -> Menu {
     }
 
     let mut subm_file_items = vec![];
+    let mut subm_edit_items = vec![];
     let mut subm_view_items = vec![];
 
     let mi_open = MenuItem::item(
@@ -56,13 +58,44 @@ pub(crate) fn app_menu_bar() -> Menu {
         Some(Accelerator { modifier, key: KeyCode::KeyO }),
     );
 
-    let mi_save_as = MenuItem::item(
-        "Save As...",
+    let subm_recent = MenuItem::submenu(
+        "Open Recent",
         true,
-        AppMenuItemId::SaveAs,
+        AppMenuItemId::RecentFiles,
+        recent_files_items(&[], None, false),
+    );
+
+    let mi_import_metadata = MenuItem::item(
+        "Import Metadata...",
+        false,
+        AppMenuItemId::ImportMetadata,
+        None,
+    );
+
+    let mi_import_alignment = MenuItem::item(
+        "Import Alignment...",
+        false,
+        AppMenuItemId::ImportAlignment,
+        None,
+    );
+
+    let mi_build_tree = MenuItem::item(
+        "Build Tree from Distances...",
+        true,
+        AppMenuItemId::BuildTree,
+        None,
+    );
+
+    let mi_save = MenuItem::item(
+        "Save",
+        true,
+        AppMenuItemId::Save,
         Some(Accelerator { modifier, key: KeyCode::KeyS }),
     );
 
+    let mi_save_as =
+        MenuItem::item("Save As...", true, AppMenuItemId::SaveAs, None);
+
     let mi_export_subtree = MenuItem::item(
         "Save Current Subtree",
         false,
@@ -77,6 +110,30 @@ pub(crate) fn app_menu_bar() -> Menu {
         Some(Accelerator { modifier, key: KeyCode::KeyP }),
     );
 
+    let mi_export_tanglegram_pdf = MenuItem::item(
+        "Export Tanglegram as PDF",
+        false,
+        AppMenuItemId::ExportTanglegramPdf,
+        None,
+    );
+
+    let mi_export_statistics = MenuItem::item(
+        "Export Tree Statistics...",
+        false,
+        AppMenuItemId::ExportStatistics,
+        None,
+    );
+
+    let mi_close_tab =
+        MenuItem::item("Close Tab", false, AppMenuItemId::CloseTab, None);
+
+    let mi_undo = MenuItem::item(
+        "Undo",
+        false,
+        AppMenuItemId::Undo,
+        Some(Accelerator { modifier, key: KeyCode::KeyZ }),
+    );
+
     let mi_toggle_search_bar = MenuItem::item(
         "Find",
         false,
@@ -85,11 +142,20 @@ pub(crate) fn app_menu_bar() -> Menu {
     );
 
     subm_file_items.push(mi_open);
+    subm_file_items.push(subm_recent);
+    subm_file_items.push(mi_import_metadata);
+    subm_file_items.push(mi_import_alignment);
+    subm_file_items.push(mi_build_tree);
     subm_file_items.push(MenuItem::separator());
+    subm_file_items.push(mi_save);
     subm_file_items.push(mi_save_as);
     subm_file_items.push(mi_export_subtree);
     subm_file_items.push(MenuItem::separator());
     subm_file_items.push(mi_export_pdf);
+    subm_file_items.push(mi_export_tanglegram_pdf);
+    subm_file_items.push(mi_export_statistics);
+    subm_file_items.push(MenuItem::separator());
+    subm_file_items.push(mi_close_tab);
 
     #[cfg(all(target_os = "windows", debug_assertions))]
     {
@@ -127,6 +193,8 @@ pub(crate) fn app_menu_bar() -> Menu {
         subm_file_items.push(mi_close_win);
     }
 
+    subm_edit_items.push(mi_undo);
+
     subm_view_items.push(mi_toggle_search_bar);
 
     let subm_file = MenuItem::submenu(
@@ -136,6 +204,13 @@ pub(crate) fn app_menu_bar() -> Menu {
         subm_file_items,
     );
 
+    let subm_edit = MenuItem::submenu(
+        "Edit",
+        true,
+        AppMenuItemId::Submenu,
+        subm_edit_items,
+    );
+
     let subm_view = MenuItem::submenu(
         "View",
         true,
@@ -144,7 +219,83 @@ pub(crate) fn app_menu_bar() -> Menu {
     );
 
     menu.append(subm_file);
+    menu.append(subm_edit);
     menu.append(subm_view);
 
     menu
 }
+
+/// Items of the "Open Recent" submenu. `current_pinned` is `None` when the
+/// active document has no file to pin.
+pub(crate) fn recent_files_items(
+    recent_files: &[RecentFile],
+    current_pinned: Option<bool>,
+    reopen_last_session: bool,
+) -> Vec<MenuItem> {
+    let mut items = vec![];
+
+    let (pinned, unpinned): (Vec<_>, Vec<_>) =
+        recent_files.iter().enumerate().partition(|(_, rf)| rf.pinned);
+
+    pinned.iter().for_each(|(idx, rf)| {
+        items.push(MenuItem::item(
+            format!("\u{2605} {}", rf.path.display()),
+            true,
+            AppMenuItemId::OpenRecent(*idx),
+            None,
+        ));
+    });
+
+    if !pinned.is_empty() && !unpinned.is_empty() {
+        items.push(MenuItem::separator());
+    }
+
+    unpinned.iter().for_each(|(idx, rf)| {
+        items.push(MenuItem::item(
+            rf.path.display().to_string(),
+            true,
+            AppMenuItemId::OpenRecent(*idx),
+            None,
+        ));
+    });
+
+    if recent_files.is_empty() {
+        items.push(MenuItem::item(
+            "No Recent Files",
+            false,
+            AppMenuItemId::Undefined,
+            None,
+        ));
+    }
+
+    let lbl_pin = match current_pinned {
+        Some(true) => "Unpin Current File",
+        _ => "Pin Current File",
+    };
+
+    items.push(MenuItem::separator());
+    items.push(MenuItem::item(
+        lbl_pin,
+        current_pinned.is_some(),
+        AppMenuItemId::PinCurrentFile,
+        None,
+    ));
+    items.push(MenuItem::item(
+        "Clear Recent Files",
+        recent_files.iter().any(|rf| !rf.pinned),
+        AppMenuItemId::ClearRecentFiles,
+        None,
+    ));
+    items.push(MenuItem::separator());
+    items.push(MenuItem::item(
+        match reopen_last_session {
+            true => "\u{2713} Reopen Last Session on Launch",
+            false => "Reopen Last Session on Launch",
+        },
+        true,
+        AppMenuItemId::ReopenLastSession,
+        None,
+    ));
+
+    items
+}
@@ -8,12 +8,25 @@ pub enum AppMenuItemId {
     About,
     Settings,
     OpenFile,
+    RecentFiles,
+    OpenRecent(usize),
+    PinCurrentFile,
+    ClearRecentFiles,
+    ReopenLastSession,
+    ImportMetadata,
+    ImportAlignment,
+    BuildTree,
+    Save,
     SaveAs,
     Quit,
     CloseWindow,
+    CloseTab,
+    Undo,
     Find,
     ExportPdf,
+    ExportTanglegramPdf,
     ExportSubtree,
+    ExportStatistics,
     #[cfg(target_os = "windows")]
     RegisterFileTypes,
     #[cfg(target_os = "windows")]
@@ -29,12 +42,24 @@ impl From<String> for AppMenuItemId {
             "Settings" => AppMenuItemId::Settings,
             "About" => AppMenuItemId::About,
             "OpenFile" => AppMenuItemId::OpenFile,
+            "RecentFiles" => AppMenuItemId::RecentFiles,
+            "PinCurrentFile" => AppMenuItemId::PinCurrentFile,
+            "ClearRecentFiles" => AppMenuItemId::ClearRecentFiles,
+            "ReopenLastSession" => AppMenuItemId::ReopenLastSession,
+            "ImportMetadata" => AppMenuItemId::ImportMetadata,
+            "ImportAlignment" => AppMenuItemId::ImportAlignment,
+            "BuildTree" => AppMenuItemId::BuildTree,
+            "Save" => AppMenuItemId::Save,
             "SaveAs" => AppMenuItemId::SaveAs,
             "CloseWindow" => AppMenuItemId::CloseWindow,
+            "CloseTab" => AppMenuItemId::CloseTab,
             "Quit" => AppMenuItemId::Quit,
+            "Undo" => AppMenuItemId::Undo,
             "Find" => AppMenuItemId::Find,
             "ExportPdf" => AppMenuItemId::ExportPdf,
+            "ExportTanglegramPdf" => AppMenuItemId::ExportTanglegramPdf,
             "ExportSubtree" => AppMenuItemId::ExportSubtree,
+            "ExportStatistics" => AppMenuItemId::ExportStatistics,
             "Submenu" => AppMenuItemId::Submenu,
             #[cfg(target_os = "windows")]
             "RegisterFileTypes" => AppMenuItemId::RegisterFileTypes,
@@ -50,6 +75,14 @@ impl From<String> for AppMenuItemId {
                     AppMenuItemId::Undefined
                 }
             }
+            val if val.starts_with("OpenRecent") => {
+                let idx_str = val.replace("OpenRecent(", "").replace(")", "");
+                if let Ok(idx) = idx_str.parse::<usize>() {
+                    AppMenuItemId::OpenRecent(idx)
+                } else {
+                    AppMenuItemId::Undefined
+                }
+            }
             // -----------------------------------------------------------------
             _ => AppMenuItemId::Undefined,
         }
@@ -67,15 +100,27 @@ impl From<&AppMenuItemId> for AppMsg {
         match app_menu_item_id {
             AppMenuItemId::Settings => AppMsg::ShowSettings,
             AppMenuItemId::OpenFile => AppMsg::OpenFile,
+            AppMenuItemId::OpenRecent(idx) => AppMsg::OpenRecentFile(*idx),
+            AppMenuItemId::PinCurrentFile => AppMsg::ToggleRecentFilePin,
+            AppMenuItemId::ClearRecentFiles => AppMsg::ClearRecentFiles,
+            AppMenuItemId::ReopenLastSession => AppMsg::ToggleReopenLastSession,
+            AppMenuItemId::ImportMetadata => AppMsg::ImportMetadata,
+            AppMenuItemId::ImportAlignment => AppMsg::ImportAlignment,
+            AppMenuItemId::BuildTree => AppMsg::BuildTree,
+            AppMenuItemId::Save => AppMsg::Save,
             AppMenuItemId::SaveAs => AppMsg::SaveAs,
             AppMenuItemId::Quit => AppMsg::WinCloseRequested,
             AppMenuItemId::CloseWindow => AppMsg::WinCloseRequested,
+            AppMenuItemId::CloseTab => AppMsg::CloseTab,
+            AppMenuItemId::Undo => AppMsg::TvMsg(TvMsg::Undo),
             AppMenuItemId::Find => AppMsg::TvMsg(TvMsg::ShowSearchBar),
             AppMenuItemId::ContextMenuIndex(idx) => {
                 AppMsg::TvMsg(TvMsg::ContextMenuChosenIdx(*idx))
             }
             AppMenuItemId::ExportPdf => AppMsg::ExportPdf,
+            AppMenuItemId::ExportTanglegramPdf => AppMsg::ExportTanglegramPdf,
             AppMenuItemId::ExportSubtree => AppMsg::ExportSubtree,
+            AppMenuItemId::ExportStatistics => AppMsg::ExportStatistics,
             #[cfg(target_os = "windows")]
             AppMenuItemId::RegisterFileTypes => AppMsg::RegisterFileTypes,
             #[cfg(target_os = "windows")]
@@ -15,6 +15,7 @@ use crate::app::menu::menu_model::Modifier;
 use super::super::app_menu_bar::app_menu_bar;
 use super::super::app_menu_item_id::AppMenuItemId;
 use super::super::menu_model::Menu;
+use super::super::menu_model::MenuItem;
 
 use menu_bar::menu_bar;
 
@@ -48,6 +49,14 @@ impl AppMenu {
 
     pub(crate) fn update(&mut self, _app_menu_item_id: AppMenuItemId) {}
 
+    pub(crate) fn set_submenu_items(
+        &mut self,
+        app_menu_item_id: AppMenuItemId,
+        items: Vec<MenuItem>,
+    ) {
+        self.menu.set_submenu_items(app_menu_item_id.into(), items);
+    }
+
     pub(crate) fn process_menu_accelerator(
         &self,
         riced_modifiers: RicedModifiers,
@@ -110,6 +110,65 @@ impl AppMenu {
         }
     }
 
+    pub(crate) fn set_submenu_items(
+        &self,
+        app_menu_item_id: AppMenuItemId,
+        items: Vec<MenuItem>,
+    ) {
+        if let Some(muda_menu) = &self.muda_menu
+            && let Some(muda_submenu) = Self::find_submenu_recursive(
+                &muda_menu.items(),
+                &app_menu_item_id,
+            )
+        {
+            muda_submenu.items().iter().for_each(|mik| match mik {
+                MIK::MenuItem(itm) => _ = muda_submenu.remove(itm),
+                MIK::Submenu(itm) => _ = muda_submenu.remove(itm),
+                MIK::Predefined(itm) => _ = muda_submenu.remove(itm),
+                MIK::Check(itm) => _ = muda_submenu.remove(itm),
+                MIK::Icon(itm) => _ = muda_submenu.remove(itm),
+            });
+            Self::append_items(&muda_submenu, &Menu::with_items(items));
+        }
+    }
+
+    fn find_submenu_recursive(
+        muda_items: &[MIK],
+        app_menu_item_id: &AppMenuItemId,
+    ) -> Option<muda::Submenu> {
+        for mik in muda_items {
+            if let MIK::Submenu(muda_submenu) = mik {
+                if mik.id() == app_menu_item_id.to_string() {
+                    return Some(muda_submenu.clone());
+                }
+                let found = Self::find_submenu_recursive(
+                    &muda_submenu.items(),
+                    app_menu_item_id,
+                );
+                if found.is_some() {
+                    return found;
+                }
+            }
+        }
+        None
+    }
+
+    fn append_items(muda_submenu: &muda::Submenu, menu: &Menu) {
+        let submenu_items: Vec<MIK> = menu
+            .items()
+            .iter()
+            .map(|menu_item| menu_item.clone().into())
+            .collect();
+
+        submenu_items.iter().for_each(|mik| match mik {
+            MIK::MenuItem(itm) => _ = muda_submenu.append(itm),
+            MIK::Submenu(itm) => _ = muda_submenu.append(itm),
+            MIK::Predefined(itm) => _ = muda_submenu.append(itm),
+            MIK::Check(itm) => _ = muda_submenu.append(itm),
+            MIK::Icon(itm) => _ = muda_submenu.append(itm),
+        });
+    }
+
     fn prepare_app_menu() -> muda::Menu {
         let muda_menu = muda::Menu::default();
         let menu_bar = app_menu_bar();
@@ -131,22 +190,8 @@ impl AppMenu {
 
 impl From<Menu> for muda::Submenu {
     fn from(menu: Menu) -> Self {
-        let submenu_items: Vec<MIK> = menu
-            .items()
-            .iter()
-            .map(|menu_item| menu_item.clone().into())
-            .collect();
-
         let muda_submenu = muda::Submenu::new("", false);
-
-        submenu_items.iter().for_each(|mik| match mik {
-            MIK::MenuItem(itm) => _ = muda_submenu.append(itm),
-            MIK::Submenu(itm) => _ = muda_submenu.append(itm),
-            MIK::Predefined(itm) => _ = muda_submenu.append(itm),
-            MIK::Check(itm) => _ = muda_submenu.append(itm),
-            MIK::Icon(itm) => _ = muda_submenu.append(itm),
-        });
-
+        AppMenu::append_items(&muda_submenu, &menu);
         muda_submenu
     }
 }
@@ -176,6 +221,7 @@ impl From<MenuItem> for muda::MenuItemKind {
                             KeyCode::KeyP => muda::accelerator::Code::KeyP,
                             KeyCode::KeyQ => muda::accelerator::Code::KeyQ,
                             KeyCode::KeyS => muda::accelerator::Code::KeyS,
+                            KeyCode::KeyZ => muda::accelerator::Code::KeyZ,
                             KeyCode::F4 => muda::accelerator::Code::F4,
                             KeyCode::Comma => muda::accelerator::Code::Comma,
                             KeyCode::Other => {
@@ -202,10 +248,10 @@ impl From<MenuItem> for muda::MenuItemKind {
                 }
             }
 
-            MenuItem::Submenu { label, enabled, id: _, menu } => {
-                let muda_submenu: muda::Submenu = menu.into();
-                muda_submenu.set_enabled(enabled);
-                muda_submenu.set_text(label);
+            MenuItem::Submenu { label, enabled, id, menu } => {
+                let muda_submenu =
+                    muda::Submenu::with_id(id.to_string(), label, enabled);
+                AppMenu::append_items(&muda_submenu, &menu);
                 muda::MenuItemKind::Submenu(muda_submenu)
             }
 
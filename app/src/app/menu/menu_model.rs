@@ -14,6 +14,7 @@ impl From<RicedKey> for KeyCode {
                 "p" => KeyCode::KeyP,
                 "q" => KeyCode::KeyQ,
                 "s" => KeyCode::KeyS,
+                "z" => KeyCode::KeyZ,
                 "," => KeyCode::Comma,
                 _ => KeyCode::Other,
             }
@@ -60,6 +61,7 @@ pub enum KeyCode {
     KeyP,
     KeyQ,
     KeyS,
+    KeyZ,
     F4,
     Comma,
     Other,
@@ -101,6 +103,7 @@ impl Display for KeyCode {
             KeyCode::KeyP => "P",
             KeyCode::KeyQ => "Q",
             KeyCode::KeyS => "S",
+            KeyCode::KeyZ => "Z",
             KeyCode::F4 => "F4",
             KeyCode::Comma => ",",
             KeyCode::Other => "Other",
@@ -218,6 +221,42 @@ impl Menu {
         });
     }
 
+    pub fn set_submenu_items(
+        &mut self,
+        menu_item_id: MenuItemId,
+        items: Vec<MenuItem>,
+    ) {
+        if Self::set_submenu_items_recursive(
+            &mut self.items, &menu_item_id, items,
+        ) {
+            self.accelerator_id_map =
+                Self::make_accelerator_id_map_recursive(&self.items);
+        }
+    }
+
+    fn set_submenu_items_recursive(
+        items: &mut [MenuItem],
+        menu_item_id: &MenuItemId,
+        new_items: Vec<MenuItem>,
+    ) -> bool {
+        for itm in items {
+            if let MenuItem::Submenu { id, menu, .. } = itm {
+                if id == menu_item_id {
+                    *menu = Menu::with_items(new_items);
+                    return true;
+                }
+                if Self::set_submenu_items_recursive(
+                    &mut menu.items,
+                    menu_item_id,
+                    new_items.clone(),
+                ) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
     pub fn is_enabled(&self, menu_item_id: MenuItemId) -> bool {
         Self::is_enabled_recursive(&self.items, menu_item_id)
     }
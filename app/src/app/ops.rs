@@ -1,8 +1,23 @@
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime};
 
+use bzip2::read::MultiBzDecoder;
+use dendros::parse_trees;
+use flate2::read::MultiGzDecoder;
+use riced::{Sipper, StreamExt, UnboundedSender, sipper, unbounded};
 use thiserror::Error;
 
-use super::AppMsg;
+use super::consts::{TREE_LOAD_BATCH_INTERVAL, TREE_LOAD_CHUNK_SIZE};
+use super::{AppError, AppMsg};
+use treeview::{
+    DataExport, DistanceMatrix, DistanceModel, PreparedTree,
+    TreeBuildingMethod, TvMsg, parse_jplace, parse_xml_trees,
+};
+use xz2::read::XzDecoder;
 
 #[derive(Debug, Error, Clone)]
 pub enum FileReadError {
@@ -23,31 +38,127 @@ pub fn choose_file_to_open_sync() -> AppMsg {
     AppMsg::PathToOpen(chosen.map(|path_buf| path_buf.as_path().into()))
 }
 
-pub async fn choose_file_to_save(subtree: bool) -> AppMsg {
+pub async fn choose_metadata_file_to_open(doc_id: usize) -> AppMsg {
+    let chosen = rfd::AsyncFileDialog::new()
+        .add_filter("csv", &["csv", "tsv", "txt"])
+        .pick_file()
+        .await;
+    AppMsg::MetadataPathToOpen(
+        doc_id,
+        chosen.map(|file_handle| file_handle.path().into()),
+    )
+}
+
+pub async fn choose_alignment_file_to_open(doc_id: usize) -> AppMsg {
+    let chosen = rfd::AsyncFileDialog::new()
+        .add_filter(
+            "alignment",
+            &[
+                "fasta", "fas", "fa", "fna", "faa", "phy", "phylip", "nex",
+                "nexus",
+            ],
+        )
+        .pick_file()
+        .await;
+    AppMsg::AlignmentPathToOpen(
+        doc_id,
+        chosen.map(|file_handle| file_handle.path().into()),
+    )
+}
+
+pub async fn choose_distance_file_to_open() -> AppMsg {
+    let chosen = rfd::AsyncFileDialog::new()
+        .add_filter(
+            "distances or alignment",
+            &[
+                "phy", "phylip", "dist", "txt", "fasta", "fas", "fa", "fna",
+                "faa", "nex", "nexus",
+            ],
+        )
+        .pick_file()
+        .await;
+    AppMsg::BuildTreePathToOpen(
+        chosen.map(|file_handle| file_handle.path().into()),
+    )
+}
+
+pub async fn choose_file_to_save(doc_id: usize, subtree: bool) -> AppMsg {
     let chosen = rfd::AsyncFileDialog::new()
         .add_filter("newick", &["newick", "tre"])
+        .add_filter("phyloxml", &["phyloxml", "xml"])
+        .add_filter("nexml", &["nexml"])
         .save_file()
         .await;
     AppMsg::PathToSave {
+        doc_id,
         path: chosen.map(|file_handle| file_handle.path().into()),
         subtree,
     }
 }
 
-pub async fn choose_file_to_pdf_export() -> AppMsg {
+pub async fn choose_file_to_pdf_export(doc_id: usize) -> AppMsg {
     let chosen = rfd::AsyncFileDialog::new()
         .add_filter("pdf", &["pdf"])
         .save_file()
         .await;
     AppMsg::PathToSave {
+        doc_id,
         path: chosen.map(|file_handle| file_handle.path().into()),
         subtree: false,
     }
 }
 
+pub async fn choose_file_to_tanglegram_pdf_export(doc_id: usize) -> AppMsg {
+    let chosen = rfd::AsyncFileDialog::new()
+        .add_filter("pdf", &["pdf"])
+        .save_file()
+        .await;
+    match chosen {
+        Some(file_handle) => AppMsg::DocTvMsg(
+            doc_id,
+            TvMsg::ExportTanglegramPdf(file_handle.path().into()),
+        ),
+        None => AppMsg::Other(None),
+    }
+}
+
+pub async fn choose_file_to_stats_export(doc_id: usize) -> AppMsg {
+    let chosen = rfd::AsyncFileDialog::new()
+        .add_filter("tsv", &["tsv", "txt"])
+        .save_file()
+        .await;
+    match chosen {
+        Some(file_handle) => AppMsg::DocTvMsg(
+            doc_id,
+            TvMsg::ExportTreeStats(file_handle.path().into()),
+        ),
+        None => AppMsg::Other(None),
+    }
+}
+
+pub async fn choose_file_to_data_export(
+    doc_id: usize,
+    data: DataExport,
+) -> AppMsg {
+    let chosen = rfd::AsyncFileDialog::new()
+        .add_filter("csv", &["csv"])
+        .add_filter("tsv", &["tsv", "txt"])
+        .save_file()
+        .await;
+    match chosen {
+        Some(file_handle) => AppMsg::DocTvMsg(
+            doc_id,
+            TvMsg::ExportData(data, file_handle.path().into()),
+        ),
+        None => AppMsg::Other(None),
+    }
+}
+
 pub fn read_text_file(path_buf: PathBuf) -> Result<String, FileReadError> {
-    let result_io = std::fs::read(&path_buf);
-    if let Ok(data) = result_io {
+    let mut data: Vec<u8> = Vec::new();
+    let result_io = open_input(&path_buf, Arc::default())
+        .and_then(|mut reader| reader.read_to_end(&mut data));
+    if result_io.is_ok() {
         let result_parse = String::from_utf8(data);
         if let Ok(s) = result_parse {
             Ok(s)
@@ -59,10 +170,558 @@ pub fn read_text_file(path_buf: PathBuf) -> Result<String, FileReadError> {
     }
 }
 
-pub fn write_text_file(path_buf: &PathBuf, s: &str) {
-    std::fs::write(path_buf, s)
-        .map_err(|e| {
-            eprintln!("IO error: {e:?}");
+pub fn write_text_file(path_buf: &Path, s: &str) -> Result<(), AppError> {
+    std::fs::write(path_buf, s).map_err(|e| AppError::FileWriteError {
+        message: e.to_string(),
+        file_path: path_buf.to_path_buf(),
+    })
+}
+
+// --- Input -------------------------------------------------------------------
+
+/// `-` stands for the standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// When the file was last modified, if that can be read.
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+pub fn input_name(path: &Path) -> String {
+    match is_stdin(path) {
+        true => String::from("stdin"),
+        false => path.file_name().unwrap_or_default().to_string_lossy().into(),
+    }
+}
+
+/// Opens a file (or the standard input) for reading. Input compressed with
+/// gzip, bzip2 or xz is recognized by its first bytes and decompressed on the
+/// fly. `bytes_read` counts the bytes read before decompression.
+fn open_input(
+    path: &Path,
+    bytes_read: Arc<AtomicU64>,
+) -> std::io::Result<Box<dyn BufRead + Send>> {
+    let inner: Box<dyn Read + Send> = match is_stdin(path) {
+        true => Box::new(std::io::stdin()),
+        false => Box::new(File::open(path)?),
+    };
+    let mut reader = BufReader::with_capacity(
+        TREE_LOAD_CHUNK_SIZE,
+        CountingReader { inner, bytes_read },
+    );
+    let magic = reader.fill_buf()?;
+    Ok(if magic.starts_with(&[0x1f, 0x8b]) {
+        Box::new(BufReader::with_capacity(
+            TREE_LOAD_CHUNK_SIZE,
+            MultiGzDecoder::new(reader),
+        ))
+    } else if magic.starts_with(b"BZh") {
+        Box::new(BufReader::with_capacity(
+            TREE_LOAD_CHUNK_SIZE,
+            MultiBzDecoder::new(reader),
+        ))
+    } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        Box::new(BufReader::with_capacity(
+            TREE_LOAD_CHUNK_SIZE,
+            XzDecoder::new_multi_decoder(reader),
+        ))
+    } else {
+        Box::new(reader)
+    })
+}
+
+struct CountingReader {
+    inner: Box<dyn Read + Send>,
+    bytes_read: Arc<AtomicU64>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        _ = self.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+/// Splits text into statements ending with a `;` that is not quoted or inside
+/// a `[...]` comment. A NEWICK tree and every NEXUS command is one statement.
+struct StatementReader {
+    reader: Box<dyn BufRead + Send>,
+    quote: Option<u8>,
+    comment_depth: usize,
+}
+
+impl StatementReader {
+    fn new(reader: Box<dyn BufRead + Send>) -> Self {
+        Self { reader, quote: None, comment_depth: 0 }
+    }
+
+    /// `on_read` is called every time more input is about to be read. Reading
+    /// stops, as if at the end of the input, when it returns `false`.
+    fn next_statement(
+        &mut self,
+        mut on_read: impl FnMut() -> bool,
+    ) -> std::io::Result<Option<Vec<u8>>> {
+        let mut statement: Vec<u8> = Vec::new();
+        loop {
+            if !on_read() {
+                return Ok(None);
+            }
+            let available = self.reader.fill_buf()?;
+            if available.is_empty() {
+                return Ok(
+                    match statement.iter().all(u8::is_ascii_whitespace) {
+                        true => None,
+                        false => Some(statement),
+                    },
+                );
+            }
+            let mut end: Option<usize> = None;
+            for (i, &b) in available.iter().enumerate() {
+                match self.quote {
+                    Some(q) => {
+                        if b == q {
+                            self.quote = None;
+                        }
+                    }
+                    None => match b {
+                        b'[' => self.comment_depth += 1,
+                        b']' => {
+                            self.comment_depth =
+                                self.comment_depth.saturating_sub(1);
+                        }
+                        b'\'' | b'"' if self.comment_depth == 0 => {
+                            self.quote = Some(b);
+                        }
+                        b';' if self.comment_depth == 0 => {
+                            end = Some(i);
+                            break;
+                        }
+                        _ => {}
+                    },
+                }
+            }
+            let n = end.map_or(available.len(), |i| i + 1);
+            statement.extend_from_slice(&available[..n]);
+            self.reader.consume(n);
+            if end.is_some() {
+                return Ok(Some(statement));
+            }
+        }
+    }
+}
+
+// --- Command Line ------------------------------------------------------------
+
+/// `treehouse [--burn-in N] [--thin K] [--new-instance] [FILE... | -]`
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+#[derive(Debug, Default)]
+pub struct CmdLineArgs {
+    pub paths: Vec<PathBuf>,
+    pub burn_in: Option<usize>,
+    pub thinning: Option<usize>,
+    pub new_instance: bool,
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+impl CmdLineArgs {
+    pub fn parse(args: impl Iterator<Item = String>) -> Self {
+        let mut rv = Self::default();
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--burn-in" => {
+                    rv.burn_in = args.next().and_then(|n| n.parse().ok());
+                }
+                "--thin" => {
+                    rv.thinning = args.next().and_then(|n| n.parse().ok());
+                }
+                "--new-instance" => {
+                    rv.new_instance = true;
+                }
+                _ => {
+                    rv.paths.push(PathBuf::from(arg));
+                }
+            }
+        }
+        rv
+    }
+}
+
+// --- Background Tree Loading -------------------------------------------------
+
+/// Trees to skip from the start of a file (`burn_in`) and the interval at which
+/// the remaining trees are kept (`thinning`).
+#[derive(Debug, Clone, Copy)]
+pub struct TreeLoadOptions {
+    pub burn_in: usize,
+    pub thinning: usize,
+}
+
+impl TreeLoadOptions {
+    fn keep(&self, idx: usize) -> bool {
+        idx >= self.burn_in && (idx - self.burn_in) % self.thinning.max(1) == 0
+    }
+
+    pub fn keeps_all(&self) -> bool {
+        self.burn_in == 0 && self.thinning <= 1
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TreeLoadEvent {
+    Progress(TreeLoadProgress),
+    Trees(Vec<PreparedTree>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TreeLoadProgress {
+    bytes_read: u64,
+    bytes_total: Option<u64>,
+    trees_read: usize,
+    trees_kept: usize,
+}
+
+impl TreeLoadProgress {
+    pub fn message(&self) -> String {
+        let mb_read = self.bytes_read as f64 / 1e6;
+        let mb = match self.bytes_total {
+            Some(bytes_total) => {
+                format!("{mb_read:.1} of {:.1} MB", bytes_total as f64 / 1e6)
+            }
+            None => format!("{mb_read:.1} MB"),
+        };
+        match self.trees_read == self.trees_kept {
+            true => format!("{mb}, {} trees", self.trees_kept),
+            false => format!(
+                "{mb}, {} of {} trees kept",
+                self.trees_kept, self.trees_read
+            ),
+        }
+    }
+
+    pub fn fraction(&self) -> Option<f32> {
+        match self.bytes_total {
+            Some(bytes_total) if bytes_total > 0 => {
+                Some(self.bytes_read as f32 / bytes_total as f32)
+            }
+            _ => None,
+        }
+    }
+}
+
+enum TreeLoadStep {
+    Event(TreeLoadEvent),
+    Done(Result<usize, AppError>),
+}
+
+/// Reads, parses and prepares the trees in a file on a separate thread, one
+/// tree at a time. The first tree is sent as soon as it is ready, the rest in
+/// batches. Dropping the returned sipper (e.g. by aborting its task) stops the
+/// thread before it reads any further.
+pub fn load_trees(
+    path_buf: PathBuf,
+    options: TreeLoadOptions,
+) -> impl Sipper<Result<usize, AppError>, TreeLoadEvent> {
+    sipper(async move |mut output| {
+        let (sender, mut receiver) = unbounded();
+        let file_path = path_buf.clone();
+        _ = std::thread::spawn(move || {
+            let result = load_trees_blocking(path_buf, options, &sender);
+            _ = sender.unbounded_send(TreeLoadStep::Done(result));
+        });
+        while let Some(step) = receiver.next().await {
+            match step {
+                TreeLoadStep::Event(event) => output.send(event).await,
+                TreeLoadStep::Done(result) => return result,
+            }
+        }
+        Err(AppError::FileReadError {
+            file_read_error: FileReadError::InputError { file_path },
+        })
+    })
+}
+
+fn load_trees_blocking(
+    path_buf: PathBuf,
+    options: TreeLoadOptions,
+    sender: &UnboundedSender<TreeLoadStep>,
+) -> Result<usize, AppError> {
+    let send = |event: TreeLoadEvent| {
+        sender.unbounded_send(TreeLoadStep::Event(event)).is_ok()
+    };
+    let input_error = || AppError::FileReadError {
+        file_read_error: FileReadError::InputError {
+            file_path: path_buf.clone(),
+        },
+    };
+    let text_error = || AppError::FileReadError {
+        file_read_error: FileReadError::CouldNotParseDataAsText {
+            file_path: path_buf.clone(),
+        },
+    };
+    let parse_error = |tree_parse_error| AppError::TreeParseError {
+        tree_parse_error,
+        file_path: Some(path_buf.clone()),
+    };
+
+    let bytes_read: Arc<AtomicU64> = Arc::default();
+    let bytes_total = match is_stdin(&path_buf) {
+        true => None,
+        false => std::fs::metadata(&path_buf).map(|m| m.len()).ok(),
+    };
+    let progress = |trees_read, trees_kept| {
+        TreeLoadEvent::Progress(TreeLoadProgress {
+            bytes_read: bytes_read.load(Ordering::Relaxed),
+            bytes_total,
+            trees_read,
+            trees_kept,
+        })
+    };
+    let mut reader =
+        open_input(&path_buf, bytes_read.clone()).map_err(|_| input_error())?;
+
+    let mut trees_read: usize = 0;
+    let mut batches = TreeBatches::default();
+    let mut progress_sent_at = Instant::now();
+
+    // PhyloXML, NeXML and jplace documents are read whole.
+    let first_byte = reader.fill_buf().ok().and_then(|data| {
+        let data = data.strip_prefix("\u{feff}".as_bytes()).unwrap_or(data);
+        data.trim_ascii_start().first().copied()
+    });
+    if let Some(first_byte @ (b'<' | b'{')) = first_byte {
+        let mut text = String::new();
+        _ = reader.read_to_string(&mut text).map_err(|e| {
+            match e.kind() == std::io::ErrorKind::InvalidData {
+                true => text_error(),
+                false => input_error(),
+            }
+        })?;
+        let document_error = |message| AppError::DocumentParseError {
+            message,
+            file_path: Some(path_buf.clone()),
+        };
+        // Trees are prepared one at a time, so that a cancelled load stops.
+        let trees: Box<dyn Iterator<Item = PreparedTree>> = match first_byte {
+            b'<' => Box::new(
+                parse_xml_trees(&text)
+                    .map_err(document_error)?
+                    .into_iter()
+                    .map(PreparedTree::new),
+            ),
+            _ => Box::new(std::iter::once(
+                parse_jplace(&text).map_err(document_error)?,
+            )),
+        };
+        for tre in trees {
+            if sender.is_closed() {
+                return Ok(batches.kept);
+            }
+            let idx = trees_read;
+            trees_read += 1;
+            if options.keep(idx) {
+                batches.push(tre);
+            }
+            if let Some(batch) = batches.take_due()
+                && (!send(TreeLoadEvent::Trees(batch))
+                    || !send(progress(trees_read, batches.kept)))
+            {
+                return Ok(batches.kept);
+            }
+        }
+        return Ok(batches.finish(send));
+    }
+
+    // NEXUS tree commands are parsed one at a time inside a minimal TREES
+    // block, which holds only the translate table from before the first tree.
+    let mut statements = StatementReader::new(reader);
+    let mut nexus_header: Option<String> = None;
+
+    while let Some(statement) = statements
+        .next_statement(|| {
+            if progress_sent_at.elapsed() >= TREE_LOAD_BATCH_INTERVAL {
+                progress_sent_at = Instant::now();
+                return send(progress(trees_read, batches.kept));
+            }
+            !sender.is_closed()
+        })
+        .map_err(|_| input_error())?
+    {
+        let statement =
+            String::from_utf8(statement).map_err(|_| text_error())?;
+        let trimmed = statement.trim_start();
+        if trimmed == ";" {
+            continue;
+        }
+
+        if trees_read == 0
+            && nexus_header.is_none()
+            && trimmed
+                .get(..6)
+                .is_some_and(|s| s.eq_ignore_ascii_case("#NEXUS"))
+        {
+            nexus_header = Some(String::from("#NEXUS\nbegin trees;\n"));
+        }
+
+        let tree_text = match &mut nexus_header {
+            Some(header) => {
+                let command = nexus_command(&statement);
+                let is_tree = command.eq_ignore_ascii_case("tree")
+                    || command.eq_ignore_ascii_case("utree");
+                match is_tree {
+                    true => format!("{header}{statement}\nend;\n"),
+                    false => {
+                        if trees_read == 0
+                            && command.eq_ignore_ascii_case("translate")
+                        {
+                            header.push_str(&statement);
+                            header.push('\n');
+                        }
+                        continue;
+                    }
+                }
+            }
+            None => statement,
+        };
+
+        let idx = trees_read;
+        trees_read += 1;
+        if !options.keep(idx) {
+            continue;
+        }
+
+        for tre in parse_trees(tree_text).map_err(parse_error)? {
+            batches.push(PreparedTree::new(tre));
+        }
+
+        if let Some(batch) = batches.take_due() {
+            if !send(TreeLoadEvent::Trees(batch))
+                || !send(progress(trees_read, batches.kept))
+            {
+                return Ok(batches.kept);
+            }
+            progress_sent_at = Instant::now();
+        }
+    }
+
+    Ok(batches.finish(send))
+}
+
+/// The command a NEXUS statement starts with, after any comments.
+fn nexus_command(statement: &str) -> &str {
+    let mut s = statement.trim_start();
+    while let Some(rest) = s.strip_prefix('[') {
+        s = rest.split_once(']').map_or("", |(_, rest)| rest).trim_start();
+    }
+    s.split(|c: char| c.is_whitespace() || c == '=' || c == ';')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Trees prepared in the background, handed over to the view in batches.
+#[derive(Default)]
+struct TreeBatches {
+    batch: Vec<PreparedTree>,
+    sent_at: Option<Instant>,
+    kept: usize,
+}
+
+impl TreeBatches {
+    fn push(&mut self, tre: PreparedTree) {
+        self.batch.push(tre);
+        self.kept += 1;
+    }
+
+    /// The trees to hand over now, if any. The first tree is handed over on
+    /// its own, so that it can be shown while the rest of the file is read.
+    fn take_due(&mut self) -> Option<Vec<PreparedTree>> {
+        let is_due = match self.sent_at {
+            Some(sent_at) => sent_at.elapsed() >= TREE_LOAD_BATCH_INTERVAL,
+            None => true,
+        };
+        match is_due && !self.batch.is_empty() {
+            true => {
+                self.sent_at = Some(Instant::now());
+                Some(std::mem::take(&mut self.batch))
+            }
+            false => None,
+        }
+    }
+
+    /// Hands over the remaining trees (an empty batch for a file without
+    /// trees) and returns the number of trees kept.
+    fn finish(self, send: impl Fn(TreeLoadEvent) -> bool) -> usize {
+        if !self.batch.is_empty() || self.kept == 0 {
+            _ = send(TreeLoadEvent::Trees(self.batch));
+        }
+        self.kept
+    }
+}
+
+// --- Background Tree Building ------------------------------------------------
+
+enum TreeBuildStep {
+    Progress(f32),
+    Done(Result<PreparedTree, AppError>),
+}
+
+/// Builds a tree from the distance matrix or alignment in a file on a
+/// separate thread, reporting the fraction of the work done. Dropping the
+/// returned sipper stops the thread the next time it reports progress.
+pub fn build_tree(
+    path_buf: PathBuf,
+    method: TreeBuildingMethod,
+    model: DistanceModel,
+) -> impl Sipper<Result<PreparedTree, AppError>, f32> {
+    sipper(async move |mut output| {
+        let (sender, mut receiver) = unbounded();
+        let file_path = path_buf.clone();
+        _ = std::thread::spawn(move || {
+            let mut progress_sent_at = Instant::now();
+            let result =
+                build_tree_blocking(path_buf, method, model, |fraction| {
+                    if progress_sent_at.elapsed() < TREE_LOAD_BATCH_INTERVAL {
+                        return true;
+                    }
+                    progress_sent_at = Instant::now();
+                    sender
+                        .unbounded_send(TreeBuildStep::Progress(fraction))
+                        .is_ok()
+                });
+            _ = sender.unbounded_send(TreeBuildStep::Done(result));
+        });
+        while let Some(step) = receiver.next().await {
+            match step {
+                TreeBuildStep::Progress(fraction) => {
+                    output.send(fraction).await
+                }
+                TreeBuildStep::Done(result) => return result,
+            }
+        }
+        Err(AppError::FileReadError {
+            file_read_error: FileReadError::InputError { file_path },
         })
-        .unwrap();
+    })
+}
+
+fn build_tree_blocking(
+    path_buf: PathBuf,
+    method: TreeBuildingMethod,
+    model: DistanceModel,
+    on_progress: impl FnMut(f32) -> bool,
+) -> Result<PreparedTree, AppError> {
+    let text = read_text_file(path_buf.clone()).map_err(|file_read_error| {
+        AppError::FileReadError { file_read_error }
+    })?;
+    let document_error = |message| AppError::DocumentParseError {
+        message,
+        file_path: Some(path_buf.clone()),
+    };
+    let matrix = DistanceMatrix::parse(&text, model).map_err(document_error)?;
+    let tre = treeview::build_tree(&matrix, method, on_progress)
+        .map_err(document_error)?;
+    Ok(PreparedTree::new(tre))
 }
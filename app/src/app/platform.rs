@@ -10,3 +10,11 @@ pub use windows::{
     register_file_associations, setup_file_handling,
     unregister_file_associations,
 };
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::{
+    add_to_recently_used, forward_to_running_instance, instance_events,
+    remove_instance_socket,
+};
@@ -0,0 +1,7 @@
+mod recently_used;
+mod single_instance;
+
+pub use recently_used::add_to_recently_used;
+pub use single_instance::{
+    forward_to_running_instance, instance_events, remove_instance_socket,
+};
@@ -0,0 +1,133 @@
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::super::super::consts::APP_NAME;
+
+const XBEL_EMPTY: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+    "<xbel version=\"1.0\"\n",
+    "      xmlns:bookmark=\"http://www.freedesktop.org/standards/desktop-bookmarks\"\n",
+    "      xmlns:mime=\"http://www.freedesktop.org/standards/shared-mime-info\"\n",
+    ">\n",
+    "</xbel>\n"
+);
+
+/// Adds a file to the freedesktop.org recently used store
+/// (`recently-used.xbel`) that file choosers and desktop shells list recent
+/// files from. A previous entry for the file is replaced, which moves it to
+/// the top.
+pub fn add_to_recently_used(path: &Path) {
+    let (Ok(path), Some(store)) = (path.canonicalize(), recently_used_store())
+    else {
+        return;
+    };
+
+    // The store is shared with other applications, so one that cannot be
+    // read, or is not a complete store, is left alone rather than replaced.
+    let mut xbel = match std::fs::read_to_string(&store) {
+        Ok(xbel) if xbel.contains("</xbel>") => xbel,
+        Ok(xbel) if xbel.trim().is_empty() => XBEL_EMPTY.to_string(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            XBEL_EMPTY.to_string()
+        }
+        Ok(_) | Err(_) => return,
+    };
+
+    let href = file_uri(&path);
+    if let Some(start) = xbel.find(&format!("<bookmark href=\"{href}\""))
+        && let Some(len) = xbel[start..].find("</bookmark>")
+    {
+        let mut end = start + len + "</bookmark>".len();
+        let start = xbel[..start].rfind('\n').map_or(start, |i| i + 1);
+        if xbel[end..].starts_with('\n') {
+            end += 1;
+        }
+        xbel.replace_range(start..end, "");
+    }
+
+    let now = timestamp();
+    let exec = env!("CARGO_BIN_NAME");
+    let bookmark = format!(
+        concat!(
+            "  <bookmark href=\"{href}\" added=\"{now}\" modified=\"{now}\" visited=\"{now}\">\n",
+            "    <info>\n",
+            "      <metadata owner=\"http://freedesktop.org\">\n",
+            "        <mime:mime-type type=\"text/plain\"/>\n",
+            "        <bookmark:applications>\n",
+            "          <bookmark:application name=\"{name}\" exec=\"&apos;{exec} %u&apos;\" modified=\"{now}\" count=\"1\"/>\n",
+            "        </bookmark:applications>\n",
+            "      </metadata>\n",
+            "    </info>\n",
+            "  </bookmark>\n"
+        ),
+        name = APP_NAME,
+    );
+    if let Some(idx) = xbel.rfind("</xbel>") {
+        xbel.insert_str(idx, &bookmark);
+    }
+
+    // Written next to the store and renamed, so that readers never see a
+    // partially written file.
+    if let Some(dir) = store.parent() {
+        _ = std::fs::create_dir_all(dir);
+    }
+    let tmp = store.with_extension("xbel.tmp");
+    if std::fs::write(&tmp, xbel).is_ok() {
+        _ = std::fs::rename(tmp, store);
+    }
+}
+
+fn recently_used_store() -> Option<PathBuf> {
+    let data_home = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(data_home.join("recently-used.xbel"))
+}
+
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for &b in path.as_os_str().as_bytes() {
+        match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'/' => uri.push(char::from(b)),
+            _ => uri.push_str(&format!("%{b:02X}")),
+        }
+    }
+    uri
+}
+
+/// The current time as an ISO 8601 UTC timestamp.
+fn timestamp() -> String {
+    let secs =
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from the number of days since 1970-01-01.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = match mp < 10 {
+        true => mp + 3,
+        false => mp - 9,
+    };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
@@ -0,0 +1,124 @@
+use crate::app::AppMsg;
+use riced::{
+    Never, Sipper, StreamExt, Subscription, UnboundedSender, sipper, unbounded,
+};
+use std::ffi::OsStr;
+use std::fs::DirBuilder;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use super::super::super::consts::APP_ID;
+
+/// The socket this instance listens on, once it does.
+static LISTENING_ON: OnceLock<PathBuf> = OnceLock::new();
+
+/// Sends `paths` to an already running instance, which opens them and raises
+/// its window. Returns `false` when no instance is listening, in which case the
+/// caller should start normally.
+pub fn forward_to_running_instance(paths: &[PathBuf]) -> bool {
+    let Some(socket) = instance_socket() else {
+        return false;
+    };
+    let Ok(mut stream) = UnixStream::connect(socket) else {
+        return false;
+    };
+
+    let mut msg: Vec<u8> = Vec::new();
+    for path in paths {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+        msg.extend_from_slice(path.as_os_str().as_bytes());
+        msg.push(b'\0');
+    }
+    stream.write_all(&msg).is_ok()
+}
+
+/// Removes the socket this instance listens on, so that the next launch starts
+/// a new instance instead of trying to forward its files to this one.
+pub fn remove_instance_socket() {
+    if let Some(socket) = LISTENING_ON.get() {
+        _ = std::fs::remove_file(socket);
+    }
+}
+
+pub fn instance_events() -> Subscription<AppMsg> {
+    Subscription::run(instance_events_sipper)
+}
+
+fn instance_events_sipper() -> impl Sipper<Never, AppMsg> {
+    sipper(async |mut output| {
+        let (sender, mut receiver) = unbounded();
+        _ = std::thread::spawn(move || listen(&sender));
+        while let Some(paths) = receiver.next().await {
+            output.send(AppMsg::InstanceActivated(paths)).await;
+        }
+        std::future::pending().await
+    })
+}
+
+/// Accepts connections from [`forward_to_running_instance`] until the app
+/// exits. Returns early when another instance already owns the socket or it
+/// cannot be created.
+fn listen(sender: &UnboundedSender<Vec<PathBuf>>) {
+    let Some(socket) = instance_socket() else {
+        return;
+    };
+    // Binding fails while the socket exists, so it is replaced only when no
+    // instance accepts connections on it, i.e. it was left behind by an
+    // instance that did not exit cleanly.
+    let listener = UnixListener::bind(&socket).or_else(|e| match e.kind() {
+        ErrorKind::AddrInUse if UnixStream::connect(&socket).is_err() => {
+            _ = std::fs::remove_file(&socket);
+            UnixListener::bind(&socket)
+        }
+        _ => Err(e),
+    });
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == ErrorKind::AddrInUse => return,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {e}", socket.display());
+            return;
+        }
+    };
+    _ = LISTENING_ON.set(socket);
+
+    for stream in listener.incoming().map_while(Result::ok) {
+        let paths: Vec<PathBuf> = BufReader::new(stream)
+            .split(b'\0')
+            .map_while(Result::ok)
+            .filter(|bytes| !bytes.is_empty())
+            .map(|bytes| PathBuf::from(OsStr::from_bytes(&bytes)))
+            .collect();
+        if sender.unbounded_send(paths).is_err() {
+            return;
+        }
+    }
+}
+
+/// The socket is kept in `$XDG_RUNTIME_DIR`, which only the user can access.
+/// Without it, a directory in the temporary directory is used that must be
+/// owned by the user and closed to everyone else. `None` when there is no
+/// such place, which turns single-instance mode off.
+fn instance_socket() -> Option<PathBuf> {
+    if let Some(dir) =
+        std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty())
+    {
+        return Some(PathBuf::from(dir).join(format!("{APP_ID}.sock")));
+    }
+
+    let uid = std::fs::metadata("/proc/self").ok()?.uid();
+    let dir = std::env::temp_dir().join(format!("{APP_ID}-{uid}"));
+    _ = DirBuilder::new().mode(0o700).create(&dir);
+    let metadata = std::fs::symlink_metadata(&dir).ok()?;
+    match metadata.is_dir()
+        && metadata.uid() == uid
+        && metadata.mode() & 0o077 == 0
+    {
+        true => Some(dir.join("instance.sock")),
+        false => None,
+    }
+}
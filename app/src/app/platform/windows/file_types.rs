@@ -55,6 +55,23 @@ pub fn register_file_associations() -> Result<(), Box<dyn std::error::Error>> {
         &format!("{},2", exe_path),
     )?;
 
+    // Register .phyloxml and .nexml extensions
+    register_file_type(
+        "phyloxml",
+        "TreeHouse.PhyloXmlFile",
+        "PhyloXML Phylogenetic Tree",
+        &exe_path,
+        &format!("{},0", exe_path),
+    )?;
+
+    register_file_type(
+        "nexml",
+        "TreeHouse.NeXmlFile",
+        "NeXML Phylogenetic Tree",
+        &exe_path,
+        &format!("{},0", exe_path),
+    )?;
+
     notify_shell_change();
 
     Ok(())
@@ -98,7 +115,8 @@ pub fn unregister_file_associations() -> Result<(), Box<dyn std::error::Error>>
     let classes_key = hkcu.open_subkey("Software\\Classes")?;
 
     // Remove file extensions
-    let extensions = ["newick", "tre", "nexus", "nex", "tree", "trees"];
+    let extensions =
+        ["newick", "tre", "nexus", "nex", "tree", "trees", "phyloxml", "nexml"];
     for ext in &extensions {
         let _ = classes_key.delete_subkey_all(format!(".{}", ext));
     }
@@ -106,6 +124,8 @@ pub fn unregister_file_associations() -> Result<(), Box<dyn std::error::Error>>
     // Remove ProgIDs
     let _ = classes_key.delete_subkey_all("TreeHouse.NewickFile");
     let _ = classes_key.delete_subkey_all("TreeHouse.NexusFile");
+    let _ = classes_key.delete_subkey_all("TreeHouse.PhyloXmlFile");
+    let _ = classes_key.delete_subkey_all("TreeHouse.NeXmlFile");
 
     // Notify the shell of the changes
     notify_shell_change();
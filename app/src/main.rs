@@ -23,6 +23,10 @@ use app::App;
 
 fn main() -> riced::IcedResult {
     // tracing_subscriber::fmt::init();
+    #[cfg(target_os = "linux")]
+    if App::forward_to_running_instance() {
+        return Ok(());
+    }
     riced::daemon(App::boot, App::update, App::view)
         .title(App::title)
         .subscription(App::subscription)
@@ -74,6 +74,26 @@ pub fn error_container<'a, Msg: Clone + 'a>(
     c.into()
 }
 
+/// A status message with an optional progress bar (`fraction` is in `0..=1`)
+/// and a button to cancel the work in progress.
+pub fn progress_row<'a, Msg: Clone + 'a>(
+    message: impl Into<String>,
+    fraction: Option<f32>,
+    on_cancel: Msg,
+) -> Row<'a, Msg> {
+    let mut r = iced_row![txt(message).width(Length::Fill)];
+    if let Some(fraction) = fraction {
+        r = r.push(
+            progress_bar(ZERO..=ONE, fraction.clamp(ZERO, ONE))
+                .length(Length::Fill)
+                .girth(SLIDER_H / THREE)
+                .style(sty_progress_bar),
+        );
+    }
+    r = r.push(btn_txt("Cancel", Some(on_cancel)).width(BTN_H1 * 3e0));
+    r.spacing(PADDING).align_y(Vertical::Center)
+}
+
 pub fn settings_container<'a, Msg: Clone + 'a>(
     w: impl Into<Length>,
     h: impl Into<Length>,
@@ -101,6 +121,49 @@ pub fn settings_container<'a, Msg: Clone + 'a>(
     c.into()
 }
 
+/// A floating box with a title, the given content and buttons to confirm
+/// (labelled `confirm_label`) or cancel. `alt` adds a button for a second
+/// way to go ahead, e.g. "Don't Save" next to "Save".
+#[allow(clippy::too_many_arguments)]
+pub fn dialog_container<'a, Msg: Clone + 'a>(
+    title: impl Into<String>,
+    content: impl Into<Element<'a, Msg>>,
+    confirm_label: &'a str,
+    on_confirm: Option<Msg>,
+    alt: Option<(&'a str, Msg)>,
+    on_cancel: Msg,
+    padding_inner: impl Into<Padding>,
+    padding_outer: impl Into<Padding>,
+) -> Element<'a, Msg> {
+    let padding_inner: Padding = padding_inner.into();
+    let mut buttons = iced_row![
+        space_h(Length::Fill, Length::Shrink),
+        btn_txt("Cancel", Some(on_cancel)).width(BTN_H1 * 3e0),
+    ]
+    .spacing(PADDING);
+    if let Some((alt_label, on_alt)) = alt {
+        buttons =
+            buttons.push(btn_txt(alt_label, Some(on_alt)).width(BTN_H1 * 3e0));
+    }
+    buttons =
+        buttons.push(btn_txt(confirm_label, on_confirm).width(BTN_H1 * 3e0));
+    let mut c = center(iced_col![
+        iced_col![txt(title.into()), content.into()]
+            .spacing(padding_inner.top)
+            .padding(padding_inner)
+            .width(BTN_H1 * 16e0),
+        space_v(Length::Shrink, padding_inner.bottom),
+        buttons,
+    ]);
+    c = c.width(Length::Shrink);
+    c = c.height(Length::Shrink);
+    c = c.padding(padding_inner);
+    c = c.style(sty_cont_floating_bg);
+    c = center(c);
+    c = c.padding(padding_outer);
+    c.into()
+}
+
 fn btn_common<Msg>(btn: Button<'_, Msg>, msg: Option<Msg>) -> Button<'_, Msg> {
     let mut btn = btn;
     btn = btn.on_press_maybe(msg);
@@ -152,6 +215,28 @@ pub fn btn_txt<Msg>(lab: &'_ str, msg: Option<Msg>) -> Button<'_, Msg> {
     btn
 }
 
+/// A document tab: a button showing `label`, which stands out when
+/// `is_active`, followed by a button that closes the tab.
+pub fn tab<'a, Msg: Clone + 'a>(
+    label: impl Into<String>,
+    is_active: bool,
+    on_select: Msg,
+    on_close: Msg,
+) -> Row<'a, Msg> {
+    let style: fn(&Theme, ButtonStatus) -> ButtonStyle = match is_active {
+        true => sty_btn_stateful_on,
+        false => sty_btn_stateful_off,
+    };
+    let mut lab = Text::new(label.into());
+    lab = lab.align_y(Vertical::Center);
+    let mut btn = Button::new(lab);
+    btn = btn.on_press(on_select);
+    btn = btn.clip(true);
+    btn = btn.height(BTN_H1);
+    btn = btn.padding([ZERO, PADDING]);
+    iced_row![btn.style(style), btn_txt("×", Some(on_close)).style(style)]
+}
+
 pub fn checkbox<'a, Msg>(
     lab: &'a str,
     is_checked: bool,
@@ -166,9 +251,14 @@ pub fn checkbox<'a, Msg>(
         .style(sty_checkbox)
 }
 
-pub fn pick_list_common<'a, T: PartialEq + Display + Clone, Msg: Clone + 'a>(
-    pl: PickList<'a, T, &[T], T, Msg>,
-) -> PickList<'a, T, &'a [T], T, Msg> {
+pub fn pick_list_common<
+    'a,
+    T: PartialEq + Display + Clone,
+    L: std::borrow::Borrow<[T]> + 'a,
+    Msg: Clone + 'a,
+>(
+    pl: PickList<'a, T, L, T, Msg>,
+) -> PickList<'a, T, L, T, Msg> {
     let mut pl = pl;
     pl = pl.handle(PickListHandle::Arrow { size: Some(LINE_H_PIX) });
     pl = pl.text_line_height(LINE_H_PIX);
@@ -333,3 +423,17 @@ pub fn txt_input<'a, Msg: Clone + 'a>(
         .line_height(Pixels(TEXT_INPUT_H - PADDING * TWO))
         .padding(PADDING)
 }
+
+/// Text input that fits into a table cell.
+pub fn txt_input_table_cell<'a, Msg: Clone + 'a>(
+    value: &str,
+    id: &'static str,
+    msg: impl Fn(String) -> Msg + 'a,
+) -> TextInput<'a, Msg> {
+    TextInput::new("", value)
+        .style(sty_text_input)
+        .id(id)
+        .on_input(msg)
+        .size(TABLE_TXT_SIZE)
+        .padding(ZERO)
+}
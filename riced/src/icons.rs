@@ -10,6 +10,9 @@ pub enum Icon {
     RemoveFromSelection,
     Search,
     DataTable,
+    Tanglegram,
+    TreeSet,
+    TreeStats,
 }
 
 impl From<Icon> for SvgHandle {
@@ -25,6 +28,9 @@ impl From<Icon> for SvgHandle {
             Icon::RemoveFromSelection => i(&PLAYLIST_REMOVE),
             Icon::Search => i(&SEARCH),
             Icon::DataTable => i(&TABLE_ROWS_NARROW),
+            Icon::Tanglegram => i(&TANGLEGRAM),
+            Icon::TreeSet => i(&TREE_SET),
+            Icon::TreeStats => i(&TREE_STATS),
         }
     }
 }
@@ -46,6 +46,7 @@ pub use i::advanced::mouse::click::{
 };
 pub use i::alignment::{Horizontal, Vertical};
 pub use i::border::{Border, Radius};
+pub use i::clipboard;
 pub use i::debug::{time as timer, time_with as timer_with};
 pub use i::font::{Family, Stretch, Style as FontStyle, Weight};
 pub use i::futures::{
@@ -60,13 +61,14 @@ pub use i::mouse::{
     Button as MouseButton, Cursor, Event as MouseEvent,
     Interaction as MouseInteraction,
 };
-pub use i::task::{Never, Sipper, sipper};
+pub use i::task::{Handle as TaskHandle, Never, Sipper, sipper};
 pub use i::theme::{Style as ThemeStyle, Theme, palette::Pair as PalettePair};
+pub use i::time::every as time_every;
 pub use i::window::{
     Event as WindowEvent, Id as WindowId, Level as WindowLevel,
     Position as WindowPosition, Settings as WindowSettings,
     allow_automatic_tabbing, close as close_window, events as window_events,
-    open as open_window, raw_id,
+    gain_focus as focus_window, open as open_window, raw_id,
     raw_window_handle::{HasWindowHandle, RawWindowHandle},
     run,
     settings::PlatformSpecific as PlatformSpecificWindowSettings,
@@ -114,6 +116,9 @@ pub use w::pick_list::{
     Handle as PickListHandle, PickList, Status as PickListStatus,
     Style as PickListStyle,
 };
+pub use w::progress_bar::{
+    ProgressBar, Style as ProgressBarStyle, progress_bar,
+};
 pub use w::responsive;
 pub use w::row as iced_row;
 pub use w::rule::{
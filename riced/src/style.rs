@@ -604,6 +604,19 @@ pub(crate) fn sty_slider(theme: &Theme, status: SliderStatus) -> SliderStyle {
     }
 }
 
+pub(crate) fn sty_progress_bar(theme: &Theme) -> ProgressBarStyle {
+    let ep = theme.extended_palette();
+    ProgressBarStyle {
+        background: ep.background.strong.color.into(),
+        bar: ep.primary.base.color.into(),
+        border: Border {
+            radius: WIDGET_RADIUS.into(),
+            width: ZERO,
+            color: Clr::TRN,
+        },
+    }
+}
+
 pub(crate) fn sty_toggler(
     theme: &Theme,
     status: TogglerStatus,
@@ -0,0 +1,271 @@
+use crate::*;
+use std::collections::HashMap;
+
+/// Kind of residues in an alignment, which decides how they are colored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SeqType {
+    #[default]
+    Nucleotide,
+    AminoAcid,
+}
+
+/// Multiple sequence alignment read from a FASTA, PHYLIP or NEXUS file.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Alignment {
+    names: Vec<String>,
+    seqs: Vec<Vec<u8>>,
+    idx_by_name: HashMap<String, usize>,
+    ncol: usize,
+    seq_type: SeqType,
+}
+
+impl Alignment {
+    /// The format is detected from the first non-empty line: `>` starts a
+    /// FASTA file, `#NEXUS` a NEXUS file with a DATA or CHARACTERS block and
+    /// anything else is read as (relaxed, sequential or interleaved) PHYLIP.
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let text = text.trim_start_matches('\u{feff}');
+        let first = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+        let records = if first.starts_with('>') {
+            parse_fasta(text)
+        } else if first.to_ascii_lowercase().starts_with("#nexus") {
+            parse_nexus(text)?
+        } else {
+            parse_phylip(text)?
+        };
+        Self::from_records(records)
+    }
+
+    fn from_records(records: Vec<(String, Vec<u8>)>) -> Option<Self> {
+        let records: Vec<(String, Vec<u8>)> = records
+            .into_iter()
+            .filter(|(name, seq)| !name.is_empty() && !seq.is_empty())
+            .collect();
+        let ncol = records.iter().map(|(_, seq)| seq.len()).max()?;
+
+        let mut aln = Self { ncol, ..Default::default() };
+        for (name, mut seq) in records {
+            seq.make_ascii_uppercase();
+            seq.resize(ncol, b'-');
+            _ = aln.idx_by_name.insert(name_key(&name), aln.names.len());
+            aln.names.push(name);
+            aln.seqs.push(seq);
+        }
+        aln.seq_type = detect_seq_type(&aln.seqs);
+        Some(aln)
+    }
+
+    pub(crate) fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub(crate) fn nrow(&self) -> usize {
+        self.seqs.len()
+    }
+
+    pub(crate) fn ncol(&self) -> usize {
+        self.ncol
+    }
+
+    pub(crate) fn seq_type(&self) -> SeqType {
+        self.seq_type
+    }
+
+    pub(crate) fn seq(&self, idx: usize) -> &[u8] {
+        &self.seqs[idx]
+    }
+
+    /// Index of the sequence for a tip label. Underscores and spaces are
+    /// treated as the same character.
+    pub(crate) fn seq_idx(&self, name: &str) -> Option<usize> {
+        self.idx_by_name.get(&name_key(name)).copied()
+    }
+}
+
+fn name_key(name: &str) -> String {
+    name.trim().replace('_', " ")
+}
+
+fn detect_seq_type(seqs: &[Vec<u8>]) -> SeqType {
+    let (mut nuc, mut total) = (0usize, 0usize);
+    for &b in seqs.iter().flatten().filter(|b| b.is_ascii_alphabetic()) {
+        total += 1;
+        if matches!(b, b'A' | b'C' | b'G' | b'T' | b'U' | b'N') {
+            nuc += 1;
+        }
+    }
+    match total > 0 && nuc as Float / total as Float >= 0.9 {
+        true => SeqType::Nucleotide,
+        false => SeqType::AminoAcid,
+    }
+}
+
+/// Color of a residue or `None` for gaps and ambiguous residues.
+pub(crate) fn residue_color(seq_type: SeqType, residue: u8) -> Option<Color> {
+    let i = match seq_type {
+        SeqType::Nucleotide => match residue {
+            b'A' => 0,
+            b'C' => 1,
+            b'G' => 2,
+            b'T' | b'U' => 3,
+            _ => return None,
+        },
+        SeqType::AminoAcid => match residue {
+            b'A' | b'I' | b'L' | b'M' | b'V' => 0,
+            b'K' | b'R' => 1,
+            b'D' | b'E' => 2,
+            b'N' | b'Q' | b'S' | b'T' => 3,
+            b'C' => 4,
+            b'G' => 5,
+            b'P' => 6,
+            b'F' | b'W' | b'Y' | b'H' => 7,
+            _ => return None,
+        },
+    };
+    Some(match seq_type {
+        SeqType::Nucleotide => CLRS_NUCLEOTIDE[i],
+        SeqType::AminoAcid => CLRS_AMINO_ACID[i],
+    })
+}
+
+fn seq_bytes(s: &str) -> impl Iterator<Item = u8> + '_ {
+    s.bytes().filter(|b| !b.is_ascii_whitespace())
+}
+
+// --- FASTA -------------------------------------------------------------------
+
+fn parse_fasta(text: &str) -> Vec<(String, Vec<u8>)> {
+    let mut records: Vec<(String, Vec<u8>)> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('>') {
+            records.push((name.trim().to_string(), Vec::new()));
+        } else if let Some((_, seq)) = records.last_mut()
+            && !line.starts_with(';')
+        {
+            seq.extend(seq_bytes(line));
+        }
+    }
+    records
+}
+
+// --- PHYLIP ------------------------------------------------------------------
+
+fn parse_phylip(text: &str) -> Option<Vec<(String, Vec<u8>)>> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    let mut header = lines.next()?.split_whitespace();
+    let ntax: usize = header.next()?.parse().ok()?;
+    let nchar: usize = header.next()?.parse().ok()?;
+    let lines: Vec<&str> = lines.collect();
+    if ntax == 0 || lines.len() < ntax {
+        return None;
+    }
+
+    let name_and_seq = |line: &str| -> (String, Vec<u8>) {
+        match line.split_once(char::is_whitespace) {
+            Some((name, seq)) => (name.to_string(), seq_bytes(seq).collect()),
+            None => (line.to_string(), Vec::new()),
+        }
+    };
+
+    // Interleaved, or sequential with each sequence on a single line.
+    let mut records: Vec<(String, Vec<u8>)> =
+        lines[..ntax].iter().map(|l| name_and_seq(l)).collect();
+    for (i, line) in lines[ntax..].iter().enumerate() {
+        records[i % ntax].1.extend(seq_bytes(line));
+    }
+    if records.iter().all(|(_, seq)| seq.len() == nchar) {
+        return Some(records);
+    }
+
+    // Sequential with sequences wrapped over several lines.
+    let mut records: Vec<(String, Vec<u8>)> = Vec::new();
+    for line in lines {
+        match records.last_mut() {
+            Some((_, seq)) if seq.len() < nchar => seq.extend(seq_bytes(line)),
+            _ => records.push(name_and_seq(line)),
+        }
+    }
+    match records.len() == ntax {
+        true => Some(records),
+        false => None,
+    }
+}
+
+// --- NEXUS -------------------------------------------------------------------
+
+fn strip_nexus_comments(text: &str) -> String {
+    let mut depth = 0usize;
+    text.chars()
+        .filter(|&c| {
+            match c {
+                '[' => depth += 1,
+                ']' if depth > 0 => {
+                    depth -= 1;
+                    return false;
+                }
+                _ => {}
+            }
+            depth == 0
+        })
+        .collect()
+}
+
+fn parse_nexus(text: &str) -> Option<Vec<(String, Vec<u8>)>> {
+    let text = strip_nexus_comments(text);
+    let lower = text.to_ascii_lowercase();
+    let block_start = lower
+        .find("begin data;")
+        .or_else(|| lower.find("begin characters;"))?;
+    let block = &lower[block_start..];
+    let block_end = block_start + block.find("end;").unwrap_or(block.len());
+
+    let matchchar = lower[block_start..block_end]
+        .find("matchchar")
+        .and_then(|i| {
+            let rest =
+                lower[block_start + i + "matchchar".len()..].trim_start();
+            rest.strip_prefix('=')?.trim_start().bytes().next()
+        })
+        .map(|b| b.to_ascii_uppercase());
+
+    let matrix_start =
+        block_start + lower[block_start..block_end].find("matrix")? + 6;
+    let matrix = &text[matrix_start..block_end];
+    let matrix = &matrix[..matrix.find(';').unwrap_or(matrix.len())];
+
+    let mut records: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut idx_by_name: HashMap<String, usize> = HashMap::new();
+    for line in matrix.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (name, seq) = match line.strip_prefix('\'') {
+            Some(quoted) => {
+                let (name, seq) = quoted.split_once('\'')?;
+                (name.to_string(), seq)
+            }
+            None => match line.split_once(char::is_whitespace) {
+                Some((name, seq)) => (name.to_string(), seq),
+                None => (line.to_string(), ""),
+            },
+        };
+        let idx = *idx_by_name.entry(name.clone()).or_insert_with(|| {
+            records.push((name, Vec::new()));
+            records.len() - 1
+        });
+        records[idx].1.extend(seq_bytes(seq).map(|b| b.to_ascii_uppercase()));
+    }
+
+    if let Some(matchchar) = matchchar
+        && let Some((first, rest)) = records.split_first_mut()
+    {
+        for (_, seq) in rest {
+            for (j, b) in seq.iter_mut().enumerate() {
+                if *b == matchchar
+                    && let Some(&r) = first.1.get(j)
+                {
+                    *b = r;
+                }
+            }
+        }
+    }
+
+    Some(records)
+}
@@ -0,0 +1,203 @@
+use crate::newick::{NwkNode, nwk_nodes_from_tree, nwk_string, preorder};
+use crate::*;
+
+/// Branch length transformations. Some of them take a parameter, see
+/// [`BrlenTransform::param_name`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BrlenTransform {
+    /// Sets all branch lengths to one.
+    #[default]
+    Unit,
+    /// Grafen's (1989) method: node heights are the number of tips below the
+    /// node minus one, scaled to a tree height of one and raised to the
+    /// power given as the parameter.
+    Grafen,
+    /// Scales all branches so that the tree has the height given as the
+    /// parameter.
+    ScaleToHeight,
+    /// Divides branch lengths by the substitution rate given as the
+    /// parameter.
+    SubsToTime,
+    /// Replaces each branch length `x` by `ln(1 + x)`.
+    Log,
+    /// Collapses internal branches shorter than the threshold given as the
+    /// parameter into polytomies. The length of a collapsed branch is added
+    /// to its child branches, keeping root-to-tip distances.
+    CollapseShort,
+}
+
+pub(super) const BRLEN_TRANSFORM_OPTS: [BrlenTransform; 6] = [
+    BrlenTransform::Unit,
+    BrlenTransform::Grafen,
+    BrlenTransform::ScaleToHeight,
+    BrlenTransform::SubsToTime,
+    BrlenTransform::Log,
+    BrlenTransform::CollapseShort,
+];
+
+impl BrlenTransform {
+    pub fn param_name(&self) -> Option<&'static str> {
+        match self {
+            BrlenTransform::Unit | BrlenTransform::Log => None,
+            BrlenTransform::Grafen => Some("Power"),
+            BrlenTransform::ScaleToHeight => Some("Height"),
+            BrlenTransform::SubsToTime => Some("Rate"),
+            BrlenTransform::CollapseShort => Some("Threshold"),
+        }
+    }
+
+    pub fn default_param(&self) -> Option<TreeFloat> {
+        match self {
+            BrlenTransform::Unit | BrlenTransform::Log => None,
+            BrlenTransform::CollapseShort => Some(1e-6),
+            _ => Some(1e0),
+        }
+    }
+
+    /// Whether the transformation works on trees without branch lengths.
+    pub fn needs_brlen(&self) -> bool {
+        !matches!(self, BrlenTransform::Unit | BrlenTransform::Grafen)
+    }
+
+    /// Unit of the transformed branch lengths, if it differs from the unit of
+    /// the input.
+    pub fn tre_unit(&self) -> Option<TreUnit> {
+        match self {
+            BrlenTransform::Unit
+            | BrlenTransform::Grafen
+            | BrlenTransform::Log => Some(TreUnit::Unitless),
+            BrlenTransform::SubsToTime => Some(TreUnit::MillionYears),
+            BrlenTransform::ScaleToHeight | BrlenTransform::CollapseShort => {
+                None
+            }
+        }
+    }
+}
+
+impl Display for BrlenTransform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            BrlenTransform::Unit => "Unit Length",
+            BrlenTransform::Grafen => "Grafen",
+            BrlenTransform::ScaleToHeight => "Scale to Height",
+            BrlenTransform::SubsToTime => "Substitutions to Time",
+            BrlenTransform::Log => "Log",
+            BrlenTransform::CollapseShort => "Collapse Short Branches",
+        })
+    }
+}
+
+/// Applies the transformation and returns the tree as a NEWICK string, or
+/// `None` if the parameter is missing or not positive or the tree lacks the
+/// branch lengths the transformation needs.
+pub(crate) fn transformed_newick(
+    tree: &Tree,
+    transform: BrlenTransform,
+    param: Option<TreeFloat>,
+) -> Option<String> {
+    if transform.needs_brlen() && !tree.has_branch_lengths() {
+        return None;
+    }
+
+    let param = match transform.param_name() {
+        Some(_) => Some(param.filter(|&p| p > 0e0)?),
+        None => None,
+    };
+
+    let (mut nodes, _, root) = nwk_nodes_from_tree(tree)?;
+    let order = preorder(&nodes, root);
+
+    match transform {
+        BrlenTransform::Unit => {
+            for &i in &order[1..] {
+                nodes[i].brlen = Some(1e0);
+            }
+        }
+
+        BrlenTransform::Grafen => {
+            let power = param?;
+            let mut tip_counts: Vec<usize> = vec![0; nodes.len()];
+            for &i in order.iter().rev() {
+                tip_counts[i] = match nodes[i].children.is_empty() {
+                    true => 1,
+                    false => {
+                        nodes[i].children.iter().map(|&c| tip_counts[c]).sum()
+                    }
+                };
+            }
+
+            let n = tip_counts[root];
+            if n < 2 {
+                return None;
+            }
+
+            let height = |i: usize| {
+                ((tip_counts[i] - 1) as TreeFloat / (n - 1) as TreeFloat)
+                    .powf(power)
+            };
+
+            nodes[root].brlen = None;
+            for &i in &order {
+                for c in nodes[i].children.clone() {
+                    nodes[c].brlen = Some(height(i) - height(c));
+                }
+            }
+        }
+
+        BrlenTransform::ScaleToHeight => {
+            let mut dists: Vec<TreeFloat> = vec![0e0; nodes.len()];
+            for &i in &order[1..] {
+                dists[i] = nodes[i].brlen.unwrap_or(0e0);
+            }
+            for &i in &order {
+                for &c in &nodes[i].children {
+                    dists[c] += dists[i];
+                }
+            }
+
+            let height = dists.into_iter().fold(0e0, TreeFloat::max);
+            if height <= 0e0 {
+                return None;
+            }
+
+            let factor = param? / height;
+            scale_brlens(&mut nodes, |brlen| brlen * factor);
+        }
+
+        BrlenTransform::SubsToTime => {
+            let rate = param?;
+            scale_brlens(&mut nodes, |brlen| brlen / rate);
+        }
+
+        BrlenTransform::Log => {
+            scale_brlens(&mut nodes, |brlen| brlen.max(0e0).ln_1p());
+        }
+
+        BrlenTransform::CollapseShort => {
+            let threshold = param?;
+            for &i in order.iter().rev() {
+                let children = std::mem::take(&mut nodes[i].children);
+                for c in children {
+                    let brlen = nodes[c].brlen.unwrap_or(0e0);
+                    if nodes[c].children.is_empty() || brlen >= threshold {
+                        nodes[i].children.push(c);
+                        continue;
+                    }
+                    for gc in std::mem::take(&mut nodes[c].children) {
+                        nodes[gc].brlen =
+                            Some(nodes[gc].brlen.unwrap_or(0e0) + brlen);
+                        nodes[i].children.push(gc);
+                    }
+                }
+            }
+        }
+    }
+
+    Some(nwk_string(&nodes, root))
+}
+
+fn scale_brlens(nodes: &mut [NwkNode], f: impl Fn(TreeFloat) -> TreeFloat) {
+    for nd in nodes {
+        nd.brlen = nd.brlen.map(&f);
+    }
+}
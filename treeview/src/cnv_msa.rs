@@ -0,0 +1,206 @@
+mod draw;
+mod state;
+
+use crate::alignment::Alignment;
+use crate::*;
+use draw::*;
+use state::St;
+
+/// Placement of the alignment rows and columns. The rows line up with the
+/// tips of the tree canvas in the phylogram layout.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct MsaGeom {
+    /// Position of the first row.
+    pub(crate) y0: Float,
+    /// Distance between the first and the last row.
+    pub(crate) h: Float,
+    pub(crate) col_w: Float,
+}
+
+impl MsaGeom {
+    pub(crate) fn row_h(&self, row_count: usize) -> Float {
+        match row_count > 1 {
+            true => self.h / (row_count - 1) as Float,
+            false => self.h,
+        }
+    }
+}
+
+/// A tip of the tree and the sequence drawn in its row.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MsaRow {
+    pub(crate) y: Float,
+    pub(crate) edge_idx: usize,
+    pub(crate) seq_idx: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct MsaCnv {
+    pub(super) tree_state: Option<Rc<TreeState>>,
+    pub(super) alignment: Option<Alignment>,
+    pub(super) geom: MsaGeom,
+    pub(super) rows: Vec<MsaRow>,
+    pub(super) tips_missing: Vec<String>,
+    pub(super) seqs_missing: Vec<String>,
+    // -------------------------------------------------------------------------
+    pub(super) vis_x0: Float,
+    pub(super) vis_y0: Float,
+    pub(super) vis_w: Float,
+    pub(super) vis_h: Float,
+    // -------------------------------------------------------------------------
+    pub(super) cache_cnv_residues: CnvCache,
+    pub(super) cache_cnv_sel_rows: CnvCache,
+}
+
+impl Program<TvMsg> for MsaCnv {
+    type State = St;
+
+    fn mouse_interaction(
+        &self,
+        _st: &St,
+        _bnds: Rectangle,
+        _crsr: Cursor,
+    ) -> MouseInteraction {
+        MouseInteraction::default()
+    }
+
+    fn update(
+        &self,
+        st: &mut St,
+        _ev: &Event,
+        bnds: Rectangle,
+        _crsr: Cursor,
+    ) -> Option<Action<TvMsg>> {
+        if st.bnds.width != bnds.width || st.bnds.height != bnds.height {
+            st.bnds = bnds;
+            self.clear_caches_cnv_all();
+        }
+        None
+    }
+
+    fn draw(
+        &self,
+        st: &St,
+        rndr: &Renderer,
+        _thm: &Theme,
+        bnds: Rectangle,
+        _crsr: Cursor,
+    ) -> Vec<Geometry> {
+        let mut geoms: Vec<Geometry> = Vec::new();
+        if self.tree_state.is_none() || self.alignment.is_none() {
+            return geoms;
+        }
+        // ---------------------------------------------------------------------
+        let t = timer("msa_residues");
+        draw_residues(self, st, rndr, bnds.size(), &mut geoms);
+        t.finish();
+
+        let t = timer("msa_sel_rows");
+        draw_sel_rows(self, st, rndr, bnds.size(), &mut geoms);
+        t.finish();
+        // ---------------------------------------------------------------------
+        geoms
+    }
+}
+
+impl MsaCnv {
+    pub(super) fn set_alignment(&mut self, alignment: Option<Alignment>) {
+        self.alignment = alignment;
+        self.update_rows();
+        self.clear_caches_cnv_all();
+    }
+
+    /// Points the canvas at the selected tree. The rows are only rebuilt when
+    /// the order or the labels of the tips have changed.
+    pub(super) fn set_tree(&mut self, tree_state: Option<Rc<TreeState>>) {
+        self.tree_state = tree_state;
+        let rows_prev = std::mem::take(&mut self.rows);
+        self.update_rows();
+        if self.rows != rows_prev {
+            self.clear_caches_cnv_all();
+        } else {
+            self.clear_cache_cnv_sel_rows();
+        }
+    }
+
+    /// Drops the reference to the tree state so that it can be mutated.
+    pub(super) fn release_tree(&mut self) {
+        self.tree_state = None;
+    }
+
+    pub(super) fn set_geom(&mut self, geom: MsaGeom) {
+        if geom != self.geom {
+            self.geom = geom;
+            self.clear_caches_cnv_all();
+        }
+    }
+
+    pub(super) fn width(&self) -> Float {
+        match &self.alignment {
+            Some(aln) => aln.ncol() as Float * self.geom.col_w + PADDING * TWO,
+            None => ZRO,
+        }
+    }
+
+    /// First and last (exclusive) alignment columns in view.
+    pub(super) fn vis_cols(&self) -> (usize, usize) {
+        let Some(aln) = &self.alignment else {
+            return (0, 0);
+        };
+        let col_w = self.geom.col_w.max(EPSILON);
+        let c0 = ((self.vis_x0 - PADDING) / col_w).floor().max(ZRO) as usize;
+        let c1 = ((self.vis_x0 + self.vis_w - PADDING) / col_w).ceil().max(ZRO)
+            as usize;
+        (c0.min(aln.ncol()), c1.min(aln.ncol()))
+    }
+
+    fn update_rows(&mut self) {
+        self.rows.clear();
+        self.tips_missing.clear();
+        self.seqs_missing.clear();
+        let Some(aln) = &self.alignment else {
+            return;
+        };
+        let Some(edges) = self.tree_state.as_deref().and_then(|ts| ts.edges())
+        else {
+            return;
+        };
+
+        let mut seq_in_tree = vec![false; aln.nrow()];
+        for edge in edges.iter().filter(|edge| edge.is_tip) {
+            let label = edge.label.as_deref().unwrap_or_default();
+            let seq_idx = aln.seq_idx(label);
+            match seq_idx {
+                Some(idx) => seq_in_tree[idx] = true,
+                None => self.tips_missing.push(label.to_string()),
+            }
+            self.rows.push(MsaRow {
+                y: edge.y as Float,
+                edge_idx: edge.edge_index,
+                seq_idx,
+            });
+        }
+        self.rows.sort_by(|a, b| a.y.total_cmp(&b.y));
+
+        self.seqs_missing = aln
+            .names()
+            .iter()
+            .zip(seq_in_tree)
+            .filter(|(_, in_tree)| !in_tree)
+            .map(|(name, _)| name.clone())
+            .collect();
+    }
+
+    pub(super) fn clear_cache_cnv_residues(&self) {
+        self.cache_cnv_residues.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_sel_rows(&self) {
+        self.cache_cnv_sel_rows.clear();
+    }
+
+    pub(super) fn clear_caches_cnv_all(&self) {
+        self.clear_cache_cnv_residues();
+        self.clear_cache_cnv_sel_rows();
+    }
+}
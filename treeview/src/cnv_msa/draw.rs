@@ -0,0 +1,91 @@
+use super::*;
+use crate::alignment::residue_color;
+use crate::cnv_utils::*;
+
+/// Rows with any part in view, with the position of their centers.
+fn vis_rows(mc: &MsaCnv) -> impl Iterator<Item = (&MsaRow, Float)> {
+    let row_h = mc.geom.row_h(mc.rows.len());
+    let y_min = mc.vis_y0 - row_h;
+    let y_max = mc.vis_y0 + mc.vis_h + row_h;
+    mc.rows
+        .iter()
+        .map(|row| (row, mc.geom.y0 + row.y * mc.geom.h))
+        .filter(move |(_, y)| (y_min..=y_max).contains(y))
+}
+
+pub(super) fn draw_residues(
+    mc: &MsaCnv,
+    _st: &St,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(mc.cache_cnv_residues.draw(rndr, sz, |f| {
+        let Some(aln) = &mc.alignment else {
+            return;
+        };
+        let seq_type = aln.seq_type();
+        let col_w = mc.geom.col_w;
+        let row_h = mc.geom.row_h(mc.rows.len()).min(col_w * 3e0);
+        let (c0, c1) = mc.vis_cols();
+        let draw_letters = col_w >= SF * 7e0 && row_h >= SF * 7e0;
+        let lab_size = col_w.min(row_h) * 0.8;
+
+        for (row, y) in vis_rows(mc) {
+            let Some(seq_idx) = row.seq_idx else {
+                continue;
+            };
+            let seq = aln.seq(seq_idx);
+            for (c, &residue) in seq.iter().enumerate().take(c1).skip(c0) {
+                let x = PADDING + c as Float * col_w;
+                if let Some(color) = residue_color(seq_type, residue) {
+                    f.fill_rectangle(
+                        Point { x, y: y - row_h / TWO },
+                        Size { width: col_w, height: row_h },
+                        color,
+                    );
+                }
+                if draw_letters {
+                    let mut text = lab_text(
+                        (residue as char).to_string(),
+                        Point { x: x + col_w / TWO, y },
+                        lab_size,
+                        TEMPLATE_TXT_LAB_TIP,
+                        false,
+                    );
+                    text.align_x = TextAlignment::Center;
+                    f.fill_text(text);
+                }
+            }
+        }
+    }));
+}
+
+pub(super) fn draw_sel_rows(
+    mc: &MsaCnv,
+    _st: &St,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(mc.cache_cnv_sel_rows.draw(rndr, sz, |f| {
+        let Some(ts) = &mc.tree_state else {
+            return;
+        };
+        let sel_edge_idxs: HashSet<&usize> =
+            HashSet::from_iter(ts.sel_edge_idxs());
+        if sel_edge_idxs.is_empty() {
+            return;
+        }
+        let row_h = mc.geom.row_h(mc.rows.len()).max(SF * 2e0);
+        for (_, y) in vis_rows(mc)
+            .filter(|(row, _)| sel_edge_idxs.contains(&row.edge_idx))
+        {
+            f.fill_rectangle(
+                Point { x: mc.vis_x0, y: y - row_h / TWO },
+                Size { width: mc.vis_w, height: row_h },
+                FILL_NODE_SELECTED,
+            );
+        }
+    }));
+}
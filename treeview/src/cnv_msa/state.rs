@@ -0,0 +1,6 @@
+use crate::*;
+
+#[derive(Debug, Default)]
+pub struct St {
+    pub(super) bnds: Rectangle<Float>,
+}
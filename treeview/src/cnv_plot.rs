@@ -2,6 +2,8 @@ mod draw;
 mod state;
 
 use crate::cnv_utils::*;
+use crate::root_to_tip::RootToTip;
+use crate::tables::delimited_text;
 use crate::*;
 use draw::*;
 use state::St;
@@ -10,7 +12,13 @@ use state::St;
 pub(super) struct PlotCnv {
     pub(super) cfg: Rc<TreeViewConfig>,
     // -------------------------------------------------------------------------
+    pub(super) plot_type: PlotType,
     ltt_plot_data: PlotData,
+    ltt_set: LttSet,
+    hist_plot_data: PlotData,
+    pub(super) rtt: Option<RootToTip>,
+    rtt_plot_data: PlotData,
+    rtt_sel_node_ids: HashSet<NodeId>,
     pub(super) x_axis_is_reversed: bool,
     pub(super) x_axis_scale_type: AxisScaleType,
     pub(super) y_axis_scale_type: AxisScaleType,
@@ -25,6 +33,8 @@ pub(super) struct PlotCnv {
     pub(super) cache_cnv_background: CnvCache,
     pub(super) cache_cnv_gts: CnvCache,
     pub(super) cache_cnv_ltt: CnvCache,
+    pub(super) cache_cnv_rtt: CnvCache,
+    pub(super) cache_cnv_hist: CnvCache,
     pub(super) cache_cnv_cursor_line: CnvCache,
     pub(super) cache_cnv_axes: CnvCache,
     pub(super) cache_cnv_ticks: CnvCache,
@@ -35,7 +45,13 @@ impl Default for PlotCnv {
     fn default() -> Self {
         Self {
             cfg: Default::default(),
+            plot_type: Default::default(),
             ltt_plot_data: Default::default(),
+            ltt_set: Default::default(),
+            hist_plot_data: Default::default(),
+            rtt: Default::default(),
+            rtt_plot_data: Default::default(),
+            rtt_sel_node_ids: Default::default(),
             x_axis_is_reversed: Default::default(),
             x_axis_scale_type: AxisScaleType::Linear,
             y_axis_scale_type: AxisScaleType::LogTwo,
@@ -50,6 +66,8 @@ impl Default for PlotCnv {
             cache_cnv_background: Default::default(),
             cache_cnv_gts: Default::default(),
             cache_cnv_ltt: Default::default(),
+            cache_cnv_rtt: Default::default(),
+            cache_cnv_hist: Default::default(),
             cache_cnv_cursor_line: Default::default(),
             cache_cnv_axes: Default::default(),
             cache_cnv_ticks: Default::default(),
@@ -63,11 +81,14 @@ impl Program<TvMsg> for PlotCnv {
 
     fn mouse_interaction(
         &self,
-        _st: &St,
+        st: &St,
         _bnds: Rectangle,
         _crsr: Cursor,
     ) -> MouseInteraction {
-        MouseInteraction::default()
+        match st.hovered_rtt_point {
+            Some(_) => MouseInteraction::Pointer,
+            None => MouseInteraction::default(),
+        }
     }
 
     fn update(
@@ -79,11 +100,14 @@ impl Program<TvMsg> for PlotCnv {
     ) -> Option<Action<TvMsg>> {
         let mut action: Option<Action<TvMsg>> = None;
 
+        let plot_data = self.plot_data();
+
         if st.tre_unit != self.cfg.tre_unit
-            || st.ltt_plot_data.x_min != self.ltt_plot_data.x_min
-            || st.ltt_plot_data.y_min != self.ltt_plot_data.y_min
-            || st.ltt_plot_data.x_max != self.ltt_plot_data.x_max
-            || st.ltt_plot_data.y_max != self.ltt_plot_data.y_max
+            || st.plot_type != self.plot_type
+            || st.plot_data.x_min != plot_data.x_min
+            || st.plot_data.y_min != plot_data.y_min
+            || st.plot_data.x_max != plot_data.x_max
+            || st.plot_data.y_max != plot_data.y_max
             || st.x_axis_scale_type != self.x_axis_scale_type
             || st.y_axis_scale_type != self.y_axis_scale_type
             || st.bnds.width != bnds.width
@@ -93,7 +117,8 @@ impl Program<TvMsg> for PlotCnv {
             || st.plt_padd_t != self.padd_t
             || st.plt_padd_b != self.padd_b
         {
-            st.ltt_plot_data = self.ltt_plot_data.clone();
+            st.plot_type = self.plot_type;
+            st.plot_data = plot_data.clone();
             st.x_axis_scale_type = self.x_axis_scale_type;
             st.y_axis_scale_type = self.y_axis_scale_type;
             st.tre_unit = self.cfg.tre_unit;
@@ -126,17 +151,41 @@ impl Program<TvMsg> for PlotCnv {
                 / (st.text_size * 3.0))
                 .floor() as usize;
 
-            let (ticks_x, x_max_lab_nchar) = calc_ticks(
-                n_ticks_x, self.x_axis_scale_type,
-                self.ltt_plot_data.x_data_type, self.ltt_plot_data.x_min,
-                self.ltt_plot_data.x_max, self.x_axis_is_reversed,
-            );
-
-            let (ticks_y, y_max_lab_nchar) = calc_ticks(
-                n_ticks_y, self.y_axis_scale_type,
-                self.ltt_plot_data.y_data_type, self.ltt_plot_data.y_min,
-                self.ltt_plot_data.y_max, false,
-            );
+            let ((ticks_x, x_max_lab_nchar), (ticks_y, y_max_lab_nchar)) =
+                match self.plot_type {
+                    PlotType::Ltt | PlotType::LttAll => (
+                        calc_ticks(
+                            n_ticks_x, self.x_axis_scale_type,
+                            plot_data.x_data_type, plot_data.x_min,
+                            plot_data.x_max, self.x_axis_is_reversed,
+                        ),
+                        calc_ticks(
+                            n_ticks_y, self.y_axis_scale_type,
+                            plot_data.y_data_type, plot_data.y_min,
+                            plot_data.y_max, false,
+                        ),
+                    ),
+                    PlotType::RootToTip => (
+                        calc_ticks_linear(
+                            n_ticks_x, plot_data.x_min, plot_data.x_max,
+                        ),
+                        calc_ticks_linear(
+                            n_ticks_y, plot_data.y_min, plot_data.y_max,
+                        ),
+                    ),
+                    PlotType::BrlenHistogram
+                    | PlotType::DepthHistogram
+                    | PlotType::AttrHistogram => (
+                        calc_ticks_linear(
+                            n_ticks_x, plot_data.x_min, plot_data.x_max,
+                        ),
+                        calc_ticks(
+                            n_ticks_y, self.y_axis_scale_type,
+                            plot_data.y_data_type, plot_data.y_min,
+                            plot_data.y_max, false,
+                        ),
+                    ),
+                };
 
             st.ticks_x = ticks_x;
             st.ticks_y = ticks_y;
@@ -157,6 +206,15 @@ impl Program<TvMsg> for PlotCnv {
             st.plt_rect = st.plt_vs.clone().into();
         }
 
+        if !self.plot_type.is_tree_aligned() {
+            if self.plot_type == PlotType::RootToTip
+                && let Event::Mouse(mouse_ev) = ev
+            {
+                action = self.rtt_mouse_event(st, mouse_ev, crsr);
+            }
+            return action;
+        }
+
         if let Event::Mouse(mouse_ev) = ev {
             match mouse_ev {
                 MouseEvent::CursorEntered => {
@@ -203,13 +261,41 @@ impl Program<TvMsg> for PlotCnv {
         draw_plot_background(self, st, rndr, bnds.size(), &mut geoms);
         t.finish();
 
+        if !self.plot_type.is_tree_aligned() {
+            match self.plot_type {
+                PlotType::RootToTip => {
+                    let t = timer("rtt");
+                    draw_rtt(self, st, rndr, bnds.size(), &mut geoms);
+                    t.finish();
+                }
+                _ => {
+                    let t = timer("histogram");
+                    draw_histogram(self, st, rndr, bnds.size(), &mut geoms);
+                    t.finish();
+                }
+            }
+
+            let t = timer("ticks");
+            draw_ticks(self, st, rndr, bnds.size(), &mut geoms);
+            t.finish();
+
+            let t = timer("axes");
+            draw_axes(self, st, rndr, bnds.size(), &mut geoms);
+            t.finish();
+            return geoms;
+        }
+
         if self.cfg.draw_gts && self.cfg.tre_unit == TreUnit::MillionYears {
             let t = timer("gts");
             draw_gts(self, st, rndr, bnds.size(), &mut geoms);
             t.finish();
         }
 
-        if self.cfg.draw_ltt {
+        if self.plot_type == PlotType::LttAll {
+            let t = timer("ltt_set");
+            draw_ltt_set(self, st, rndr, bnds.size(), &mut geoms);
+            t.finish();
+        } else if self.cfg.draw_ltt {
             let t = timer("ltt");
             draw_ltt(self, st, rndr, bnds.size(), &mut geoms);
             t.finish();
@@ -286,6 +372,135 @@ pub fn plot_data_from_ltt_points(
     }
 }
 
+/// Histogram of `values` in equal-width bins. The plot points hold the start
+/// and the count of each bin.
+pub fn plot_data_from_histogram(values: &[Float]) -> PlotData {
+    if values.is_empty() {
+        return PlotData::default();
+    }
+    let x_min = values.iter().copied().fold(Float::MAX, Float::min);
+    let mut x_max = values.iter().copied().fold(Float::MIN, Float::max);
+    if x_max <= x_min {
+        x_max = x_min + ONE;
+    }
+
+    let bin_count = ((values.len() as Float).sqrt().ceil() as usize)
+        .clamp(HIST_BINS_MIN, HIST_BINS_MAX);
+    let bin_w = (x_max - x_min) / bin_count as Float;
+    let mut counts: Vec<usize> = vec![0; bin_count];
+    for v in values {
+        let bin = ((v - x_min) / bin_w) as usize;
+        counts[bin.min(bin_count - 1)] += 1;
+    }
+
+    PlotData {
+        x_data_type: AxisDataType::Continuous,
+        y_data_type: AxisDataType::Discrete,
+        x_min,
+        x_max,
+        y_min: ZRO,
+        y_max: counts.iter().copied().max().unwrap_or(0) as Float,
+        plot_points: counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| PlotPoint {
+                x: x_min + bin_w * i as Float,
+                y: count as Float,
+            })
+            .collect(),
+    }
+}
+
+/// Lineage-through-time curves of several trees sampled at the same times,
+/// with their pointwise median and 95% envelope.
+#[derive(Debug, Default, Clone)]
+pub struct LttSet {
+    pub curves: Vec<PlotData>,
+    pub median: PlotData,
+    pub lower: PlotData,
+    pub upper: PlotData,
+}
+
+impl LttSet {
+    /// `spans` holds the start and end times of the branches of each tree,
+    /// measured from its root. Times run from `x_min` to `x_max`.
+    pub fn new(
+        spans: &[Vec<(Float, Float)>],
+        x_min: Float,
+        x_max: Float,
+        point_count: usize,
+    ) -> Self {
+        if spans.is_empty() || point_count < 2 || x_max <= x_min {
+            return Self::default();
+        }
+        let times: Vec<Float> = (0..point_count)
+            .map(|i| {
+                x_min
+                    + (x_max - x_min) * i as Float / (point_count - 1) as Float
+            })
+            .collect();
+
+        let counts: Vec<Vec<usize>> = spans
+            .iter()
+            .map(|spans| {
+                let mut starts: Vec<Float> =
+                    spans.iter().map(|(start, _)| *start).collect();
+                let mut ends: Vec<Float> =
+                    spans.iter().map(|(_, end)| *end).collect();
+                starts.sort_by(Float::total_cmp);
+                ends.sort_by(Float::total_cmp);
+                times
+                    .iter()
+                    .map(|&t| {
+                        // Branches that started before `t` and did not end.
+                        let t = t.max(EPSILON);
+                        starts.partition_point(|s| *s < t)
+                            - ends.partition_point(|e| *e < t)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let y_max =
+            counts.iter().flatten().copied().max().unwrap_or(0) as Float;
+        let plot_data = |ys: Vec<Float>| PlotData {
+            x_data_type: AxisDataType::Continuous,
+            y_data_type: AxisDataType::Discrete,
+            x_min,
+            x_max,
+            y_min: ZRO,
+            y_max,
+            plot_points: times
+                .iter()
+                .zip(ys)
+                .map(|(&x, y)| PlotPoint { x, y })
+                .collect(),
+        };
+
+        let quantile = |q: Float| -> Vec<Float> {
+            (0..point_count)
+                .map(|i| {
+                    let mut at: Vec<usize> =
+                        counts.iter().map(|c| c[i]).collect();
+                    at.sort_unstable();
+                    let k = ((at.len() - 1) as Float * q).round() as usize;
+                    at[k] as Float
+                })
+                .collect()
+        };
+
+        Self {
+            curves: counts
+                .iter()
+                .map(|c| plot_data(c.iter().map(|&n| n as Float).collect()))
+                .collect(),
+            median: plot_data(quantile(0.5)),
+            lower: plot_data(quantile(0.025)),
+            upper: plot_data(quantile(0.975)),
+        }
+    }
+}
+
 impl PlotCnv {
     pub(super) fn new(cfg: Rc<TreeViewConfig>, draw_debug: bool) -> Self {
         Self {
@@ -308,6 +523,14 @@ impl PlotCnv {
         self.cache_cnv_ltt.clear();
     }
 
+    pub(super) fn clear_cache_cnv_rtt(&self) {
+        self.cache_cnv_rtt.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_hist(&self) {
+        self.cache_cnv_hist.clear();
+    }
+
     pub(super) fn clear_cache_cnv_cursor_line(&self) {
         self.cache_cnv_cursor_line.clear();
     }
@@ -328,6 +551,8 @@ impl PlotCnv {
         self.clear_cache_cnv_background();
         self.clear_cache_cnv_gts();
         self.clear_cache_cnv_ltt();
+        self.clear_cache_cnv_rtt();
+        self.clear_cache_cnv_hist();
         self.clear_cache_cnv_cursor_line();
         self.clear_cache_cnv_axes();
         self.clear_cache_cnv_ticks();
@@ -338,8 +563,249 @@ impl PlotCnv {
         self.ltt_plot_data = data;
         self.clear_caches_cnv_all();
     }
+
+    pub(super) fn set_ltt_set(&mut self, ltt_set: LttSet) {
+        self.ltt_set = ltt_set;
+        self.clear_caches_cnv_all();
+    }
+
+    pub(super) fn set_hist_plot_data(&mut self, data: PlotData) {
+        self.hist_plot_data = data;
+        self.clear_caches_cnv_all();
+    }
+
+    pub(super) fn set_rtt(&mut self, rtt: Option<RootToTip>) {
+        self.rtt_plot_data = match &rtt {
+            Some(rtt) => rtt.plot_data(),
+            None => PlotData::default(),
+        };
+        self.rtt = rtt;
+        self.clear_caches_cnv_all();
+    }
+
+    /// Keeps the selected tips highlighted in the root-to-tip plot.
+    pub(super) fn set_rtt_sel_node_ids(&mut self, sel: &HashSet<NodeId>) {
+        if self.rtt_sel_node_ids != *sel {
+            self.rtt_sel_node_ids = sel.clone();
+            self.clear_cache_cnv_rtt();
+        }
+    }
+
+    fn plot_data(&self) -> &PlotData {
+        match self.plot_type {
+            PlotType::Ltt => &self.ltt_plot_data,
+            PlotType::LttAll => &self.ltt_set.median,
+            PlotType::RootToTip => &self.rtt_plot_data,
+            PlotType::BrlenHistogram
+            | PlotType::DepthHistogram
+            | PlotType::AttrHistogram => &self.hist_plot_data,
+        }
+    }
+
+    /// Data behind the current plot as delimited text.
+    pub(super) fn export_text(&self, delimiter: u8) -> String {
+        let rows: Vec<Vec<String>>;
+        let header: Vec<String>;
+        match self.plot_type {
+            PlotType::Ltt => {
+                header = vec!["time".into(), "lineages".into()];
+                rows = self
+                    .ltt_plot_data
+                    .plot_points
+                    .iter()
+                    .map(|pt| vec![pt.x.to_string(), pt.y.to_string()])
+                    .collect();
+            }
+            PlotType::LttAll => {
+                let set = &self.ltt_set;
+                header = ["time", "median", "lower", "upper"]
+                    .into_iter()
+                    .map(String::from)
+                    .chain((1..=set.curves.len()).map(|i| format!("tree_{i}")))
+                    .collect();
+                rows = set
+                    .median
+                    .plot_points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, pt)| {
+                        let y_at =
+                            |data: &PlotData| match data.plot_points.get(i) {
+                                Some(pt) => pt.y.to_string(),
+                                None => String::new(),
+                            };
+                        [pt.x.to_string(), pt.y.to_string()]
+                            .into_iter()
+                            .chain([y_at(&set.lower), y_at(&set.upper)])
+                            .chain(set.curves.iter().map(y_at))
+                            .collect()
+                    })
+                    .collect();
+            }
+            PlotType::RootToTip => {
+                header = ["node_id", "label", "date", "distance"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect();
+                rows = match &self.rtt {
+                    Some(rtt) => (0..rtt.node_ids.len())
+                        .map(|i| {
+                            vec![
+                                rtt.node_ids[i].to_string(),
+                                rtt.labels[i].clone(),
+                                rtt.dates[i].to_string(),
+                                rtt.dists[i].to_string(),
+                            ]
+                        })
+                        .collect(),
+                    None => Vec::new(),
+                };
+            }
+            PlotType::BrlenHistogram
+            | PlotType::DepthHistogram
+            | PlotType::AttrHistogram => {
+                header = ["bin_start", "bin_end", "count"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect();
+                let data = &self.hist_plot_data;
+                let bin_w = match data.plot_points.len() {
+                    0 => ZRO,
+                    n => (data.x_max - data.x_min) / n as Float,
+                };
+                rows = data
+                    .plot_points
+                    .iter()
+                    .map(|pt| {
+                        vec![
+                            pt.x.to_string(),
+                            (pt.x + bin_w).to_string(),
+                            pt.y.to_string(),
+                        ]
+                    })
+                    .collect();
+            }
+        }
+        let header: Vec<&str> = header.iter().map(String::as_str).collect();
+        delimited_text(&header, rows.into_iter(), delimiter)
+    }
+
+    /// Positions of the root-to-tip points relative to the plot area.
+    fn rtt_points(&self, w: Float, h: Float) -> Vec<Point> {
+        let data = &self.rtt_plot_data;
+        data.plot_points
+            .iter()
+            .map(|pt| {
+                let x_rel = (pt.x - data.x_min) / (data.x_max - data.x_min);
+                let y_rel = match data.y_max > data.y_min {
+                    true => (pt.y - data.y_min) / (data.y_max - data.y_min),
+                    false => ZRO,
+                };
+                Point { x: x_rel * w, y: (ONE - y_rel) * h }
+            })
+            .collect()
+    }
+
+    /// Tracks the hovered point and selects its tip when clicked.
+    fn rtt_mouse_event(
+        &self,
+        st: &mut St,
+        mouse_ev: &MouseEvent,
+        crsr: Cursor,
+    ) -> Option<Action<TvMsg>> {
+        match mouse_ev {
+            MouseEvent::CursorMoved { position: _ } => {
+                let hovered = crsr.position_in(st.bnds).and_then(|mouse| {
+                    let adj = mouse - st.plt_vs.trans;
+                    self.rtt_points(st.plt_vs.w, st.plt_vs.h)
+                        .iter()
+                        .map(|pt| pt.distance(adj))
+                        .enumerate()
+                        .filter(|(_, d)| *d <= RTT_POINT_R * TWO)
+                        .min_by(|a, b| a.1.total_cmp(&b.1))
+                        .map(|(i, _)| i)
+                });
+                if hovered != st.hovered_rtt_point {
+                    st.hovered_rtt_point = hovered;
+                    self.clear_cache_cnv_rtt();
+                }
+                None
+            }
+            MouseEvent::CursorLeft => {
+                st.hovered_rtt_point = None;
+                self.clear_cache_cnv_rtt();
+                None
+            }
+            MouseEvent::ButtonPressed(MouseButton::Left) => {
+                let rtt = self.rtt.as_ref()?;
+                let node_id = *rtt.node_ids.get(st.hovered_rtt_point?)?;
+                Some(Action::publish(match self.cfg.selection_lock {
+                    true => TvMsg::SelectDeselectNode(node_id),
+                    false => TvMsg::SelectDeselectNodeExclusive(node_id),
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Data shown in the plot pane.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlotType {
+    /// Lineages through time, aligned with the tree.
+    #[default]
+    Ltt,
+    /// Lineages through time of every loaded tree, aligned with the tree.
+    LttAll,
+    /// Root-to-tip distance against sampling date.
+    RootToTip,
+    BrlenHistogram,
+    /// Distances from the root to the internal nodes, or their ages when the
+    /// tree is in millions of years.
+    DepthHistogram,
+    /// Values of a numeric node attribute.
+    AttrHistogram,
 }
 
+impl PlotType {
+    /// Whether the x-axis of the plot follows the tree and the cursor line is
+    /// shared with it.
+    pub(super) fn is_tree_aligned(&self) -> bool {
+        matches!(self, PlotType::Ltt | PlotType::LttAll)
+    }
+
+    pub(super) fn is_histogram(&self) -> bool {
+        matches!(
+            self,
+            PlotType::BrlenHistogram
+                | PlotType::DepthHistogram
+                | PlotType::AttrHistogram
+        )
+    }
+}
+
+impl Display for PlotType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            PlotType::Ltt => "Lineages Through Time",
+            PlotType::LttAll => "LTT of All Trees",
+            PlotType::RootToTip => "Root-to-Tip",
+            PlotType::BrlenHistogram => "Branch Lengths",
+            PlotType::DepthHistogram => "Node Depths",
+            PlotType::AttrHistogram => "Attribute Values",
+        })
+    }
+}
+
+pub(super) const PLOT_TYPE_OPTS: [PlotType; 6] = [
+    PlotType::Ltt,
+    PlotType::LttAll,
+    PlotType::RootToTip,
+    PlotType::BrlenHistogram,
+    PlotType::DepthHistogram,
+    PlotType::AttrHistogram,
+];
+
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum AxisScaleType {
     #[default]
@@ -324,3 +324,175 @@ pub(super) fn draw_bounds(
         stroke_rect(st.plt_rect, STRK_3_GRN_50, f);
     }));
 }
+
+pub(super) fn draw_rtt(
+    plt: &PlotCnv,
+    st: &St,
+    rndr: &Renderer,
+    size: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(plt.cache_cnv_rtt.draw(rndr, size, |f| {
+        let Some(rtt) = &plt.rtt else {
+            return;
+        };
+        let data = &st.plot_data;
+        let w = st.plt_vs.w;
+        let h = st.plt_vs.h;
+
+        f.push_transform();
+        f.translate(st.plt_vs.trans);
+
+        // regression line, clipped to the plot area ---------------------------
+        let y_rel = |x: Float| {
+            (rtt.intercept + rtt.slope * x - data.y_min)
+                / (data.y_max - data.y_min)
+        };
+        let x_at =
+            |x_rel: Float| data.x_min + x_rel * (data.x_max - data.x_min);
+        let (y0, y1) = (y_rel(x_at(ZRO)), y_rel(x_at(ONE)));
+        let (mut x0, mut x1) = (ZRO, ONE);
+        if y0 != y1 {
+            let x_for_y = |y: Float| (y - y0) / (y1 - y0);
+            let (lo, hi) = match y0 < y1 {
+                true => (x_for_y(ZRO), x_for_y(ONE)),
+                false => (x_for_y(ONE), x_for_y(ZRO)),
+            };
+            (x0, x1) = (x0.max(lo), x1.min(hi));
+        }
+        if x0 < x1 && data.y_max > data.y_min {
+            let pt = |x_rel: Float| Point {
+                x: x_rel * w,
+                y: (ONE - y_rel(x_at(x_rel))) * h,
+            };
+            let path =
+                PathBuilder::new().move_to(pt(x0)).line_to(pt(x1)).build();
+            f.stroke(&path, STRK_RTT_FIT);
+        }
+
+        // points --------------------------------------------------------------
+        let points = plt.rtt_points(w, h);
+        let mut pb_pts: PathBuilder = PathBuilder::new();
+        let mut pb_sel: PathBuilder = PathBuilder::new();
+        for (pt, node_id) in points.iter().zip(&rtt.node_ids) {
+            match plt.rtt_sel_node_ids.contains(node_id) {
+                true => pb_sel = pb_sel.circle(*pt, RTT_POINT_R),
+                false => pb_pts = pb_pts.circle(*pt, RTT_POINT_R),
+            }
+        }
+        let path_pts = pb_pts.build();
+        f.fill(&path_pts, FILL_BLU_50);
+        f.stroke(&path_pts, STRK_1_BLU);
+        let path_sel = pb_sel.build();
+        f.fill(&path_sel, FILL_NODE_SELECTED);
+        f.stroke(&path_sel, STRK_NODE_SELECTED);
+
+        if let Some(pt) = st.hovered_rtt_point.and_then(|i| points.get(i)) {
+            let path =
+                PathBuilder::new().circle(*pt, RTT_POINT_R * TWO).build();
+            f.fill(&path, FILL_NODE_HOVER);
+            f.stroke(&path, STRK_NODE_HOVER);
+        }
+
+        f.pop_transform();
+    }));
+}
+
+pub(super) fn draw_ltt_set(
+    plt: &PlotCnv,
+    st: &St,
+    rndr: &Renderer,
+    size: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(plt.cache_cnv_ltt.draw(rndr, size, |f| {
+        let w = st.plt_vs.w;
+        let h = st.plt_vs.h;
+        let x_scale = plt.x_axis_scale_type;
+        let y_scale = plt.y_axis_scale_type;
+        let set = &plt.ltt_set;
+
+        f.with_save(|f| {
+            f.translate(st.plt_vs.trans);
+
+            let pb_upper = path_builder_ltt(&set.upper, x_scale, y_scale, w, h);
+            let pb_lower = path_builder_ltt(&set.lower, x_scale, y_scale, w, h);
+            let mut pb_envelope = pb_upper;
+            for pt in set.lower.plot_points.iter().rev() {
+                let x_rel = transformed_relative_value(
+                    pt.x, set.lower.x_min, set.lower.x_max, x_scale,
+                )
+                .unwrap_or(ZRO)
+                .clamp(ZRO, ONE);
+                let y_rel = transformed_relative_value(
+                    pt.y, set.lower.y_min, set.lower.y_max, y_scale,
+                )
+                .unwrap_or(ZRO)
+                .clamp(ZRO, ONE);
+                pb_envelope = pb_envelope
+                    .line_to(Point { x: x_rel * w, y: (ONE - y_rel) * h });
+            }
+            f.fill(&pb_envelope.close().build(), FILL_BLU_25);
+
+            for curve in &set.curves {
+                let pb = path_builder_ltt(curve, x_scale, y_scale, w, h);
+                f.stroke(&pb.build(), STRK_1_BLK_25);
+            }
+
+            let path_lower = pb_lower.build();
+            let path_upper =
+                path_builder_ltt(&set.upper, x_scale, y_scale, w, h).build();
+            f.stroke(&path_lower, STRK_1_BLU);
+            f.stroke(&path_upper, STRK_1_BLU);
+
+            let path_median =
+                path_builder_ltt(&set.median, x_scale, y_scale, w, h).build();
+            f.stroke(&path_median, STRK_4_WHT_75);
+            f.stroke(&path_median, STRK_2_BLU);
+        });
+    }));
+}
+
+pub(super) fn draw_histogram(
+    plt: &PlotCnv,
+    st: &St,
+    rndr: &Renderer,
+    size: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(plt.cache_cnv_hist.draw(rndr, size, |f| {
+        let data = &st.plot_data;
+        let bin_count = data.plot_points.len();
+        if bin_count == 0 || data.y_max <= ZRO {
+            return;
+        }
+        let w = st.plt_vs.w;
+        let h = st.plt_vs.h;
+        let bar_w = w / bin_count as Float;
+
+        let mut pb: PathBuilder = PathBuilder::new();
+        for (i, pt) in data.plot_points.iter().enumerate() {
+            if pt.y <= ZRO {
+                continue;
+            }
+            let y_rel = transformed_relative_value(
+                pt.y, data.y_min, data.y_max, plt.y_axis_scale_type,
+            )
+            .unwrap_or(ZRO)
+            .clamp(ZRO, ONE);
+            pb = pb.rectangle(Rectangle {
+                x: bar_w * i as Float,
+                y: (ONE - y_rel) * h,
+                width: bar_w,
+                height: y_rel * h,
+            });
+        }
+
+        f.with_save(|f| {
+            f.translate(st.plt_vs.trans);
+            let path = pb.build();
+            f.fill(&path, FILL_BLU_50);
+            f.stroke(&path, STRK_1_BLU);
+        });
+    }));
+}
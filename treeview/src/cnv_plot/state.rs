@@ -17,7 +17,9 @@ pub struct St {
     pub(super) lab_offset: Float,
     pub(super) ticks_x: Vec<Tick>,
     pub(super) ticks_y: Vec<Tick>,
-    pub(super) ltt_plot_data: PlotData,
+    pub(super) plot_type: PlotType,
+    pub(super) plot_data: PlotData,
+    pub(super) hovered_rtt_point: Option<usize>,
     pub(super) x_axis_scale_type: AxisScaleType,
     pub(super) y_axis_scale_type: AxisScaleType,
     pub(super) tre_unit: TreUnit,
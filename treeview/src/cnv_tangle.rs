@@ -0,0 +1,483 @@
+mod draw;
+mod layout;
+mod state;
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::*;
+use draw::*;
+pub(crate) use layout::TangleLayout;
+use layout::untangle;
+use state::St;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) enum TangleSide {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Placement of the two facing trees, their tip labels and the connecting
+/// lines within the tanglegram canvas.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct TangleGeom {
+    pub(crate) x_l: Float,
+    pub(crate) x_r: Float,
+    pub(crate) y0: Float,
+    pub(crate) h: Float,
+    pub(crate) tre_w: Float,
+    pub(crate) lab_w: Float,
+    pub(crate) lab_size: Float,
+    pub(crate) lab_offset: Float,
+    pub(crate) draw_labs: bool,
+}
+
+impl TangleGeom {
+    pub(crate) fn new(
+        w: Float,
+        h: Float,
+        lab_size: Float,
+        lay_l: &TangleLayout,
+        lay_r: &TangleLayout,
+        draw_labs: bool,
+    ) -> Self {
+        let padd = lab_size.max(SF * TEN);
+        let inner_w = (w - padd * TWO).max(ZRO);
+        let inner_h = (h - padd * TWO).max(ZRO);
+        let tip_count = lay_l.tip_count().max(lay_r.tip_count());
+        let nchar = lay_l.max_tip_lab_nchar().max(lay_r.max_tip_lab_nchar());
+        let draw_labs = draw_labs && tip_count as Float * lab_size <= inner_h;
+        let lab_offset = lab_size / TWO;
+        let lab_w = match draw_labs {
+            true => (nchar as Float * lab_size * 6e-1 + lab_offset * TWO)
+                .min(inner_w / 6e0),
+            false => ZRO,
+        };
+        let gap = inner_w / 5e0;
+        let tre_w = ((inner_w - gap) / TWO - lab_w).max(ZRO);
+        Self {
+            x_l: padd,
+            x_r: padd + inner_w,
+            y0: padd,
+            h: inner_h,
+            tre_w,
+            lab_w,
+            lab_size,
+            lab_offset,
+            draw_labs,
+        }
+    }
+
+    pub(crate) fn point(&self, side: TangleSide, x: Float, y: Float) -> Point {
+        let y = self.y0 + y * self.h;
+        match side {
+            TangleSide::Left => Point { x: self.x_l + x * self.tre_w, y },
+            TangleSide::Right => Point { x: self.x_r - x * self.tre_w, y },
+        }
+    }
+
+    fn line_x(&self, side: TangleSide) -> Float {
+        match side {
+            TangleSide::Left => self.x_l + self.tre_w + self.lab_w,
+            TangleSide::Right => self.x_r - self.tre_w - self.lab_w,
+        }
+    }
+
+    pub(crate) fn lab_nchar_max(&self) -> usize {
+        ((self.lab_w - self.lab_offset * TWO) / (self.lab_size * 6e-1)).floor()
+            as usize
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct TangleCnv {
+    pub(super) cfg: Rc<TreeViewConfig>,
+    // -------------------------------------------------------------------------
+    pub(super) tre_l: Option<Rc<TreeState>>,
+    pub(super) tre_r: Option<Rc<TreeState>>,
+    pub(super) lay_l: TangleLayout,
+    pub(super) lay_r: TangleLayout,
+    pub(super) crossings: usize,
+    sig: u64,
+    // -------------------------------------------------------------------------
+    pub(super) lab_size: Float,
+    // -------------------------------------------------------------------------
+    pub(super) cache_cnv_edges: CnvCache,
+    pub(super) cache_cnv_lines: CnvCache,
+    pub(super) cache_cnv_labs: CnvCache,
+    pub(super) cache_cnv_sel_nodes: CnvCache,
+    pub(super) cache_cnv_hovered_node: CnvCache,
+}
+
+impl Program<TvMsg> for TangleCnv {
+    type State = St;
+
+    fn mouse_interaction(
+        &self,
+        st: &St,
+        _bnds: Rectangle,
+        _crsr: Cursor,
+    ) -> MouseInteraction {
+        match st.hovered_node {
+            Some(_) => MouseInteraction::Pointer,
+            None => MouseInteraction::default(),
+        }
+    }
+
+    fn update(
+        &self,
+        st: &mut St,
+        ev: &Event,
+        bnds: Rectangle,
+        crsr: Cursor,
+    ) -> Option<Action<TvMsg>> {
+        let mut action: Option<Action<TvMsg>> = None;
+
+        if st.bnds.width != bnds.width
+            || st.bnds.height != bnds.height
+            || st.sig != self.sig
+            || st.lab_size != self.lab_size
+            || st.draw_labs != self.cfg.draw_labs_tip
+        {
+            st.bnds = bnds;
+            st.sig = self.sig;
+            st.lab_size = self.lab_size;
+            st.draw_labs = self.cfg.draw_labs_tip;
+            st.node_radius = SF * 3e0;
+            st.geom = TangleGeom::new(
+                bnds.width, bnds.height, self.lab_size, &self.lay_l,
+                &self.lay_r, st.draw_labs,
+            );
+            st.hovered_node = None;
+            self.clear_caches_cnv_all();
+        }
+
+        if let Event::Mouse(mouse_ev) = ev {
+            match mouse_ev {
+                MouseEvent::CursorMoved { position: _ } => {
+                    let hovered_node_prev = st.hovered_node;
+                    st.hovered_node = crsr.position_in(bnds).and_then(|pt| {
+                        self.node_at(&st.geom, pt, st.node_radius + SF * 5e0)
+                    });
+                    if st.hovered_node != hovered_node_prev {
+                        self.cache_cnv_hovered_node.clear();
+                        action = Some(Action::request_redraw());
+                    }
+                }
+                MouseEvent::CursorLeft => {
+                    if st.hovered_node.take().is_some() {
+                        self.cache_cnv_hovered_node.clear();
+                        action = Some(Action::request_redraw());
+                    }
+                }
+                MouseEvent::ButtonPressed(MouseButton::Left) => {
+                    if let Some((side, idx)) = st.hovered_node
+                        && let Some(node_id) = self.sel_tre_node_id(side, idx)
+                    {
+                        action = Some(Action::publish(
+                            match self.cfg.selection_lock {
+                                true => TvMsg::SelectDeselectNode(node_id),
+                                false => {
+                                    TvMsg::SelectDeselectNodeExclusive(node_id)
+                                }
+                            },
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        action
+    }
+
+    fn draw(
+        &self,
+        st: &St,
+        rndr: &Renderer,
+        _thm: &Theme,
+        bnds: Rectangle,
+        _crsr: Cursor,
+    ) -> Vec<Geometry> {
+        let mut geoms: Vec<Geometry> = Vec::new();
+        if self.tre_l.is_none() || self.tre_r.is_none() {
+            return geoms;
+        }
+        // ---------------------------------------------------------------------
+        let t = timer("tangle_lines");
+        draw_lines(self, st, rndr, bnds.size(), &mut geoms);
+        t.finish();
+
+        let t = timer("tangle_sel_nodes");
+        draw_selected_nodes(self, st, rndr, bnds.size(), &mut geoms);
+        t.finish();
+
+        let t = timer("tangle_edges");
+        draw_edges(self, st, rndr, bnds.size(), &mut geoms);
+        t.finish();
+
+        if st.geom.draw_labs {
+            let t = timer("tangle_labs");
+            draw_labs(self, st, rndr, bnds.size(), &mut geoms);
+            t.finish();
+        }
+
+        let t = timer("tangle_hovered_node");
+        draw_hovered_node(self, st, rndr, bnds.size(), &mut geoms);
+        t.finish();
+        // ---------------------------------------------------------------------
+        geoms
+    }
+}
+
+impl TangleCnv {
+    pub(super) fn new(cfg: Rc<TreeViewConfig>) -> Self {
+        Self { cfg, lab_size: SF * TEN, ..Default::default() }
+    }
+
+    /// Points the canvas at a new pair of trees. The layouts are only rebuilt
+    /// (and untangled) when the topology or branch lengths have changed.
+    pub(super) fn set_trees(
+        &mut self,
+        tre_l: Option<Rc<TreeState>>,
+        tre_r: Option<Rc<TreeState>>,
+    ) {
+        let sig = tangle_signature(tre_l.as_deref(), tre_r.as_deref());
+        self.tre_l = tre_l;
+        self.tre_r = tre_r;
+        if sig != self.sig {
+            self.sig = sig;
+            self.update_layouts();
+            self.clear_caches_cnv_all();
+        } else {
+            self.clear_cache_cnv_sel_nodes();
+        }
+    }
+
+    /// Drops the references to the tree states so that they can be mutated.
+    pub(super) fn release_trees(&mut self) {
+        self.tre_l = None;
+        self.tre_r = None;
+    }
+
+    fn update_layouts(&mut self) {
+        if let Some(tre_l) = &self.tre_l
+            && let Some(tre_r) = &self.tre_r
+            && let Some(edges_l) = tre_l.edges()
+            && let Some(edges_r) = tre_r.edges()
+        {
+            let mut lay_l = TangleLayout::from_edges(edges_l);
+            let mut lay_r = TangleLayout::from_edges(edges_r);
+            self.crossings = untangle(&mut lay_l, &mut lay_r);
+            self.lay_l = lay_l;
+            self.lay_r = lay_r;
+        } else {
+            self.crossings = 0;
+            self.lay_l = TangleLayout::default();
+            self.lay_r = TangleLayout::default();
+        }
+    }
+
+    fn node_at(
+        &self,
+        geom: &TangleGeom,
+        pt: Point,
+        max_dist: Float,
+    ) -> Option<(TangleSide, usize)> {
+        [(TangleSide::Left, &self.lay_l), (TangleSide::Right, &self.lay_r)]
+            .into_iter()
+            .flat_map(|(side, lay)| {
+                lay.nodes.iter().enumerate().map(move |(idx, nd)| {
+                    (side, idx, pt.distance(geom.point(side, nd.x1, nd.y)))
+                })
+            })
+            .filter(|&(_, _, dist)| dist <= max_dist)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(side, idx, _)| (side, idx))
+    }
+
+    /// Node id in the selected (left) tree for a node on either side. Nodes on
+    /// the right are matched to the left tree by the tip labels they subtend.
+    fn sel_tre_node_id(&self, side: TangleSide, idx: usize) -> Option<NodeId> {
+        match side {
+            TangleSide::Left => self.lay_l.nodes.get(idx),
+            TangleSide::Right => {
+                let clade_key = self.lay_r.nodes.get(idx)?.clade_key;
+                self.lay_l
+                    .idx_for_clade_key(clade_key)
+                    .map(|idx| &self.lay_l.nodes[idx])
+            }
+        }
+        .map(|nd| nd.node_id)
+    }
+
+    /// Indexes of the selected and found nodes on both sides.
+    pub(super) fn highlighted_idxs(&self) -> (Vec<usize>, Vec<usize>) {
+        let Some(tre_l) = &self.tre_l else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let sel_node_ids = tre_l.sel_node_ids();
+        let found_node_ids = tre_l.found_node_ids();
+
+        let idxs_l: Vec<usize> = self
+            .lay_l
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, nd)| {
+                sel_node_ids.contains(&nd.node_id)
+                    || found_node_ids.contains(&nd.node_id)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let clade_keys: HashSet<(u64, usize)> =
+            idxs_l.iter().map(|&idx| self.lay_l.nodes[idx].clade_key).collect();
+
+        let idxs_r: Vec<usize> = self
+            .lay_r
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, nd)| clade_keys.contains(&nd.clade_key))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        (idxs_l, idxs_r)
+    }
+
+    pub(super) fn clear_cache_cnv_edges(&self) {
+        self.cache_cnv_edges.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_lines(&self) {
+        self.cache_cnv_lines.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_labs(&self) {
+        self.cache_cnv_labs.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_sel_nodes(&self) {
+        self.cache_cnv_sel_nodes.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_hovered_node(&self) {
+        self.cache_cnv_hovered_node.clear();
+    }
+
+    pub(super) fn clear_caches_cnv_all(&self) {
+        self.clear_cache_cnv_edges();
+        self.clear_cache_cnv_lines();
+        self.clear_cache_cnv_labs();
+        self.clear_cache_cnv_sel_nodes();
+        self.clear_cache_cnv_hovered_node();
+    }
+}
+
+pub(crate) fn path_tangle_edges(
+    lay: &TangleLayout,
+    geom: &TangleGeom,
+    side: TangleSide,
+) -> IcedPath {
+    let mut pb: PathBuilder = PathBuilder::new();
+    for nd in &lay.nodes {
+        pb = pb
+            .move_to(geom.point(side, nd.x1, nd.y))
+            .line_to(geom.point(side, nd.x0, nd.y));
+        if !nd.is_tip() {
+            pb = pb
+                .move_to(geom.point(side, nd.x1, nd.y_min))
+                .line_to(geom.point(side, nd.x1, nd.y_max));
+        }
+    }
+    pb.build()
+}
+
+/// Lines joining tips with matching labels. When `tips_l` is given, only the
+/// lines starting at those tips of the left tree are included.
+pub(crate) fn path_tangle_lines(
+    lay_l: &TangleLayout,
+    lay_r: &TangleLayout,
+    geom: &TangleGeom,
+    tips_l: Option<&[usize]>,
+) -> IcedPath {
+    let y_r = lay_r.tip_y_by_label();
+    let x_l = geom.line_x(TangleSide::Left);
+    let x_r = geom.line_x(TangleSide::Right);
+    let mut pb: PathBuilder = PathBuilder::new();
+    for &idx in tips_l.unwrap_or(&lay_l.tips) {
+        let nd = &lay_l.nodes[idx];
+        if let Some(lab) = nd.label.as_deref()
+            && let Some(&y) = y_r.get(lab)
+        {
+            let p_l = geom.point(TangleSide::Left, ZRO, nd.y);
+            let p_r = geom.point(TangleSide::Right, ZRO, y);
+            pb = pb
+                .move_to(Point { x: x_l, y: p_l.y })
+                .line_to(Point { x: x_r, y: p_r.y });
+        }
+    }
+    pb.build()
+}
+
+/// Tip labels, trimmed to fit, anchored at the tip they belong to.
+pub(crate) fn tangle_labels(
+    lay: &TangleLayout,
+    geom: &TangleGeom,
+    side: TangleSide,
+) -> Vec<(String, Point)> {
+    let nchar = geom.lab_nchar_max();
+    lay.tips
+        .iter()
+        .filter_map(|&idx| {
+            let nd = &lay.nodes[idx];
+            nd.label.as_ref().map(|lab| {
+                (ellipsize_unicode(lab, nchar), geom.point(side, nd.x1, nd.y))
+            })
+        })
+        .collect()
+}
+
+/// Tips descending from the given nodes.
+pub(crate) fn tangle_tips_under(
+    lay: &TangleLayout,
+    idxs: &[usize],
+) -> Vec<usize> {
+    let mut tips: Vec<usize> = Vec::new();
+    let mut seen: HashSet<usize> = HashSet::new();
+    let mut stack: Vec<usize> = idxs.to_vec();
+    while let Some(idx) = stack.pop() {
+        if !seen.insert(idx) {
+            continue;
+        }
+        let nd = &lay.nodes[idx];
+        match nd.is_tip() {
+            true => tips.push(idx),
+            false => stack.extend(&nd.children),
+        }
+    }
+    tips
+}
+
+fn tangle_signature(
+    tre_l: Option<&TreeState>,
+    tre_r: Option<&TreeState>,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for ts in [tre_l, tre_r] {
+        match ts.and_then(|ts| ts.edges().map(|edges| (ts.id(), edges))) {
+            Some((id, edges)) => {
+                id.hash(&mut hasher);
+                for edge in edges {
+                    edge.node_id.hash(&mut hasher);
+                    edge.parent_node_id.hash(&mut hasher);
+                    edge.x1.to_bits().hash(&mut hasher);
+                }
+            }
+            None => 0_usize.hash(&mut hasher),
+        }
+    }
+    hasher.finish()
+}
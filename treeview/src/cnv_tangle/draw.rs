@@ -0,0 +1,147 @@
+use super::*;
+use crate::cnv_utils::*;
+
+pub(super) fn draw_edges(
+    tc: &TangleCnv,
+    st: &St,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tc.cache_cnv_edges.draw(rndr, sz, |f| {
+        f.stroke(
+            &path_tangle_edges(&tc.lay_l, &st.geom, TangleSide::Left),
+            STRK_EDGE,
+        );
+        f.stroke(
+            &path_tangle_edges(&tc.lay_r, &st.geom, TangleSide::Right),
+            STRK_EDGE,
+        );
+    }));
+}
+
+pub(super) fn draw_lines(
+    tc: &TangleCnv,
+    st: &St,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tc.cache_cnv_lines.draw(rndr, sz, |f| {
+        f.stroke(
+            &path_tangle_lines(&tc.lay_l, &tc.lay_r, &st.geom, None),
+            STRK_TANGLE_LINE,
+        );
+    }));
+}
+
+pub(super) fn draw_labs(
+    tc: &TangleCnv,
+    st: &St,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tc.cache_cnv_labs.draw(rndr, sz, |f| {
+        for (side, lay) in
+            [(TangleSide::Left, &tc.lay_l), (TangleSide::Right, &tc.lay_r)]
+        {
+            let (offset, align_x) = match side {
+                TangleSide::Left => (st.geom.lab_offset, TextAlignment::Left),
+                TangleSide::Right => {
+                    (-st.geom.lab_offset, TextAlignment::Right)
+                }
+            };
+            for (text, pt) in tangle_labels(lay, &st.geom, side) {
+                let mut text = lab_text(
+                    text,
+                    Point { x: pt.x + offset, y: pt.y },
+                    st.geom.lab_size,
+                    TEMPLATE_TXT_LAB_TIP,
+                    false,
+                );
+                text.align_x = align_x;
+                f.fill_text(text);
+            }
+        }
+    }));
+}
+
+pub(super) fn draw_selected_nodes(
+    tc: &TangleCnv,
+    st: &St,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tc.cache_cnv_sel_nodes.draw(rndr, sz, |f| {
+        let (idxs_l, idxs_r) = tc.highlighted_idxs();
+        if idxs_l.is_empty() {
+            return;
+        }
+
+        let tips_l = tangle_tips_under(&tc.lay_l, &idxs_l);
+        f.stroke(
+            &path_tangle_lines(&tc.lay_l, &tc.lay_r, &st.geom, Some(&tips_l)),
+            STRK_TANGLE_LINE_SEL,
+        );
+
+        let mut points: Vec<Point> = Vec::new();
+        for (side, lay, idxs) in [
+            (TangleSide::Left, &tc.lay_l, &idxs_l),
+            (TangleSide::Right, &tc.lay_r, &idxs_r),
+        ] {
+            points.extend(idxs.iter().map(|&idx| {
+                let nd = &lay.nodes[idx];
+                st.geom.point(side, nd.x1, nd.y)
+            }));
+        }
+        draw_nodes(
+            &points,
+            st.node_radius + SF * 3e0,
+            STRK_NODE_SELECTED,
+            FILL_NODE_SELECTED,
+            f,
+        );
+    }));
+}
+
+pub(super) fn draw_hovered_node(
+    tc: &TangleCnv,
+    st: &St,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tc.cache_cnv_hovered_node.draw(rndr, sz, |f| {
+        if let Some((side, idx)) = st.hovered_node {
+            let nd = match side {
+                TangleSide::Left => &tc.lay_l.nodes[idx],
+                TangleSide::Right => &tc.lay_r.nodes[idx],
+            };
+            draw_nodes(
+                &[st.geom.point(side, nd.x1, nd.y)],
+                st.node_radius + SF * 4e0,
+                STRK_NODE_HOVER,
+                FILL_NODE_HOVER,
+                f,
+            );
+        }
+    }));
+}
+
+fn draw_nodes(
+    points: &[Point],
+    radius: Float,
+    stroke: CnvStrk,
+    fill: CnvFill,
+    f: &mut Frame,
+) {
+    let mut pb = PathBuilder::new();
+    for &pt in points {
+        pb = pb.circle(pt, radius);
+    }
+    let path = pb.build();
+    f.fill(&path, fill);
+    f.stroke(&path, stroke);
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::*;
+
+const UNTANGLE_PASSES: usize = 8;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TangleNode {
+    pub(crate) node_id: NodeId,
+    pub(crate) label: Option<String>,
+    pub(crate) x0: Float,
+    pub(crate) x1: Float,
+    pub(crate) y: Float,
+    pub(crate) y_min: Float,
+    pub(crate) y_max: Float,
+    /// Order-independent key of the set of tip labels subtended by the node;
+    /// used to find the same clade in the other tree.
+    pub(crate) clade_key: (u64, usize),
+    pub(crate) children: Vec<usize>,
+}
+
+impl TangleNode {
+    pub(crate) fn is_tip(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// Rectangular layout of a tree that can be reordered independently of the
+/// `TreeState` it was built from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TangleLayout {
+    pub(crate) nodes: Vec<TangleNode>,
+    pub(crate) roots: Vec<usize>,
+    pub(crate) tips: Vec<usize>,
+}
+
+impl TangleLayout {
+    pub(crate) fn from_edges(edges: &[Edge]) -> Self {
+        let mut nodes: Vec<TangleNode> = Vec::with_capacity(edges.len() + 1);
+        let mut idx_for_id: HashMap<NodeId, usize> =
+            HashMap::with_capacity(edges.len() + 1);
+
+        for edge in edges {
+            _ = idx_for_id.insert(edge.node_id, nodes.len());
+            nodes.push(TangleNode {
+                node_id: edge.node_id,
+                label: edge.label.as_deref().map(String::from),
+                x0: edge.x0 as Float,
+                x1: edge.x1 as Float,
+                y: edge.y as Float,
+                ..Default::default()
+            });
+        }
+
+        let mut roots: Vec<usize> = Vec::new();
+        for edge in edges {
+            let idx = idx_for_id[&edge.node_id];
+            if edge.parent_node_id == edge.node_id {
+                roots.push(idx);
+                continue;
+            }
+
+            // Unrooted trees have no edge for the first node.
+            let parent_idx = match idx_for_id.get(&edge.parent_node_id) {
+                Some(&parent_idx) => parent_idx,
+                None => {
+                    let parent_idx = nodes.len();
+                    _ = idx_for_id.insert(edge.parent_node_id, parent_idx);
+                    nodes.push(TangleNode {
+                        node_id: edge.parent_node_id,
+                        x0: edge.x0 as Float,
+                        x1: edge.x0 as Float,
+                        ..Default::default()
+                    });
+                    roots.push(parent_idx);
+                    parent_idx
+                }
+            };
+            nodes[parent_idx].children.push(idx);
+        }
+
+        let ys: Vec<Float> = nodes.iter().map(|nd| nd.y).collect();
+        for nd in &mut nodes {
+            nd.children.sort_by(|&a, &b| ys[a].total_cmp(&ys[b]));
+        }
+
+        let mut layout = Self { nodes, roots, tips: Vec::new() };
+        layout.assign_clade_keys();
+        layout.assign_y();
+        layout
+    }
+
+    fn preorder(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<usize> = self.roots.iter().rev().copied().collect();
+        while let Some(idx) = stack.pop() {
+            order.push(idx);
+            stack.extend(self.nodes[idx].children.iter().rev());
+        }
+        order
+    }
+
+    fn assign_clade_keys(&mut self) {
+        for idx in self.preorder().into_iter().rev() {
+            let nd = &self.nodes[idx];
+            let clade_key = match nd.is_tip() {
+                true => (label_hash(nd.label.as_deref()), 1),
+                false => nd.children.iter().fold((0, 0), |(h, n), &c| {
+                    let (h_c, n_c) = self.nodes[c].clade_key;
+                    (h.wrapping_add(h_c), n + n_c)
+                }),
+            };
+            self.nodes[idx].clade_key = clade_key;
+        }
+    }
+
+    fn assign_y(&mut self) {
+        let preorder = self.preorder();
+        self.tips = preorder
+            .iter()
+            .copied()
+            .filter(|&idx| self.nodes[idx].is_tip())
+            .collect();
+
+        let denom = (self.tips.len().max(2) - 1) as Float;
+        for (i, &idx) in self.tips.iter().enumerate() {
+            let nd = &mut self.nodes[idx];
+            nd.y = i as Float / denom;
+            nd.y_min = nd.y;
+            nd.y_max = nd.y;
+        }
+
+        for &idx in preorder.iter().rev() {
+            let children = &self.nodes[idx].children;
+            if let Some(&first) = children.first()
+                && let Some(&last) = children.last()
+            {
+                let y_min = self.nodes[first].y;
+                let y_max = self.nodes[last].y;
+                let nd = &mut self.nodes[idx];
+                nd.y_min = y_min;
+                nd.y_max = y_max;
+                nd.y = (y_min + y_max) / TWO;
+            }
+        }
+    }
+
+    pub(crate) fn tip_y_by_label(&self) -> HashMap<&str, Float> {
+        self.tips
+            .iter()
+            .filter_map(|&idx| {
+                let nd = &self.nodes[idx];
+                nd.label.as_deref().map(|lab| (lab, nd.y))
+            })
+            .collect()
+    }
+
+    pub(crate) fn tip_count(&self) -> usize {
+        self.tips.len()
+    }
+
+    pub(crate) fn max_tip_lab_nchar(&self) -> usize {
+        self.tips
+            .iter()
+            .filter_map(|&idx| self.nodes[idx].label.as_ref())
+            .map(|lab| lab.chars().count())
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn idx_for_clade_key(
+        &self,
+        clade_key: (u64, usize),
+    ) -> Option<usize> {
+        self.nodes.iter().position(|nd| nd.clade_key == clade_key)
+    }
+
+    /// Rotates the children of every node so that they are ordered by the mean
+    /// position of their matching tips in `fixed`.
+    fn rotated_against(&self, fixed: &TangleLayout) -> TangleLayout {
+        let fixed_y = fixed.tip_y_by_label();
+        let mut bary: Vec<Option<(Float, usize)>> =
+            vec![None; self.nodes.len()];
+
+        for idx in self.preorder().into_iter().rev() {
+            let nd = &self.nodes[idx];
+            bary[idx] = match nd.is_tip() {
+                true => nd
+                    .label
+                    .as_deref()
+                    .and_then(|lab| fixed_y.get(lab))
+                    .map(|&y| (y, 1)),
+                false => nd.children.iter().filter_map(|&c| bary[c]).fold(
+                    None,
+                    |acc, (s, n)| {
+                        let (s_acc, n_acc) = acc.unwrap_or((ZRO, 0));
+                        Some((s_acc + s, n_acc + n))
+                    },
+                ),
+            };
+        }
+
+        let mean = |idx: usize| bary[idx].map(|(s, n)| s / n as Float);
+
+        let mut rotated = self.clone();
+        for (idx, nd) in rotated.nodes.iter_mut().enumerate() {
+            let fallback = mean(idx).unwrap_or(Float::INFINITY);
+            nd.children.sort_by(|&a, &b| {
+                mean(a)
+                    .unwrap_or(fallback)
+                    .total_cmp(&mean(b).unwrap_or(fallback))
+            });
+        }
+        rotated.assign_y();
+        rotated
+    }
+}
+
+/// Alternately rotates the nodes of both trees to reduce the number of
+/// crossing lines between matching tips. Returns the remaining crossing count.
+pub(crate) fn untangle(
+    lay_l: &mut TangleLayout,
+    lay_r: &mut TangleLayout,
+) -> usize {
+    let mut crossings = crossing_count(lay_l, lay_r);
+    for _ in 0..UNTANGLE_PASSES {
+        if crossings == 0 {
+            break;
+        }
+
+        let crossings_prev = crossings;
+
+        let rotated_r = lay_r.rotated_against(lay_l);
+        let crossings_r = crossing_count(lay_l, &rotated_r);
+        if crossings_r < crossings {
+            *lay_r = rotated_r;
+            crossings = crossings_r;
+        }
+
+        let rotated_l = lay_l.rotated_against(lay_r);
+        let crossings_l = crossing_count(&rotated_l, lay_r);
+        if crossings_l < crossings {
+            *lay_l = rotated_l;
+            crossings = crossings_l;
+        }
+
+        if crossings == crossings_prev {
+            break;
+        }
+    }
+    crossings
+}
+
+pub(crate) fn crossing_count(
+    lay_l: &TangleLayout,
+    lay_r: &TangleLayout,
+) -> usize {
+    let y_r = lay_r.tip_y_by_label();
+    let mut pairs: Vec<(Float, Float)> = lay_l
+        .tips
+        .iter()
+        .filter_map(|&idx| {
+            let nd = &lay_l.nodes[idx];
+            nd.label.as_deref().and_then(|lab| y_r.get(lab)).map(|&y| (nd.y, y))
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    let mut ys: Vec<Float> = pairs.into_iter().map(|(_, y)| y).collect();
+    count_inversions(&mut ys)
+}
+
+fn count_inversions(v: &mut [Float]) -> usize {
+    if v.len() < 2 {
+        return 0;
+    }
+
+    let mid = v.len() / 2;
+    let mut count =
+        count_inversions(&mut v[..mid]) + count_inversions(&mut v[mid..]);
+
+    let mut merged: Vec<Float> = Vec::with_capacity(v.len());
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < v.len() {
+        if v[i] <= v[j] {
+            merged.push(v[i]);
+            i += 1;
+        } else {
+            merged.push(v[j]);
+            j += 1;
+            count += mid - i;
+        }
+    }
+    merged.extend_from_slice(&v[i..mid]);
+    merged.extend_from_slice(&v[j..]);
+    v.copy_from_slice(&merged);
+    count
+}
+
+fn label_hash(label: Option<&str>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    label.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,13 @@
+use super::{TangleGeom, TangleSide};
+use crate::*;
+
+#[derive(Debug, Default)]
+pub struct St {
+    pub(super) bnds: Rectangle<Float>,
+    pub(super) geom: TangleGeom,
+    pub(super) sig: u64,
+    pub(super) lab_size: Float,
+    pub(super) draw_labs: bool,
+    pub(super) hovered_node: Option<(TangleSide, usize)>,
+    pub(super) node_radius: Float,
+}
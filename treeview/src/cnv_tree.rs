@@ -2,6 +2,7 @@ mod draw;
 mod program;
 mod state;
 
+use crate::tracks::{tracks_header_h, tracks_w};
 use crate::*;
 use state::St;
 
@@ -18,6 +19,7 @@ pub(super) struct TreeCnv {
     cache_cnv_hovered_node: CnvCache,
     cache_cnv_cursor_line: CnvCache,
     cache_cnv_palette: CnvCache,
+    cache_cnv_legend: CnvCache,
     // -------------------------------------------------------------------------
     pub(super) padd_l: Float,
     pub(super) padd_r: Float,
@@ -53,6 +55,13 @@ pub(super) struct TreeCnv {
     // -------------------------------------------------------------------------
     pub(super) clade_highlights_w: Float,
     // -------------------------------------------------------------------------
+    pub(super) tracks: Vec<Track>,
+    pub(super) tracks_offset: Float,
+    pub(super) node_glyphs: NodeGlyphs,
+    /// Node attribute whose changes along the branches are colored.
+    pub(super) state_change_attr: Option<String>,
+    pub(super) placements_view: PlacementsView,
+    // -------------------------------------------------------------------------
     pub(super) opn_angle: Float,
     pub(super) rot_angle: Float,
     // -------------------------------------------------------------------------
@@ -141,6 +150,12 @@ impl TreeCnv {
             // -----------------------------------------------------------------
             clade_highlights_w: ZRO,
             // -----------------------------------------------------------------
+            tracks: Vec::new(),
+            tracks_offset: ZRO,
+            node_glyphs: NodeGlyphs::default(),
+            state_change_attr: None,
+            placements_view: PlacementsView::default(),
+            // -----------------------------------------------------------------
             cache_cnv_bnds: Default::default(),
             cache_cnv_tip_lab_w_resize_area: Default::default(),
             cache_cnv_scale_bar: Default::default(),
@@ -148,6 +163,7 @@ impl TreeCnv {
             cache_cnv_hovered_node: Default::default(),
             cache_cnv_cursor_line: Default::default(),
             cache_cnv_palette: Default::default(),
+            cache_cnv_legend: Default::default(),
             // -----------------------------------------------------------------
             crsr_x_rel: None,
             // -----------------------------------------------------------------
@@ -209,6 +225,10 @@ impl TreeCnv {
         self.cache_cnv_height_axis.clear();
     }
 
+    pub(super) fn clear_cache_cnv_legend(&self) {
+        self.cache_cnv_legend.clear();
+    }
+
     pub(super) fn clear_caches_cnv_all(&self) {
         self.clear_cache_cnv_bnds();
         self.clear_cache_cnv_tip_lab_w_resize_area();
@@ -217,6 +237,26 @@ impl TreeCnv {
         self.clear_cache_cnv_hovered_node();
         self.clear_cache_cnv_scale_bar();
         self.clear_cache_cnv_height_axis();
+        self.clear_cache_cnv_legend();
+    }
+
+    /// Space kept above the first and below the last tip in the phylogram
+    /// layout, in addition to the canvas padding.
+    pub(super) fn phygrm_padd_t_b(&self) -> (Float, Float) {
+        let mut offset_due_to_brnch_lab = ZRO;
+        let mut offset_due_to_tip_lab = ZRO;
+        if self.draw_labs_allowed && self.cfg.draw_labs_tip {
+            offset_due_to_tip_lab = self.lab_size_tip / TWO;
+        }
+        if self.draw_labs_allowed && self.cfg.draw_labs_brnch {
+            offset_due_to_brnch_lab =
+                self.lab_size_brnch + self.lab_offset_brnch.abs();
+        }
+        let top = (offset_due_to_tip_lab)
+            .max(offset_due_to_brnch_lab)
+            .max(tracks_header_h(&self.tracks, self.lab_size_tip));
+        let bottom = offset_due_to_tip_lab;
+        (top, bottom)
     }
 
     pub(super) fn calc_tre_vs(
@@ -226,7 +266,7 @@ impl TreeCnv {
         is_rooted: bool,
         has_clade_highlights: bool,
         text_w_tip: &mut TextWidth<'static>,
-    ) -> (RectVals<Float>, Float) {
+    ) -> (RectVals<Float>, Float, Float) {
         let tre_vs_prelim =
             cnv_vs.padded(self.padd_l, self.padd_r, self.padd_t, self.padd_b);
         let mut tip_w: Float = ZRO;
@@ -244,21 +284,13 @@ impl TreeCnv {
         if has_clade_highlights && self.draw_clade_highlights {
             offset_due_to_clade_highlight = self.clade_highlights_w;
         }
+        let tracks_offset = tip_w + offset_due_to_clade_highlight;
+        let tracks_total_w = tracks_w(&self.tracks, self.lab_size_tip);
         let mut root_len = ZRO;
         match self.cfg.tre_sty {
             TreSty::PhyGrm => {
-                let mut offset_due_to_brnch_lab = ZRO;
-                let mut offset_due_to_tip_lab = ZRO;
-                if self.draw_labs_allowed && self.cfg.draw_labs_tip {
-                    offset_due_to_tip_lab = self.lab_size_tip / TWO;
-                }
-                if self.draw_labs_allowed && self.cfg.draw_labs_brnch {
-                    offset_due_to_brnch_lab =
-                        self.lab_size_brnch + self.lab_offset_brnch.abs();
-                }
-                let right = tip_w + offset_due_to_clade_highlight;
-                let top = (offset_due_to_tip_lab).max(offset_due_to_brnch_lab);
-                let bottom = offset_due_to_tip_lab;
+                let right = tracks_offset + tracks_total_w;
+                let (top, bottom) = self.phygrm_padd_t_b();
                 let mut tre_vs = tre_vs_prelim.padded(ZRO, right, top, bottom);
                 if is_rooted {
                     root_len = tre_vs.w * self.root_len_frac;
@@ -276,15 +308,15 @@ impl TreeCnv {
                     tre_vs.cntr = Vector { x: tre_vs.cntr_x, y: tre_vs.cntr_y };
                 }
 
-                (tre_vs, root_len)
+                (tre_vs, root_len, tracks_offset)
             }
             TreSty::Fan => {
-                let p = tip_w + offset_due_to_clade_highlight;
+                let p = tracks_offset + tracks_total_w;
                 let tre_vs = tre_vs_prelim.padded(p, p, p, p);
                 if is_rooted {
                     root_len = tre_vs.radius_min * self.root_len_frac;
                 }
-                (tre_vs, root_len)
+                (tre_vs, root_len, tracks_offset)
             }
         }
     }
@@ -2,7 +2,12 @@ use super::St;
 use crate::cnv_plot::AxisDataType;
 use crate::cnv_utils::*;
 use crate::edge_utils::*;
+use crate::legend::Legend;
+use crate::node_glyphs::{glyph_legend, glyph_shapes};
+use crate::parsimony::{state_change_legend, state_change_paths};
 use crate::path_builders::*;
+use crate::placements::{placement_legend, placement_shapes};
+use crate::tracks::{TracksGeom, track_legend, track_shapes};
 use crate::*;
 
 pub(super) fn draw_bounds(
@@ -395,6 +400,157 @@ pub(super) fn draw_hovered_node(
     }));
 }
 
+pub(super) fn draw_tracks(
+    tc: &TreeCnv,
+    st: &St,
+    tst: &TreeState,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tst.cache_cnv_tracks().draw(rndr, sz, |f| {
+        let Some(edges) = tst.edges() else {
+            return;
+        };
+        let align_at = match st.tre_sty {
+            TreSty::PhyGrm => st.tre_vs.w,
+            TreSty::Fan => st.tre_vs.radius_min,
+        };
+        let geom = TracksGeom {
+            tre_sty: st.tre_sty,
+            h: st.tre_vs.h,
+            offset: align_at + st.tracks_offset,
+            opn_angle: st.opn_angle,
+            lab_size: tc.lab_size_tip,
+            scaling: ONE,
+        };
+        let (shapes, texts) = track_shapes(&tc.tracks, edges, &geom);
+        f.push_transform();
+        f.translate(st.translation);
+        f.rotate(st.rotation);
+        fill_colored_paths(&shapes, f);
+        f.pop_transform();
+        draw_labels(
+            &placed_text_labels(texts),
+            Vector { x: ZRO, y: ZRO },
+            Some(st.translation),
+            st.rotation,
+            f,
+        );
+    }));
+}
+
+pub(super) fn draw_node_glyphs(
+    tc: &TreeCnv,
+    st: &St,
+    tst: &TreeState,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tst.cache_cnv_node_glyphs().draw(rndr, sz, |f| {
+        let Some(edges) = tst.edges() else {
+            return;
+        };
+        let shapes = glyph_shapes(
+            tst.tree(),
+            edges,
+            &st.vis_nodes,
+            &tc.node_glyphs,
+            ONE,
+        );
+        f.push_transform();
+        f.translate(st.translation);
+        f.rotate(st.rotation);
+        fill_colored_paths(&shapes, f);
+        f.pop_transform();
+    }));
+}
+
+pub(super) fn draw_state_changes(
+    tc: &TreeCnv,
+    st: &St,
+    tst: &TreeState,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tst.cache_cnv_state_changes().draw(rndr, sz, |f| {
+        let (Some(edges), Some(attr)) = (tst.edges(), &tc.state_change_attr)
+        else {
+            return;
+        };
+        let paths = state_change_paths(tst.tree(), edges, &st.vis_nodes, attr);
+        f.push_transform();
+        f.translate(st.translation);
+        f.rotate(st.rotation);
+        fill_colored_paths(&paths, f);
+        f.pop_transform();
+    }));
+}
+
+pub(super) fn draw_placements(
+    tc: &TreeCnv,
+    st: &St,
+    tst: &TreeState,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tst.cache_cnv_placements().draw(rndr, sz, |f| {
+        let (Some(edges), Some(placements)) = (tst.edges(), tst.placements())
+        else {
+            return;
+        };
+        let shapes = placement_shapes(
+            tst.tree(),
+            edges,
+            &st.vis_nodes,
+            placements,
+            &tc.placements_view,
+            ONE,
+        );
+        f.push_transform();
+        f.translate(st.translation);
+        f.rotate(st.rotation);
+        fill_colored_paths(&shapes, f);
+        f.pop_transform();
+    }));
+}
+
+pub(super) fn draw_legend(
+    tc: &TreeCnv,
+    st: &St,
+    tst: &TreeState,
+    rndr: &Renderer,
+    sz: Size,
+    g: &mut Vec<Geometry>,
+) {
+    g.push(tc.cache_cnv_legend.draw(rndr, sz, |f| {
+        let origin =
+            Point { x: st.vis_vs.x0 + PADDING, y: st.vis_vs.y0 + PADDING };
+        let mut legend = Legend::new(origin, ONE);
+        track_legend(&tc.tracks, &mut legend);
+        if let Some(edges) = tst.edges() {
+            if let Some(attr) = &tc.state_change_attr {
+                state_change_legend(tst.tree(), edges, attr, &mut legend);
+            }
+            glyph_legend(tst.tree(), edges, &tc.node_glyphs, &mut legend);
+        }
+        if let Some(placements) = tst.placements() {
+            placement_legend(placements, &tc.placements_view, &mut legend);
+        }
+        fill_colored_paths(&legend.paths, f);
+        draw_labels(
+            &placed_text_labels(legend.texts),
+            Vector { x: ZRO, y: ZRO },
+            None,
+            ZRO,
+            f,
+        );
+    }));
+}
+
 pub(super) fn draw_selected_nodes(
     st: &St,
     tst: &TreeState,
@@ -59,7 +59,7 @@ impl Program<TvMsg> for TreeCnv {
         {
             st.cnv_vs = RectVals::cnv(bnds);
             st.cnv_rect = st.cnv_vs.clone().into();
-            (st.tre_vs, st.root_len) = self.calc_tre_vs(
+            (st.tre_vs, st.root_len, st.tracks_offset) = self.calc_tre_vs(
                 &st.cnv_vs,
                 tst.edges_tip_tallest(),
                 is_rooted,
@@ -86,9 +86,14 @@ impl Program<TvMsg> for TreeCnv {
             st.bnds = bnds;
             // -----------------------------------------------------------------
             self.clear_cache_cnv_bnds();
+            self.clear_cache_cnv_legend();
             tst.clear_cache_cnv_lab_tip();
             tst.clear_cache_cnv_lab_int();
             tst.clear_cache_cnv_lab_brnch();
+            tst.clear_cache_cnv_tracks();
+            tst.clear_cache_cnv_node_glyphs();
+            tst.clear_cache_cnv_state_changes();
+            tst.clear_cache_cnv_placements();
         }
         // ---------------------------------------------------------------------
         let align_tips_at: Float;
@@ -541,6 +546,43 @@ impl Program<TvMsg> for TreeCnv {
                 t.finish();
             }
 
+            if self.state_change_attr.is_some() {
+                let t = timer("state_changes");
+                draw_state_changes(self, st, tst, rndr, size, &mut geoms);
+                t.finish();
+            }
+
+            let placements_visible =
+                tst.placements().is_some() && !self.placements_view.is_empty();
+
+            if placements_visible {
+                let t = timer("placements");
+                draw_placements(self, st, tst, rndr, size, &mut geoms);
+                t.finish();
+            }
+
+            if !self.tracks.is_empty() {
+                let t = timer("tracks");
+                draw_tracks(self, st, tst, rndr, size, &mut geoms);
+                t.finish();
+            }
+
+            if !self.node_glyphs.is_empty() {
+                let t = timer("node_glyphs");
+                draw_node_glyphs(self, st, tst, rndr, size, &mut geoms);
+                t.finish();
+            }
+
+            if !self.tracks.is_empty()
+                || !self.node_glyphs.is_empty()
+                || self.state_change_attr.is_some()
+                || placements_visible
+            {
+                let t = timer("legend");
+                draw_legend(self, st, tst, rndr, size, &mut geoms);
+                t.finish();
+            }
+
             if st.hovered_node.is_some() {
                 let t = timer("hovered_node");
                 draw_hovered_node(self, st, tst, rndr, size, &mut geoms);
@@ -21,6 +21,7 @@ pub struct St {
     pub(crate) selected_nodes: Vec<NodeData>,
     pub(crate) node_radius: Float,
     pub(crate) root_len: Float,
+    pub(crate) tracks_offset: Float,
     pub(crate) rotation: Float,
     pub(crate) translation: Vector,
     pub(crate) text_w_tip: TextWidth<'static>,
@@ -61,6 +62,7 @@ impl Default for St {
             selected_nodes: Vec::new(),
             node_radius: SF * 3e0,
             root_len: ZRO,
+            tracks_offset: ZRO,
             rotation: ZRO,
             translation: Vector { x: ZRO, y: ZRO },
             text_w_tip: text_width(SF, FNT_NAME_LAB),
@@ -17,6 +17,58 @@ pub(crate) fn lab_text(
     text
 }
 
+impl PlacedText {
+    pub(crate) fn new(
+        text: String,
+        pt: Point,
+        angle: Float,
+        size: Float,
+    ) -> Self {
+        let width = text.chars().count() as Float * size * LAB_CHAR_W;
+        Self { text, pt, angle, size, width }
+    }
+}
+
+pub(crate) fn placed_text_labels(texts: Vec<PlacedText>) -> Vec<Label> {
+    texts
+        .into_iter()
+        .map(|PlacedText { text, pt, angle, size, width }| Label {
+            text: lab_text(text, pt, size, TEMPLATE_TXT_LAB_TIP, false),
+            width,
+            angle,
+            aligned_from: None,
+        })
+        .collect()
+}
+
+pub(crate) fn fill_colored_paths(paths: &[ColoredPath], f: &mut Frame) {
+    for ColoredPath { path, color, filled } in paths {
+        match *filled {
+            true => f.fill(path, *color),
+            false => f.stroke(path, STRK_1.with_color(*color)),
+        }
+    }
+}
+
+/// Color at the relative position `t` of the sequential color scale.
+pub(crate) fn sequential_color(t: Float) -> Color {
+    let n = CLRS_SEQUENTIAL.len();
+    let t = t.clamp(ZRO, ONE) * (n - 1) as Float;
+    let i = (t.floor() as usize).min(n - 2);
+    let (c0, c1) = (CLRS_SEQUENTIAL[i], CLRS_SEQUENTIAL[i + 1]);
+    let f = t - i as Float;
+    Color {
+        r: c0.r + (c1.r - c0.r) * f,
+        g: c0.g + (c1.g - c0.g) * f,
+        b: c0.b + (c1.b - c0.b) * f,
+        a: ONE,
+    }
+}
+
+pub(crate) fn categorical_color(i: usize) -> Color {
+    CLRS_CATEGORICAL[i % CLRS_CATEGORICAL.len()]
+}
+
 pub(crate) fn draw_labels(
     labels: &[Label],
     offset: Vector,
@@ -216,3 +268,42 @@ pub fn calc_ticks(
 
     (ticks, max_lab_nchar)
 }
+
+/// Ticks at round values on a linear axis from `min` to `max`, either of which
+/// may be negative, and the number of characters in the longest label.
+pub fn calc_ticks_linear(
+    tick_count: usize,
+    min: Float,
+    max: Float,
+) -> (Vec<Tick>, usize) {
+    let range = max - min;
+    if tick_count == 0 || range <= ZRO {
+        return (Vec::new(), 0);
+    }
+
+    let raw_step = range / tick_count as Float;
+    let magnitude = (1e1 as Float).powf(raw_step.log10().floor());
+    let step = [1e0, 2e0, 5e0, 1e1]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= raw_step)
+        .unwrap_or(raw_step);
+    let decimals = match step < ONE {
+        true => (-step.log10()).ceil() as usize,
+        false => 0,
+    };
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    let mut max_lab_nchar: usize = 0;
+    let ticks: Vec<Tick> = (first..=last)
+        .map(|k| {
+            let tick_value = k as Float * step + ZRO;
+            let label = format!("{tick_value:.decimals$}");
+            max_lab_nchar = max_lab_nchar.max(label.len());
+            Tick { relative_position: (tick_value - min) / range, label }
+        })
+        .collect();
+
+    (ticks, max_lab_nchar)
+}
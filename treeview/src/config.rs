@@ -27,7 +27,10 @@ pub struct TreeViewConfig {
     pub show_scale_bar: bool,
     pub show_search_bar: bool,
     pub show_side_bar: bool,
+    pub show_tanglegram: bool,
     pub show_tool_bar: bool,
+    pub show_tree_set: bool,
+    pub show_tree_stats: bool,
     pub tip_only_search: bool,
     pub tre_sty: TreSty,
     pub tre_unit: TreUnit,
@@ -59,7 +62,10 @@ impl Default for TreeViewConfig {
             show_scale_bar: false,
             show_search_bar: false,
             show_side_bar: true,
+            show_tanglegram: false,
             show_tool_bar: true,
+            show_tree_set: false,
+            show_tree_stats: false,
             tip_only_search: false,
             tre_sty: TreSty::PhyGrm,
             tre_unit: TreUnit::MillionYears,
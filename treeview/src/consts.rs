@@ -24,17 +24,30 @@ pub(crate) const OPN_ANGLE_IDX_MIN: u16 = 45;
 pub(crate) const OPN_ANGLE_IDX_MAX: u16 = 359;
 pub(crate) const ROT_ANGLE_IDX_MIN: u16 = 360 - 180;
 pub(crate) const ROT_ANGLE_IDX_MAX: u16 = 360 + 180;
+pub(crate) const MSA_COL_W_IDX_MIN: u16 = 1;
+pub(crate) const MSA_COL_W_IDX_MAX: u16 = 16;
+pub(crate) const MSA_MISSING_NAMES_MAX: usize = 200;
 
 pub(crate) const SIDE_BAR_W: Float = TXT_SIZE * 15.0;
 pub(crate) const TREE_CNV_SIZE_DELTA: Float = SF * 5e2;
 
 pub(crate) const PLOT_PADDING: Float = SF;
+pub(crate) const RTT_POINT_R: Float = SF * 3e0;
+pub(crate) const HIST_BINS_MIN: usize = 5;
+pub(crate) const HIST_BINS_MAX: usize = 50;
+pub(crate) const LTT_SET_POINT_COUNT: usize = 503;
+pub(crate) const UNDO_LIMIT: usize = 20;
+/// Branch colour attribute, as written by FigTree and read from PhyloXML.
+pub(crate) const BRANCH_COLOR_ATTR: &str = "!color";
+pub(crate) const STRK_RTT_FIT: Strk = STRK_2_RED_75;
 
 pub(crate) const STRK_EDGE: Strk = STRK_1_BLK;
 pub(crate) const STRK_EDGE_LAB_ALN: Strk =
     Strk { line_dash: DASH_001, ..STRK_H_BLK };
 pub(crate) const STRK_ROOT: Strk = Strk { line_dash: DASH_002, ..STRK_EDGE };
 pub(crate) const STRK_CRSR_LINE: Strk = STRK_1_RED;
+pub(crate) const STRK_TANGLE_LINE: Strk = STRK_1_BLU_50;
+pub(crate) const STRK_TANGLE_LINE_SEL: Strk = STRK_2_RED;
 
 pub(crate) const STRK_NODE_HOVER: Strk = STRK_1_BLU;
 pub(crate) const STRK_NODE_SELECTED: Strk = STRK_1_RED;
@@ -46,6 +59,52 @@ pub(crate) const FILL_NODE_SELECTED: CnvFill = FILL_YEL_50;
 pub(crate) const FILL_NODE_FILTERED: CnvFill = FILL_CYA_25;
 pub(crate) const FILL_NODE_CURRENT: CnvFill = FILL_RED_25;
 
+/// Advance width of the monospaced label font relative to its size.
+pub(crate) const LAB_CHAR_W: Float = 0.6;
+
+/// Colors for categorical data.
+pub(crate) const CLRS_CATEGORICAL: [Color; 10] = [
+    Color { r: 0.122, g: 0.467, b: 0.706, a: 1e0 },
+    Color { r: 1.000, g: 0.498, b: 0.055, a: 1e0 },
+    Color { r: 0.173, g: 0.627, b: 0.173, a: 1e0 },
+    Color { r: 0.839, g: 0.153, b: 0.157, a: 1e0 },
+    Color { r: 0.580, g: 0.404, b: 0.741, a: 1e0 },
+    Color { r: 0.549, g: 0.337, b: 0.294, a: 1e0 },
+    Color { r: 0.890, g: 0.467, b: 0.761, a: 1e0 },
+    Color { r: 0.498, g: 0.498, b: 0.498, a: 1e0 },
+    Color { r: 0.737, g: 0.741, b: 0.133, a: 1e0 },
+    Color { r: 0.090, g: 0.745, b: 0.812, a: 1e0 },
+];
+
+/// Stops of the color scale for continuous data.
+pub(crate) const CLRS_SEQUENTIAL: [Color; 3] = [
+    Color { r: 0.267, g: 0.005, b: 0.329, a: 1e0 },
+    Color { r: 0.128, g: 0.567, b: 0.551, a: 1e0 },
+    Color { r: 0.993, g: 0.906, b: 0.144, a: 1e0 },
+];
+
+/// Residue colors for nucleotides: A, C, G and T/U.
+pub(crate) const CLRS_NUCLEOTIDE: [Color; 4] = [
+    Color { r: 0.392, g: 0.800, b: 0.392, a: 1e0 },
+    Color { r: 0.392, g: 0.584, b: 0.929, a: 1e0 },
+    Color { r: 1.000, g: 0.706, b: 0.294, a: 1e0 },
+    Color { r: 0.941, g: 0.392, b: 0.392, a: 1e0 },
+];
+
+/// Residue colors for groups of amino acids with similar properties:
+/// hydrophobic, positive, negative, polar, cysteine, glycine, proline and
+/// aromatic.
+pub(crate) const CLRS_AMINO_ACID: [Color; 8] = [
+    Color { r: 0.502, g: 0.627, b: 0.941, a: 1e0 },
+    Color { r: 0.941, g: 0.361, b: 0.361, a: 1e0 },
+    Color { r: 0.753, g: 0.282, b: 0.753, a: 1e0 },
+    Color { r: 0.361, g: 0.800, b: 0.361, a: 1e0 },
+    Color { r: 0.941, g: 0.502, b: 0.502, a: 1e0 },
+    Color { r: 0.941, g: 0.565, b: 0.188, a: 1e0 },
+    Color { r: 0.800, g: 0.800, b: 0.000, a: 1e0 },
+    Color { r: 0.082, g: 0.643, b: 0.643, a: 1e0 },
+];
+
 pub(crate) const EPSILON: Float = Float::EPSILON;
 
 pub(crate) const ZRO: Float = 0e0;
@@ -1,7 +1,9 @@
 use riced::Point;
 use riced::Vector;
 
-use crate::{Clr, Display, Formatter, NodeId, Result, TreeState, TvMsg};
+use crate::{
+    Clr, Display, Formatter, NodeId, Result, RootingMethod, TreeState, TvMsg,
+};
 
 #[derive(Debug, Clone)]
 pub struct TvContextMenuItem {
@@ -29,6 +31,21 @@ impl TvContextMenuSpecification {
                 Some(tree_state),
             )
             .push(TvMsg::Root(node_id), Some(tree_state))
+            .push(
+                TvMsg::RootAll(RootingMethod::Outgroup(
+                    tree_state.clade_tip_labels([node_id]),
+                )),
+                Some(tree_state),
+            )
+            .push(TvMsg::RootAll(RootingMethod::Midpoint), Some(tree_state))
+            .push(
+                TvMsg::RootAll(RootingMethod::LongestBranch),
+                Some(tree_state),
+            )
+            .push(
+                TvMsg::RootAll(RootingMethod::MinAncestorDeviation),
+                Some(tree_state),
+            )
             .push(TvMsg::RemoveNode(node_id), Some(tree_state))
             .set_position(position)
     }
@@ -75,6 +92,27 @@ impl TvContextMenuSpecification {
                     label: "Root Here",
                 },
 
+                TvMsg::RootAll(ref rooting_method) => Values {
+                    enabled: !tree_state.is_subtree_view_active()
+                        && match rooting_method {
+                            RootingMethod::Outgroup(labels) => {
+                                !labels.is_empty()
+                                    && labels.len() < tree_state.tip_count()
+                            }
+                            _ => true,
+                        },
+                    label: match rooting_method {
+                        RootingMethod::Outgroup(_) => "Root All Trees Here",
+                        RootingMethod::Midpoint => "Midpoint Root All Trees",
+                        RootingMethod::LongestBranch => {
+                            "Root All Trees on Longest Branch"
+                        }
+                        RootingMethod::MinAncestorDeviation => {
+                            "MAD Root All Trees"
+                        }
+                    },
+                },
+
                 TvMsg::SetSubtreeView(node_id) => Values {
                     enabled: tree_state
                         .is_valid_potential_subtree_view_node(node_id),
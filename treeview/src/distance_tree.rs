@@ -0,0 +1,404 @@
+use crate::alignment::{Alignment, SeqType};
+use crate::newick::{NwkNode, nwk_string};
+use crate::*;
+
+/// Distance given to pairs of sequences too different for the substitution
+/// model, or without sites to compare.
+const SATURATED_DISTANCE: TreeFloat = 5e0;
+
+const AMINO_ACIDS: &[u8] = b"ACDEFGHIKLMNPQRSTVWY";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TreeBuildingMethod {
+    #[default]
+    NeighborJoining,
+    Bionj,
+    Upgma,
+}
+
+pub const TREE_BUILDING_METHOD_OPTS: [TreeBuildingMethod; 3] = [
+    TreeBuildingMethod::NeighborJoining,
+    TreeBuildingMethod::Bionj,
+    TreeBuildingMethod::Upgma,
+];
+
+impl Display for TreeBuildingMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            TreeBuildingMethod::NeighborJoining => "Neighbor-Joining",
+            TreeBuildingMethod::Bionj => "BIONJ",
+            TreeBuildingMethod::Upgma => "UPGMA",
+        })
+    }
+}
+
+/// How the distances between aligned sequences are computed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceModel {
+    PDistance,
+    #[default]
+    JukesCantor,
+}
+
+pub const DISTANCE_MODEL_OPTS: [DistanceModel; 2] =
+    [DistanceModel::PDistance, DistanceModel::JukesCantor];
+
+impl Display for DistanceModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            DistanceModel::PDistance => "p-distance",
+            DistanceModel::JukesCantor => "Jukes-Cantor (JC69)",
+        })
+    }
+}
+
+/// Pairwise distances between named taxa.
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix {
+    names: Vec<String>,
+    /// Row-major square matrix.
+    d: Vec<TreeFloat>,
+}
+
+impl DistanceMatrix {
+    /// Reads a PHYLIP distance matrix (square or lower-triangular), or an
+    /// alignment (FASTA, PHYLIP or NEXUS) whose pairwise distances are then
+    /// computed with `model`. Sites with a gap or an ambiguous residue in
+    /// either sequence of a pair are not compared.
+    pub fn parse(
+        text: &str,
+        model: DistanceModel,
+    ) -> std::result::Result<Self, String> {
+        let text = text.trim_start_matches('\u{feff}');
+        let first = text
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .ok_or_else(|| String::from("The file is empty."))?;
+        if first.parse::<usize>().is_ok() {
+            return parse_phylip_distances(text);
+        }
+        match Alignment::parse(text) {
+            Some(aln) => Ok(Self::from_alignment(&aln, model)),
+            None => Err(String::from("Not a distance matrix or an alignment.")),
+        }
+    }
+
+    fn from_alignment(aln: &Alignment, model: DistanceModel) -> Self {
+        let n = aln.nrow();
+        let seq_type = aln.seq_type();
+        let rows: Vec<Vec<TreeFloat>> = (0..n)
+            .into_par_iter()
+            .map(|i| {
+                (i + 1..n)
+                    .map(|j| {
+                        seq_distance(aln.seq(i), aln.seq(j), seq_type, model)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut d = vec![0e0; n * n];
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &v) in (i + 1..).zip(row) {
+                d[i * n + j] = v;
+                d[j * n + i] = v;
+            }
+        }
+        Self { names: aln.names().to_vec(), d }
+    }
+
+    pub fn taxon_count(&self) -> usize {
+        self.names.len()
+    }
+}
+
+fn parse_phylip_distances(
+    text: &str,
+) -> std::result::Result<DistanceMatrix, String> {
+    let mut tokens = text.split_whitespace();
+    let n: usize = tokens.next().and_then(|t| t.parse().ok()).unwrap_or(0);
+    if n < 2 {
+        return Err(String::from("A distance matrix needs at least two taxa."));
+    }
+    let tokens: Vec<&str> = tokens.collect();
+
+    // Values in row i: all n of them, or those below (and on) the diagonal.
+    let triangle = n * (n - 1) / 2;
+    let value_count = tokens.len().saturating_sub(n);
+    let is_square = value_count == n * n;
+    let row_len: fn(usize, usize) -> usize = match value_count {
+        l if l == n * n => |_, n| n,
+        l if l == triangle + n => |i, _| i + 1,
+        l if l == triangle => |i, _| i,
+        _ => {
+            return Err(String::from(
+                "The number of distances does not match the number of taxa.",
+            ));
+        }
+    };
+
+    let mut names: Vec<String> = Vec::with_capacity(n);
+    let mut d = vec![0e0; n * n];
+    let mut tokens = tokens.into_iter();
+    for i in 0..n {
+        names.push(tokens.next().unwrap_or_default().to_string());
+        for j in 0..row_len(i, n) {
+            let token = tokens.next().unwrap_or_default();
+            let v: TreeFloat = token
+                .parse()
+                .map_err(|_| format!("Not a distance: \"{token}\"."))?;
+            d[i * n + j] = v;
+            if !is_square {
+                d[j * n + i] = v;
+            }
+        }
+    }
+
+    // Square matrices are not always exactly symmetric.
+    for i in 0..n {
+        for j in i + 1..n {
+            let v = (d[i * n + j] + d[j * n + i]) / 2e0;
+            d[i * n + j] = v;
+            d[j * n + i] = v;
+        }
+        d[i * n + i] = 0e0;
+    }
+    Ok(DistanceMatrix { names, d })
+}
+
+fn seq_distance(
+    a: &[u8],
+    b: &[u8],
+    seq_type: SeqType,
+    model: DistanceModel,
+) -> TreeFloat {
+    let is_site = |r: &u8| match seq_type {
+        SeqType::Nucleotide => matches!(r, b'A' | b'C' | b'G' | b'T' | b'U'),
+        SeqType::AminoAcid => AMINO_ACIDS.contains(r),
+    };
+    let (mut sites, mut diffs) = (0usize, 0usize);
+    for (x, y) in a.iter().zip(b).filter(|(x, y)| is_site(x) && is_site(y)) {
+        sites += 1;
+        if x != y {
+            diffs += 1;
+        }
+    }
+    if sites == 0 {
+        return SATURATED_DISTANCE;
+    }
+
+    let p = diffs as TreeFloat / sites as TreeFloat;
+    match model {
+        DistanceModel::PDistance => p,
+        DistanceModel::JukesCantor => {
+            let b = match seq_type {
+                SeqType::Nucleotide => 3e0 / 4e0,
+                SeqType::AminoAcid => 19e0 / 20e0,
+            };
+            match 1e0 - p / b {
+                x if x > 0e0 => (-b * x.ln()).min(SATURATED_DISTANCE),
+                _ => SATURATED_DISTANCE,
+            }
+        }
+    }
+}
+
+// --- Building ----------------------------------------------------------------
+
+/// Builds a tree from the distances. `on_progress` is called with the
+/// fraction of the work done each time two clusters are joined; building
+/// stops if it returns `false`. Neighbor-joining and BIONJ trees are unrooted
+/// (the root has three children), UPGMA trees are rooted and ultrametric.
+pub fn build_tree(
+    dm: &DistanceMatrix,
+    method: TreeBuildingMethod,
+    mut on_progress: impl FnMut(f32) -> bool,
+) -> std::result::Result<Tree, String> {
+    if dm.taxon_count() < 2 {
+        return Err(String::from("At least two taxa are needed for a tree."));
+    }
+    let mut nodes: Vec<NwkNode> = dm
+        .names
+        .iter()
+        .map(|name| NwkNode { label: Some(name.clone()), ..Default::default() })
+        .collect();
+
+    let root = match method {
+        TreeBuildingMethod::NeighborJoining => {
+            neighbor_joining(dm, false, &mut nodes, &mut on_progress)
+        }
+        TreeBuildingMethod::Bionj => {
+            neighbor_joining(dm, true, &mut nodes, &mut on_progress)
+        }
+        TreeBuildingMethod::Upgma => upgma(dm, &mut nodes, &mut on_progress),
+    }
+    .ok_or_else(|| String::from("Tree building was cancelled."))?;
+
+    parse_trees(nwk_string(&nodes, root))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| String::from("Empty tree."))
+}
+
+/// Adds a node joining the given nodes at the given branch lengths.
+fn join(nodes: &mut Vec<NwkNode>, children: &[(usize, TreeFloat)]) -> usize {
+    for &(c, brlen) in children {
+        nodes[c].brlen = Some(brlen.max(0e0));
+    }
+    nodes.push(NwkNode {
+        children: children.iter().map(|&(c, _)| c).collect(),
+        ..Default::default()
+    });
+    nodes.len() - 1
+}
+
+/// Positions in `active` of the pair with the lowest score.
+fn best_pair(
+    active: &[usize],
+    score: impl Fn(usize, usize) -> TreeFloat + Sync,
+) -> Option<(usize, usize)> {
+    let m = active.len();
+    (0..m)
+        .into_par_iter()
+        .filter_map(|a| {
+            (a + 1..m)
+                .map(|b| (score(active[a], active[b]), a, b))
+                .min_by(|x, y| x.0.total_cmp(&y.0))
+        })
+        .min_by(|x, y| x.0.total_cmp(&y.0).then((x.1, x.2).cmp(&(y.1, y.2))))
+        .map(|(_, a, b)| (a, b))
+}
+
+/// Neighbor-joining (Saitou & Nei 1987) or, with `bionj`, BIONJ (Gascuel
+/// 1997), which weighs the distances to a new node by their variances. Returns
+/// the root node or `None` if cancelled.
+fn neighbor_joining(
+    dm: &DistanceMatrix,
+    bionj: bool,
+    nodes: &mut Vec<NwkNode>,
+    on_progress: &mut impl FnMut(f32) -> bool,
+) -> Option<usize> {
+    let n = dm.taxon_count();
+    let mut d = dm.d.clone();
+    // Variances of the distances, only needed by BIONJ.
+    let mut v = match bionj {
+        true => d.clone(),
+        false => Vec::new(),
+    };
+    let mut r: Vec<TreeFloat> =
+        d.chunks(n).map(|row| row.iter().sum()).collect();
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut node_of: Vec<usize> = (0..n).collect();
+    let joins = n.saturating_sub(3).max(1);
+
+    while active.len() > 3 {
+        let m = active.len();
+        let mf = (m - 2) as TreeFloat;
+        let (a, b) =
+            best_pair(&active, |i, j| mf * d[i * n + j] - r[i] - r[j])?;
+        let (i, j) = (active[a], active[b]);
+        let dij = d[i * n + j];
+        let li = (dij / 2e0 + (r[i] - r[j]) / (2e0 * mf)).max(0e0);
+        let lj = (dij - li).max(0e0);
+
+        let vij = match bionj {
+            true => v[i * n + j],
+            false => 0e0,
+        };
+        let lambda = match vij > 0e0 {
+            true => {
+                let s: TreeFloat = active
+                    .iter()
+                    .filter(|&&k| k != i && k != j)
+                    .map(|&k| v[j * n + k] - v[i * n + k])
+                    .sum();
+                (0.5 + s / (2e0 * mf * vij)).clamp(0e0, 1e0)
+            }
+            false => 0.5,
+        };
+
+        // The new node takes the place of node i.
+        r[i] = 0e0;
+        for &k in active.iter().filter(|&&k| k != i && k != j) {
+            let (dik, djk) = (d[i * n + k], d[j * n + k]);
+            let duk = lambda * (dik - li) + (1e0 - lambda) * (djk - lj);
+            r[k] += duk - dik - djk;
+            r[i] += duk;
+            d[i * n + k] = duk;
+            d[k * n + i] = duk;
+            if bionj {
+                let vuk = lambda * v[i * n + k] + (1e0 - lambda) * v[j * n + k]
+                    - lambda * (1e0 - lambda) * vij;
+                v[i * n + k] = vuk;
+                v[k * n + i] = vuk;
+            }
+        }
+        node_of[i] = join(nodes, &[(node_of[i], li), (node_of[j], lj)]);
+        _ = active.remove(b);
+
+        if !on_progress((n - active.len()) as f32 / joins as f32) {
+            return None;
+        }
+    }
+
+    let root = match active[..] {
+        [i, j, k] => {
+            let (dij, dik, djk) = (d[i * n + j], d[i * n + k], d[j * n + k]);
+            join(
+                nodes,
+                &[
+                    (node_of[i], (dij + dik - djk) / 2e0),
+                    (node_of[j], (dij + djk - dik) / 2e0),
+                    (node_of[k], (dik + djk - dij) / 2e0),
+                ],
+            )
+        }
+        [i, j] => {
+            let half = d[i * n + j] / 2e0;
+            join(nodes, &[(node_of[i], half), (node_of[j], half)])
+        }
+        _ => node_of[active[0]],
+    };
+    Some(root)
+}
+
+/// Average-linkage clustering. Returns the root node or `None` if cancelled.
+fn upgma(
+    dm: &DistanceMatrix,
+    nodes: &mut Vec<NwkNode>,
+    on_progress: &mut impl FnMut(f32) -> bool,
+) -> Option<usize> {
+    let n = dm.taxon_count();
+    let mut d = dm.d.clone();
+    let mut size: Vec<TreeFloat> = vec![1e0; n];
+    let mut height: Vec<TreeFloat> = vec![0e0; n];
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut node_of: Vec<usize> = (0..n).collect();
+
+    while active.len() > 1 {
+        let (a, b) = best_pair(&active, |i, j| d[i * n + j])?;
+        let (i, j) = (active[a], active[b]);
+        let h = d[i * n + j] / 2e0;
+
+        for &k in active.iter().filter(|&&k| k != i && k != j) {
+            let duk = (size[i] * d[i * n + k] + size[j] * d[j * n + k])
+                / (size[i] + size[j]);
+            d[i * n + k] = duk;
+            d[k * n + i] = duk;
+        }
+        node_of[i] = join(
+            nodes,
+            &[(node_of[i], h - height[i]), (node_of[j], h - height[j])],
+        );
+        size[i] += size[j];
+        height[i] = h;
+        _ = active.remove(b);
+
+        if !on_progress((n - active.len()) as f32 / (n - 1) as f32) {
+            return None;
+        }
+    }
+    Some(node_of[active[0]])
+}
@@ -0,0 +1,145 @@
+use crate::cnv_utils::sequential_color;
+use crate::*;
+
+const LEGEND_TXT_SIZE: Float = SF * 10e0;
+const LEGEND_GAP: Float = SF * 4e0;
+const LEGEND_SCALE_W: Float = SF * 80e0;
+const LEGEND_SCALE_STEPS: usize = 32;
+
+/// Legend entries stacked from the top down, kept as paths and texts so
+/// that the canvas and the PDF export draw the same legend.
+pub(crate) struct Legend {
+    x: Float,
+    y: Float,
+    scaling: Float,
+    pub(crate) paths: Vec<ColoredPath>,
+    pub(crate) texts: Vec<PlacedText>,
+}
+
+impl Legend {
+    pub(crate) fn new(origin: Point, scaling: Float) -> Self {
+        Self {
+            x: origin.x,
+            y: origin.y + LEGEND_TXT_SIZE * scaling * 0.8,
+            scaling,
+            paths: Vec::new(),
+            texts: Vec::new(),
+        }
+    }
+
+    pub(crate) fn scaling(&self) -> Float {
+        self.scaling
+    }
+
+    fn size(&self) -> Float {
+        LEGEND_TXT_SIZE * self.scaling
+    }
+
+    fn gap(&self) -> Float {
+        LEGEND_GAP * self.scaling
+    }
+
+    fn scale_w(&self) -> Float {
+        LEGEND_SCALE_W * self.scaling
+    }
+
+    fn next_line(&mut self) {
+        self.y += self.size() * 1.6;
+    }
+
+    fn text(&mut self, text: String, dx: Float) {
+        let pt = Point { x: self.x + dx, y: self.y };
+        self.texts.push(PlacedText::new(text, pt, ZRO, self.size()));
+    }
+
+    fn rect(&self, dx: Float, w: Float, h: Float) -> IcedPath {
+        PathBuilder::new()
+            .rectangle(Rectangle {
+                x: self.x + dx,
+                y: self.y - h / TWO,
+                width: w,
+                height: h,
+            })
+            .build()
+    }
+
+    pub(crate) fn title(&mut self, title: &str) {
+        self.text(title.to_string(), ZRO);
+        self.next_line();
+    }
+
+    pub(crate) fn note(&mut self, note: String) {
+        self.text(note, ZRO);
+        self.next_line();
+    }
+
+    /// The sequential color scale labelled with its range.
+    pub(crate) fn color_scale(&mut self, min: Float, max: Float) {
+        let step_w = self.scale_w() / LEGEND_SCALE_STEPS as Float;
+        for i in 0..LEGEND_SCALE_STEPS {
+            let t = i as Float / (LEGEND_SCALE_STEPS - 1) as Float;
+            let path = self.rect(
+                i as Float * step_w,
+                step_w + self.scaling / TWO,
+                self.size(),
+            );
+            self.paths.push(ColoredPath {
+                path,
+                color: sequential_color(t),
+                filled: true,
+            });
+        }
+        self.range(min, max);
+    }
+
+    /// A bar of the given color labelled with the range it spans.
+    pub(crate) fn bar(&mut self, color: Color, min: Float, max: Float) {
+        let path = self.rect(ZRO, self.scale_w(), self.size() * 0.7);
+        self.paths.push(ColoredPath { path, color, filled: true });
+        self.range(min, max);
+    }
+
+    fn range(&mut self, min: Float, max: Float) {
+        let dx = self.scale_w() + self.gap();
+        self.text(format!("{} - {}", fmt_num(min), fmt_num(max)), dx);
+        self.next_line();
+    }
+
+    /// A filled or outlined square followed by a label.
+    pub(crate) fn swatch(&mut self, color: Color, filled: bool, label: String) {
+        let size = self.size();
+        let path = self.rect(size * 0.1, size * 0.8, size * 0.8);
+        self.paths.push(ColoredPath { path, color, filled });
+        self.text(label, size + self.gap());
+        self.next_line();
+    }
+
+    /// A circle of the given radius followed by a label.
+    pub(crate) fn circle(
+        &mut self,
+        color: Color,
+        radius: Float,
+        label: String,
+    ) {
+        let line = self.size().max(radius * TWO);
+        self.y += (line - self.size()) / TWO;
+        let center = Point { x: self.x + line / TWO, y: self.y };
+        let path = PathBuilder::new().circle(center, radius).build();
+        self.paths.push(ColoredPath { path, color, filled: true });
+        self.text(label, line + self.gap());
+        self.y += (line - self.size()) / TWO;
+        self.next_line();
+    }
+
+    /// Space between the legends of different items.
+    pub(crate) fn end_section(&mut self) {
+        self.y += self.gap();
+    }
+}
+
+pub(crate) fn fmt_num(v: Float) -> String {
+    match v != ZRO && (v.abs() >= 1e5 || v.abs() < 1e-2) {
+        true => format!("{v:.2e}"),
+        false => format!("{v:.2}"),
+    }
+}
@@ -14,18 +14,34 @@
 // #![allow(clippy::needless_range_loop)]
 // -------------------------------------
 
+mod alignment;
+mod brlen_transform;
+mod cnv_msa;
 mod cnv_plot;
+mod cnv_tangle;
 mod cnv_tree;
 mod cnv_utils;
 mod config;
 mod consts;
 mod context_menu;
+mod distance_tree;
 mod edge_utils;
 mod gts;
+mod legend;
+mod newick;
+mod node_glyphs;
+mod parsimony;
 mod path_builders;
 mod pdf;
+mod placements;
 mod rect_vals;
+mod root_to_tip;
+mod rooting;
 mod tables;
+mod tracks;
+mod tree_set;
+mod tree_stats;
+mod tree_xml;
 mod treestate;
 mod treeview;
 mod view;
@@ -33,37 +49,69 @@ mod view;
 pub type Float = f32;
 pub type Integer = i32;
 
+pub use brlen_transform::BrlenTransform;
+pub use cnv_plot::PlotType;
 pub use config::TreeViewConfig;
 pub use context_menu::{TvContextMenuItem, TvContextMenuSpecification};
+pub use distance_tree::{
+    DISTANCE_MODEL_OPTS, DistanceMatrix, DistanceModel,
+    TREE_BUILDING_METHOD_OPTS, TreeBuildingMethod, build_tree,
+};
+pub use node_glyphs::{SymbolEncoding, SymbolShape};
+pub use parsimony::ParsimonyMethod;
+pub use placements::{PlacementSize, parse_jplace};
 pub use rect_vals::RectVals;
 pub use riced::{SF, TXT_SIZE};
-pub use treeview::{TreUnit, TreeView, TvMsg};
+pub use rooting::RootingMethod;
+pub use tables::DataExport;
+pub use tracks::TrackType;
+pub use tree_xml::parse_xml_trees;
+pub use treestate::PreparedTree;
+pub use treeview::{TreUnit, TreeView, TvMsg, TvViewport};
 
 use std::collections::HashSet;
 use std::f32 as float;
 use std::fmt::{Debug, Display, Formatter, Result};
 use std::rc::Rc;
 
+use alignment::Alignment;
+use brlen_transform::BRLEN_TRANSFORM_OPTS;
+use cnv_msa::{MsaCnv, MsaGeom};
 use cnv_plot::AXIS_SCALE_TYPE_OPTS;
 use cnv_plot::AxisScaleType;
+use cnv_plot::PLOT_TYPE_OPTS;
 use cnv_plot::PlotCnv;
 use cnv_plot::PlotData;
 use cnv_plot::Tick;
 use cnv_plot::plot_data_from_ltt_points;
 use cnv_plot::transformed_relative_value;
+use cnv_plot::{LttSet, plot_data_from_histogram};
 
+use cnv_tangle::TangleCnv;
 use cnv_tree::TreeCnv;
 use consts::*;
 use dendros::{
     Attribute, AttributeSelector, AttributeValue, Edge, LttPoint, Node, NodeId,
-    Tree, ltt, write_newick,
+    Tree, TreeFloat, ltt, parse_trees, write_newick,
 };
 use gts::*;
+use node_glyphs::{NodeGlyphs, SYMBOL_ENCODING_OPTS, SYMBOL_SHAPE_OPTS};
+use parsimony::{PARSIMONY_METHOD_OPTS, Parsimony};
+use placements::{PLACEMENT_SIZE_OPTS, PlacementsView};
 use rayon::prelude::*;
 use riced::*;
+use root_to_tip::{RTT_DATE_SOURCE_LABELS, RootToTip};
+use rooting::{ROOTING_METHOD_OPTS, rooted_trees};
 use tables::{
-    AttributesTableField, NodesTableField, attributes_table, nodes_table,
+    AttributeEdit, AttributesTableEdit, AttributesTableField,
+    NODES_TABLE_NO_ATTR, NodeEdit, NodesTableEdit, NodesTableField,
+    NodesTableRow, attributes_table, attributes_table_text, nodes_table,
+    nodes_table_text,
 };
+use tracks::{Metadata, TRACK_TYPE_OPTS, Track};
+use tree_set::{ConsensusType, TreeSet, TreeSetDist};
+use tree_stats::TreeStats;
+use tree_xml::{nexml_string, phyloxml_string};
 use treestate::TreeState;
 use treeview::{
     TRE_NODE_ORD_OPTS, TRE_STY_OPTS, TRE_UNIT_OPTS, TreNodeOrd, TreSty,
@@ -106,6 +154,24 @@ struct Label {
     aligned_from: Option<Point>,
 }
 
+/// Filled or outlined path, drawn the same way on the canvas and in PDFs.
+#[derive(Debug, Clone)]
+struct ColoredPath {
+    path: IcedPath,
+    color: Color,
+    filled: bool,
+}
+
+/// Text anchored at a point of a canvas frame.
+#[derive(Debug, Clone, Default)]
+struct PlacedText {
+    text: String,
+    pt: Point,
+    angle: Float,
+    size: Float,
+    width: Float,
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 struct EdgePoints {
     p0: Point,
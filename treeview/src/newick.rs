@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::*;
+
+/// Node of a tree assembled outside of `dendros` that can be serialized to
+/// NEWICK and parsed back into a `Tree`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NwkNode {
+    pub(crate) label: Option<String>,
+    pub(crate) brlen: Option<TreeFloat>,
+    pub(crate) node_attrs: Vec<(String, String)>,
+    pub(crate) branch_attrs: Vec<(String, String)>,
+    pub(crate) children: Vec<usize>,
+}
+
+/// Converts a tree into [`NwkNode`]s, keeping labels, branch lengths and
+/// attributes. Returns the nodes, the node id each of them was created from
+/// and the index of the root node.
+pub(crate) fn nwk_nodes_from_tree(
+    tree: &Tree,
+) -> Option<(Vec<NwkNode>, Vec<NodeId>, usize)> {
+    let edges = tree.edges()?;
+    let has_brlen = tree.has_branch_lengths();
+
+    let mut nodes: Vec<NwkNode> = Vec::with_capacity(edges.len() + 1);
+    let mut node_ids: Vec<NodeId> = Vec::with_capacity(edges.len() + 1);
+    let mut idx_for_id: HashMap<NodeId, usize> =
+        HashMap::with_capacity(edges.len() + 1);
+
+    for edge in edges {
+        _ = idx_for_id.insert(edge.node_id, nodes.len());
+        node_ids.push(edge.node_id);
+        nodes.push(NwkNode {
+            label: edge.label.as_deref().map(String::from),
+            brlen: has_brlen.then_some(edge.branch_length),
+            node_attrs: nwk_attributes(
+                tree.node_attributes(edge.node_id).iter(),
+            ),
+            branch_attrs: nwk_attributes(
+                tree.branch_attributes(edge.node_id).iter(),
+            ),
+            children: Vec::new(),
+        });
+    }
+
+    let mut root: Option<usize> = None;
+    for edge in edges {
+        let idx = idx_for_id[&edge.node_id];
+        if edge.parent_node_id == edge.node_id {
+            root = Some(idx);
+            continue;
+        }
+
+        // Unrooted trees have no edge for the first node.
+        let parent_idx = match idx_for_id.get(&edge.parent_node_id) {
+            Some(&parent_idx) => parent_idx,
+            None => {
+                let parent_idx = nodes.len();
+                _ = idx_for_id.insert(edge.parent_node_id, parent_idx);
+                node_ids.push(edge.parent_node_id);
+                nodes.push(NwkNode {
+                    node_attrs: nwk_attributes(
+                        tree.node_attributes(edge.parent_node_id).iter(),
+                    ),
+                    ..Default::default()
+                });
+                root = Some(parent_idx);
+                parent_idx
+            }
+        };
+        nodes[parent_idx].children.push(idx);
+    }
+
+    root.map(|root| (nodes, node_ids, root))
+}
+
+/// Node indexes with every parent before its children.
+pub(crate) fn preorder(nodes: &[NwkNode], root: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = Vec::with_capacity(nodes.len());
+    let mut stack: Vec<usize> = vec![root];
+    while let Some(i) = stack.pop() {
+        order.push(i);
+        stack.extend(nodes[i].children.iter().rev());
+    }
+    order
+}
+
+/// Writes the tree rooted at `root` as a single NEWICK string. Node
+/// attributes are written after the node label, branch attributes after the
+/// branch length (these are dropped for branches without a length).
+pub(crate) fn nwk_string(nodes: &[NwkNode], root: usize) -> String {
+    let mut s = String::new();
+    write_node(nodes, root, &mut s);
+    s.push(';');
+    s
+}
+
+/// NEWICK string of the tree with the label or branch length of one node
+/// changed.
+pub(crate) fn edited_newick(
+    tree: &Tree,
+    node_id: NodeId,
+    edit: &NodeEdit,
+) -> Option<String> {
+    let (mut nodes, node_ids, root) = nwk_nodes_from_tree(tree)?;
+    let idx = node_ids.iter().position(|&id| id == node_id)?;
+    match edit {
+        NodeEdit::Label(label) => nodes[idx].label = label.clone(),
+        NodeEdit::BranchLength(brlen) => nodes[idx].brlen = Some(*brlen),
+    }
+    Some(nwk_string(&nodes, root))
+}
+
+/// NEWICK string of the tree with node or branch attributes set, removed or
+/// renamed.
+pub(crate) fn attributes_edited_newick(
+    tree: &Tree,
+    edit: &AttributeEdit,
+) -> Option<String> {
+    let (mut nodes, node_ids, root) = nwk_nodes_from_tree(tree)?;
+    let idxs = |ids: &[NodeId]| -> Vec<usize> {
+        node_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| ids.contains(id))
+            .map(|(i, _)| i)
+            .collect()
+    };
+
+    match edit {
+        AttributeEdit::Set { node_ids, selector, key, value } => {
+            for i in idxs(node_ids) {
+                let attrs = match selector {
+                    AttributeSelector::Node => &mut nodes[i].node_attrs,
+                    AttributeSelector::Branch => &mut nodes[i].branch_attrs,
+                };
+                attrs.retain(|(k, _)| k != key);
+                attrs.push((key.clone(), value.clone()));
+                attrs.sort();
+            }
+        }
+        AttributeEdit::Remove { node_ids, key } => {
+            for i in idxs(node_ids) {
+                nodes[i].node_attrs.retain(|(k, _)| k != key);
+                nodes[i].branch_attrs.retain(|(k, _)| k != key);
+            }
+        }
+        AttributeEdit::Rename { from, to } => {
+            for nd in &mut nodes {
+                for attrs in [&mut nd.node_attrs, &mut nd.branch_attrs] {
+                    if attrs.iter().any(|(k, _)| k == from) {
+                        attrs.retain(|(k, _)| k != to);
+                        for (k, _) in attrs.iter_mut() {
+                            if *k == *from {
+                                k.clone_from(to);
+                            }
+                        }
+                        attrs.sort();
+                    }
+                }
+            }
+        }
+    }
+    Some(nwk_string(&nodes, root))
+}
+
+fn write_node(nodes: &[NwkNode], idx: usize, s: &mut String) {
+    let nd = &nodes[idx];
+    if !nd.children.is_empty() {
+        s.push('(');
+        for (i, &c) in nd.children.iter().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            write_node(nodes, c, s);
+        }
+        s.push(')');
+    }
+
+    if let Some(label) = &nd.label {
+        s.push_str(&nwk_label(label));
+    }
+
+    write_attrs(&nd.node_attrs, s);
+
+    if let Some(brlen) = nd.brlen {
+        _ = write!(s, ":{brlen}");
+        write_attrs(&nd.branch_attrs, s);
+    }
+}
+
+fn write_attrs(attrs: &[(String, String)], s: &mut String) {
+    if attrs.is_empty() {
+        return;
+    }
+    s.push_str("[&");
+    for (i, (k, v)) in attrs.iter().enumerate() {
+        if i > 0 {
+            s.push(',');
+        }
+        _ = write!(s, "{k}={v}");
+    }
+    s.push(']');
+}
+
+/// Quotes the label if it contains characters with a meaning in NEWICK.
+fn nwk_label(label: &str) -> String {
+    if label.chars().any(|c| c.is_whitespace() || "()[]{}':;,=\"".contains(c)) {
+        format!("'{}'", label.replace('\'', "''"))
+    } else {
+        label.to_string()
+    }
+}
+
+fn nwk_attributes<'a>(
+    attrs: impl Iterator<Item = (&'a String, &'a Attribute)>,
+) -> Vec<(String, String)> {
+    let mut attrs: Vec<(String, String)> =
+        attrs.map(|(k, v)| (k.clone(), nwk_attribute(v))).collect();
+    attrs.sort();
+    attrs
+}
+
+/// Formats an attribute value the way it is read back by the parser.
+pub(crate) fn nwk_attribute(attr: &Attribute) -> String {
+    match attr {
+        Attribute::Value(value) => nwk_attribute_value(value),
+        Attribute::List(values) => format!(
+            "{{{}}}",
+            values
+                .iter()
+                .map(nwk_attribute_value)
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+    }
+}
+
+/// Attribute value as shown to the user: like [`nwk_attribute`], but without
+/// quotes around text values.
+pub(crate) fn attribute_text(attr: &Attribute) -> String {
+    match attr {
+        Attribute::Value(AttributeValue::Text(text)) => text.clone(),
+        attr => nwk_attribute(attr),
+    }
+}
+
+/// Formats an attribute value typed by the user. Numbers and `{...}` lists
+/// are written as they are, anything else as text.
+pub(crate) fn nwk_attribute_from_text(text: &str) -> String {
+    let text = text.trim();
+    match text.parse::<f64>().is_ok_and(f64::is_finite)
+        || (text.starts_with('{') && text.ends_with('}'))
+    {
+        true => text.to_string(),
+        false => nwk_attribute_value(&AttributeValue::Text(text.to_string())),
+    }
+}
+
+/// Whether the text can be written as an attribute name.
+pub(crate) fn is_valid_attribute_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.chars().all(|c| {
+            !c.is_whitespace() && !matches!(c, '=' | ',' | '[' | ']' | '&')
+        })
+}
+
+fn nwk_attribute_value(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::Integer(v) => v.to_string(),
+        AttributeValue::Decimal(v) => v.to_string(),
+        AttributeValue::Text(v) => {
+            format!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        AttributeValue::Color(v) => v.to_string(),
+    }
+}
@@ -0,0 +1,322 @@
+use crate::cnv_utils::{categorical_color, sequential_color};
+use crate::legend::{Legend, fmt_num};
+use crate::*;
+
+const GLYPH_PIE_R: Float = SF * 7e0;
+const GLYPH_SYMBOL_R: Float = SF * 5e0;
+const GLYPH_SYMBOL_R_MIN: Float = SF * 2e0;
+const GLYPH_SYMBOL_R_MAX: Float = SF * 9e0;
+
+/// Shape of the symbols drawn at the internal nodes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolShape {
+    #[default]
+    Circle,
+    Square,
+}
+
+pub(super) const SYMBOL_SHAPE_OPTS: [SymbolShape; 2] =
+    [SymbolShape::Circle, SymbolShape::Square];
+
+impl Display for SymbolShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            SymbolShape::Circle => "Circle",
+            SymbolShape::Square => "Square",
+        })
+    }
+}
+
+/// How the value of the symbol attribute is shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolEncoding {
+    #[default]
+    Size,
+    Color,
+}
+
+pub(super) const SYMBOL_ENCODING_OPTS: [SymbolEncoding; 2] =
+    [SymbolEncoding::Size, SymbolEncoding::Color];
+
+impl Display for SymbolEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            SymbolEncoding::Size => "Size",
+            SymbolEncoding::Color => "Color",
+        })
+    }
+}
+
+/// Glyphs drawn at the internal nodes from numeric node attributes.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct NodeGlyphs {
+    /// Attributes holding the slices of the pie charts, e.g. the state
+    /// probabilities of an ancestral-state reconstruction. A single attribute
+    /// holding a list of numbers is split into one slice per list item.
+    pub(crate) pie_attrs: Vec<String>,
+    pub(crate) symbol_attr: Option<String>,
+    pub(crate) symbol_shape: SymbolShape,
+    pub(crate) symbol_encoding: SymbolEncoding,
+}
+
+impl NodeGlyphs {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pie_attrs.is_empty() && self.symbol_attr.is_none()
+    }
+}
+
+fn value_num(value: &AttributeValue) -> Option<Float> {
+    match value {
+        AttributeValue::Integer(v) => Some(*v as Float),
+        AttributeValue::Decimal(v) => Some(*v as Float),
+        _ => None,
+    }
+    .filter(|v| v.is_finite())
+}
+
+pub(crate) fn attr_num(
+    tree: &Tree,
+    node_id: NodeId,
+    key: &str,
+) -> Option<Float> {
+    match tree.node_attributes(node_id).get(key)? {
+        Attribute::Value(value) => value_num(value),
+        Attribute::List(_) => None,
+    }
+}
+
+/// Slice values of the pie chart at a node or `None` if the node has none of
+/// the pie attributes.
+fn pie_values(
+    tree: &Tree,
+    node_id: NodeId,
+    keys: &[String],
+) -> Option<Vec<Float>> {
+    if let [key] = keys
+        && let Some(Attribute::List(values)) =
+            tree.node_attributes(node_id).get(key)
+    {
+        return Some(
+            values.iter().map(|v| value_num(v).unwrap_or(ZRO)).collect(),
+        );
+    }
+    let values: Vec<Option<Float>> =
+        keys.iter().map(|key| attr_num(tree, node_id, key)).collect();
+    match values.iter().any(Option::is_some) {
+        true => Some(values.iter().map(|v| v.unwrap_or(ZRO)).collect()),
+        false => None,
+    }
+}
+
+/// Number of slices of the widest pie chart in the tree.
+fn pie_slice_count(tree: &Tree, edges: &[Edge], keys: &[String]) -> usize {
+    edges
+        .iter()
+        .filter(|edge| !edge.is_tip)
+        .filter_map(|edge| pie_values(tree, edge.node_id, keys))
+        .map(|values| values.len())
+        .max()
+        .unwrap_or(0)
+}
+
+fn attr_range(
+    tree: &Tree,
+    edges: &[Edge],
+    key: &str,
+) -> Option<(Float, Float)> {
+    edges
+        .iter()
+        .filter(|edge| !edge.is_tip)
+        .filter_map(|edge| attr_num(tree, edge.node_id, key))
+        .fold(None, |range, v| match range {
+            None => Some((v, v)),
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+        })
+}
+
+fn relative(v: Float, (min, max): (Float, Float)) -> Float {
+    match max > min {
+        true => (v - min) / (max - min),
+        false => ONE,
+    }
+}
+
+fn symbol_radius(t: Float) -> Float {
+    GLYPH_SYMBOL_R_MIN + (GLYPH_SYMBOL_R_MAX - GLYPH_SYMBOL_R_MIN) * t.sqrt()
+}
+
+/// Keys of the node attributes that have numeric values, or lists of numeric
+/// values, at any of the internal nodes.
+pub(crate) fn numeric_node_attr_keys(
+    tree: &Tree,
+    edges: &[Edge],
+) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for edge in edges.iter().filter(|edge| !edge.is_tip) {
+        for (key, attr) in tree.node_attributes(edge.node_id).iter() {
+            let is_num = match attr {
+                Attribute::Value(value) => value_num(value).is_some(),
+                Attribute::List(values) => {
+                    values.iter().any(|v| value_num(v).is_some())
+                }
+            };
+            if is_num && !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+    }
+    keys.sort();
+    keys
+}
+
+fn pie_paths(values: &[Float], center: Point, r: Float) -> Vec<ColoredPath> {
+    let mut paths: Vec<ColoredPath> = Vec::new();
+    let total: Float = values.iter().filter(|v| **v > ZRO).sum();
+    if total <= ZRO {
+        return paths;
+    }
+    let mut a0 = -PI / TWO;
+    for (i, v) in values.iter().enumerate() {
+        if *v <= ZRO {
+            continue;
+        }
+        let a1 = a0 + TAU * v / total;
+        let start =
+            Point { x: center.x + r * a0.cos(), y: center.y + r * a0.sin() };
+        let path = PathBuilder::new()
+            .move_to(center)
+            .line_to(start)
+            .arc(a0, a1, center, r)
+            .close()
+            .build();
+        paths.push(ColoredPath {
+            path,
+            color: categorical_color(i),
+            filled: true,
+        });
+        a0 = a1;
+    }
+    let outline = PathBuilder::new().circle(center, r).build();
+    paths.push(ColoredPath { path: outline, color: Clr::BLK, filled: false });
+    paths
+}
+
+fn symbol_paths(
+    shape: SymbolShape,
+    color: Color,
+    center: Point,
+    r: Float,
+) -> Vec<ColoredPath> {
+    let path = match shape {
+        SymbolShape::Circle => PathBuilder::new().circle(center, r).build(),
+        SymbolShape::Square => PathBuilder::new()
+            .rectangle(Rectangle {
+                x: center.x - r,
+                y: center.y - r,
+                width: r * TWO,
+                height: r * TWO,
+            })
+            .build(),
+    };
+    vec![
+        ColoredPath { path: path.clone(), color, filled: true },
+        ColoredPath { path, color: Clr::BLK, filled: false },
+    ]
+}
+
+/// Glyph paths at the given nodes, in the coordinates of the tree.
+pub(crate) fn glyph_shapes(
+    tree: &Tree,
+    edges: &[Edge],
+    nodes: &[NodeData],
+    glyphs: &NodeGlyphs,
+    scaling: Float,
+) -> Vec<ColoredPath> {
+    let mut paths: Vec<ColoredPath> = Vec::new();
+    let range = glyphs
+        .symbol_attr
+        .as_deref()
+        .and_then(|key| Some((key, attr_range(tree, edges, key)?)));
+
+    for nd in nodes {
+        if edges[nd.edge_idx].is_tip {
+            continue;
+        }
+        let center = nd.points.p1;
+        if let Some((key, range)) = range
+            && let Some(v) = attr_num(tree, nd.node_id, key)
+        {
+            let t = relative(v, range);
+            let (color, r) = match glyphs.symbol_encoding {
+                SymbolEncoding::Size => {
+                    (categorical_color(0), symbol_radius(t))
+                }
+                SymbolEncoding::Color => (sequential_color(t), GLYPH_SYMBOL_R),
+            };
+            paths.extend(symbol_paths(
+                glyphs.symbol_shape,
+                color,
+                center,
+                r * scaling,
+            ));
+        }
+        if !glyphs.pie_attrs.is_empty()
+            && let Some(values) =
+                pie_values(tree, nd.node_id, &glyphs.pie_attrs)
+        {
+            paths.extend(pie_paths(&values, center, GLYPH_PIE_R * scaling));
+        }
+    }
+    paths
+}
+
+pub(crate) fn glyph_legend(
+    tree: &Tree,
+    edges: &[Edge],
+    glyphs: &NodeGlyphs,
+    legend: &mut Legend,
+) {
+    if !glyphs.pie_attrs.is_empty() {
+        let n = pie_slice_count(tree, edges, &glyphs.pie_attrs);
+        match &glyphs.pie_attrs[..] {
+            [key] => {
+                legend.title(key);
+                for i in 0..n {
+                    legend.swatch(
+                        categorical_color(i),
+                        true,
+                        format!("{}", i + 1),
+                    );
+                }
+            }
+            keys => {
+                legend.title("Pie Charts");
+                for (i, key) in keys.iter().enumerate() {
+                    legend.swatch(categorical_color(i), true, key.clone());
+                }
+            }
+        }
+        legend.end_section();
+    }
+
+    if let Some(key) = &glyphs.symbol_attr
+        && let Some((min, max)) = attr_range(tree, edges, key)
+    {
+        legend.title(key);
+        match glyphs.symbol_encoding {
+            SymbolEncoding::Size => {
+                let mid = (min + max) / TWO;
+                for v in [min, mid, max] {
+                    let r = symbol_radius(relative(v, (min, max)));
+                    legend.circle(
+                        categorical_color(0),
+                        r * legend.scaling(),
+                        fmt_num(v),
+                    );
+                }
+            }
+            SymbolEncoding::Color => legend.color_scale(min, max),
+        }
+        legend.end_section();
+    }
+}
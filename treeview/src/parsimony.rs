@@ -0,0 +1,328 @@
+use crate::cnv_utils::categorical_color;
+use crate::legend::Legend;
+use crate::newick::{
+    NwkNode, nwk_attribute, nwk_nodes_from_tree, nwk_string, preorder,
+};
+use crate::*;
+
+/// Method used to reconstruct the ancestral states of a categorical trait.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParsimonyMethod {
+    /// Unordered states, every change costs one step.
+    #[default]
+    Fitch,
+    /// Changes cost what the user supplied cost matrix says.
+    Sankoff,
+}
+
+pub(super) const PARSIMONY_METHOD_OPTS: [ParsimonyMethod; 2] =
+    [ParsimonyMethod::Fitch, ParsimonyMethod::Sankoff];
+
+impl Display for ParsimonyMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            ParsimonyMethod::Fitch => "Fitch",
+            ParsimonyMethod::Sankoff => "Sankoff",
+        })
+    }
+}
+
+/// Outcome of the last reconstruction.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Parsimony {
+    pub(crate) tre_id: usize,
+    /// Node attribute holding the reconstructed states.
+    pub(crate) attr: String,
+    pub(crate) method: ParsimonyMethod,
+    /// Number of changes (Fitch) or their total cost (Sankoff).
+    pub(crate) tree_len: Float,
+}
+
+/// Name of the node attribute the states of a metadata column are stored in.
+pub(crate) fn state_attr_key(column: &str) -> String {
+    column
+        .chars()
+        .map(|c| match c.is_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+/// Reads a square matrix with one row per state. Rows are separated by
+/// semicolons or new lines, values by spaces or commas. Returns `None` unless
+/// the matrix is `n` by `n` and holds no negative values.
+pub(crate) fn parse_cost_matrix(
+    text: &str,
+    n: usize,
+) -> Option<Vec<Vec<Float>>> {
+    let rows: Vec<Vec<Float>> = text
+        .split([';', '\n'])
+        .map(str::trim)
+        .filter(|row| !row.is_empty())
+        .map(|row| {
+            row.split([' ', ',', '\t'])
+                .filter(|v| !v.is_empty())
+                .map(|v| v.parse::<Float>().ok().filter(|v| *v >= ZRO))
+                .collect::<Option<Vec<Float>>>()
+        })
+        .collect::<Option<Vec<Vec<Float>>>>()?;
+    match rows.len() == n && rows.iter().all(|row| row.len() == n) {
+        true => Some(rows),
+        false => None,
+    }
+}
+
+/// Cost matrix where every change costs one step.
+pub(crate) fn unit_cost_matrix(n: usize) -> Vec<Vec<Float>> {
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| match i == j {
+                    true => ZRO,
+                    false => ONE,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Reconstructs the state at every node and returns the tree as a NEWICK
+/// string with the states stored as `attr` node attributes, together with the
+/// tree length. `tip_state` gives the index in `states` of the state of a tip;
+/// tips without one may take any state. Fitch parsimony is used when `costs`
+/// is `None`, otherwise Sankoff parsimony with `costs` indexed like `states`.
+pub(crate) fn reconstructed_newick(
+    tree: &Tree,
+    attr: &str,
+    states: &[String],
+    tip_state: &dyn Fn(&str) -> Option<usize>,
+    costs: Option<&[Vec<Float>]>,
+) -> Option<(String, Float)> {
+    if states.is_empty() {
+        return None;
+    }
+
+    let (mut nodes, _, root) = nwk_nodes_from_tree(tree)?;
+    let order = preorder(&nodes, root);
+    let mut parents: Vec<usize> = vec![root; nodes.len()];
+    for (i, nd) in nodes.iter().enumerate() {
+        for &c in &nd.children {
+            parents[c] = i;
+        }
+    }
+
+    let node_state = |nd: &NwkNode| -> Option<usize> {
+        match nd.children.is_empty() {
+            true => tip_state(nd.label.as_deref()?),
+            false => None,
+        }
+    };
+
+    let (assigned, tree_len) = match costs {
+        None => fitch(&nodes, &order, &parents, states.len(), &node_state),
+        Some(costs) => sankoff(&nodes, &order, &parents, costs, &node_state),
+    };
+
+    for (nd, &s) in nodes.iter_mut().zip(&assigned) {
+        nd.node_attrs.retain(|(k, _)| k != attr);
+        nd.node_attrs.push((
+            attr.to_string(),
+            nwk_attribute(&Attribute::Value(AttributeValue::Text(
+                states[s].clone(),
+            ))),
+        ));
+    }
+
+    Some((nwk_string(&nodes, root), tree_len))
+}
+
+fn first_in_set(set: &[bool]) -> usize {
+    set.iter().position(|&in_set| in_set).unwrap_or(0)
+}
+
+/// Fitch parsimony generalized to polytomies: the state set of a node holds
+/// the states found in the largest number of its children's sets.
+fn fitch(
+    nodes: &[NwkNode],
+    order: &[usize],
+    parents: &[usize],
+    n: usize,
+    tip_state: &dyn Fn(&NwkNode) -> Option<usize>,
+) -> (Vec<usize>, Float) {
+    let mut sets: Vec<Vec<bool>> = vec![Vec::new(); nodes.len()];
+    let mut changes = 0usize;
+    for &i in order.iter().rev() {
+        let nd = &nodes[i];
+        sets[i] = match nd.children.is_empty() {
+            true => match tip_state(nd) {
+                Some(s) => (0..n).map(|j| j == s).collect(),
+                None => vec![true; n],
+            },
+            false => {
+                let mut counts: Vec<usize> = vec![0; n];
+                for &c in &nd.children {
+                    for (count, &in_set) in counts.iter_mut().zip(&sets[c]) {
+                        if in_set {
+                            *count += 1;
+                        }
+                    }
+                }
+                let max = counts.iter().copied().max().unwrap_or(0);
+                changes += nd.children.len() - max;
+                counts.iter().map(|&count| count == max).collect()
+            }
+        };
+    }
+
+    let mut assigned: Vec<usize> = vec![0; nodes.len()];
+    for (k, &i) in order.iter().enumerate() {
+        assigned[i] = match k {
+            0 => first_in_set(&sets[i]),
+            _ => {
+                let parent_state = assigned[parents[i]];
+                match sets[i][parent_state] {
+                    true => parent_state,
+                    false => first_in_set(&sets[i]),
+                }
+            }
+        };
+    }
+
+    (assigned, changes as Float)
+}
+
+/// Cheapest state of a child, and its cost, given the row of the cost matrix
+/// for the state of the parent.
+fn cheapest(row: &[Float], child_costs: &[Float]) -> (usize, Float) {
+    row.iter().zip(child_costs).map(|(a, b)| a + b).enumerate().fold(
+        (0, Float::INFINITY),
+        |best, (j, cost)| match cost < best.1 {
+            true => (j, cost),
+            false => best,
+        },
+    )
+}
+
+fn sankoff(
+    nodes: &[NwkNode],
+    order: &[usize],
+    parents: &[usize],
+    costs: &[Vec<Float>],
+    tip_state: &dyn Fn(&NwkNode) -> Option<usize>,
+) -> (Vec<usize>, Float) {
+    let n = costs.len();
+    let mut node_costs: Vec<Vec<Float>> = vec![Vec::new(); nodes.len()];
+    for &i in order.iter().rev() {
+        let nd = &nodes[i];
+        node_costs[i] = match nd.children.is_empty() {
+            true => match tip_state(nd) {
+                Some(s) => (0..n)
+                    .map(|j| match j == s {
+                        true => ZRO,
+                        false => Float::INFINITY,
+                    })
+                    .collect(),
+                None => vec![ZRO; n],
+            },
+            false => costs
+                .iter()
+                .map(|row| {
+                    nd.children
+                        .iter()
+                        .map(|&c| cheapest(row, &node_costs[c]).1)
+                        .sum()
+                })
+                .collect(),
+        };
+    }
+
+    let mut assigned: Vec<usize> = vec![0; nodes.len()];
+    let mut tree_len = ZRO;
+    for (k, &i) in order.iter().enumerate() {
+        assigned[i] = match k {
+            0 => {
+                let (s, cost) = cheapest(&vec![ZRO; n], &node_costs[i]);
+                tree_len = cost;
+                s
+            }
+            _ => cheapest(&costs[assigned[parents[i]]], &node_costs[i]).0,
+        };
+    }
+
+    (assigned, tree_len)
+}
+
+// --- Drawing -----------------------------------------------------------------
+
+fn attr_text(tree: &Tree, node_id: NodeId, key: &str) -> Option<String> {
+    match tree.node_attributes(node_id).get(key)? {
+        Attribute::Value(AttributeValue::Text(text)) => Some(text.clone()),
+        attr => Some(nwk_attribute(attr)),
+    }
+}
+
+/// Distinct values of a node attribute, sorted.
+fn attr_states(tree: &Tree, edges: &[Edge], key: &str) -> Vec<String> {
+    let mut states: Vec<String> = edges
+        .iter()
+        .filter_map(|edge| attr_text(tree, edge.node_id, key))
+        .collect();
+    states.sort();
+    states.dedup();
+    states
+}
+
+/// Branches along which the value of `attr` changes, colored by the state the
+/// branch leads to, in the coordinates of the tree.
+pub(crate) fn state_change_paths(
+    tree: &Tree,
+    edges: &[Edge],
+    nodes: &[NodeData],
+    attr: &str,
+) -> Vec<ColoredPath> {
+    let states = attr_states(tree, edges, attr);
+    let mut paths: Vec<ColoredPath> = Vec::new();
+    for nd in nodes {
+        let edge = &edges[nd.edge_idx];
+        if edge.parent_node_id == edge.node_id {
+            continue;
+        }
+        let Some(state) = attr_text(tree, edge.node_id, attr) else {
+            continue;
+        };
+        if attr_text(tree, edge.parent_node_id, attr).as_ref() == Some(&state) {
+            continue;
+        }
+        let Ok(i) = states.binary_search(&state) else {
+            continue;
+        };
+        let path = PathBuilder::new()
+            .move_to(nd.points.p1)
+            .line_to(nd.points.p0)
+            .build();
+        paths.push(ColoredPath {
+            path,
+            color: categorical_color(i),
+            filled: false,
+        });
+    }
+    paths
+}
+
+pub(crate) fn state_change_legend(
+    tree: &Tree,
+    edges: &[Edge],
+    attr: &str,
+    legend: &mut Legend,
+) {
+    let states = attr_states(tree, edges, attr);
+    if states.is_empty() {
+        return;
+    }
+    legend.title(&format!("{attr} Changes"));
+    for (i, state) in states.into_iter().enumerate() {
+        legend.swatch(categorical_color(i), true, state);
+    }
+    legend.end_section();
+}
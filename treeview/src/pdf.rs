@@ -6,9 +6,19 @@ use draw::*;
 use object_conversion::*;
 use utils::*;
 
+use crate::cnv_tangle::{
+    TangleGeom, TangleLayout, TangleSide, path_tangle_edges, path_tangle_lines,
+    tangle_labels,
+};
+use crate::consts::{STRK_EDGE, STRK_TANGLE_LINE};
 use crate::edge_utils::{node_data_cart, node_data_pol};
+use crate::legend::Legend;
+use crate::node_glyphs::{NodeGlyphs, glyph_legend, glyph_shapes};
+use crate::parsimony::{state_change_legend, state_change_paths};
+use crate::placements::{PlacementsView, placement_legend, placement_shapes};
+use crate::tracks::{TracksGeom, track_legend, track_shapes};
 use crate::{
-    Float, NodeData, Rc, RectVals, TreSty, TreeState, ellipsize_unicode,
+    Float, NodeData, Rc, RectVals, Track, TreSty, TreeState, ellipsize_unicode,
 };
 use dendros::Edge;
 use num_traits::{AsPrimitive, real::Real};
@@ -18,6 +28,7 @@ use oxidize_pdf::{
 };
 use rayon::prelude::*;
 use riced::fonts::JET_BRAINS_MONO_REGULAR;
+use riced::{PADDING, Point};
 use std::path::PathBuf;
 
 #[allow(clippy::too_many_arguments)]
@@ -52,6 +63,12 @@ pub fn tree_to_pdf<
     _draw_scale_bar: bool,
     draw_debug: bool,
     // --------------------------------
+    tracks: &[Track],
+    tracks_offset: T,
+    node_glyphs: &NodeGlyphs,
+    state_change_attr: Option<&str>,
+    placements_view: &PlacementsView,
+    // --------------------------------
 ) -> Result<(), PdfError> {
     let dim_max = AsPrimitive::<f64>::as_(cnv_vs.dim_max);
     let margin = 72.0 / 2.0;
@@ -83,6 +100,12 @@ pub fn tree_to_pdf<
     let lab_offset_int: f64 = AsPrimitive::<f64>::as_(lab_offset_int) * scaling;
     let lab_offset_brnch: f64 =
         AsPrimitive::<f64>::as_(lab_offset_brnch) * scaling;
+    let tracks_offset: Float =
+        (AsPrimitive::<f64>::as_(tracks_offset) * scaling) as Float;
+
+    let font_data = JET_BRAINS_MONO_REGULAR.to_vec();
+    let font_name = "JetBrainsMono-Regular".to_string();
+    let font = Font::Custom(font_name.clone());
 
     let mut pg =
         Page::new(cnv_vs_f64.w + margin * 2e0, cnv_vs_f64.h + margin * 2e0);
@@ -94,6 +117,39 @@ pub fn tree_to_pdf<
         draw_bounds(&cnv_vs_float, &tre_vs_float, pg.graphics());
     } // -----------------------------------------------------------------------
 
+    let placements = match placements_view.is_empty() {
+        true => None,
+        false => tree_state.placements(),
+    };
+
+    // Legend ------------------------------------------------------------------
+    if !tracks.is_empty()
+        || !node_glyphs.is_empty()
+        || state_change_attr.is_some()
+        || placements.is_some()
+    {
+        let pad = PADDING * scaling as Float;
+        let mut legend =
+            Legend::new(Point { x: pad, y: pad }, scaling as Float);
+        track_legend(tracks, &mut legend);
+        if let Some(edges) = tree_state.edges() {
+            if let Some(attr) = state_change_attr {
+                state_change_legend(
+                    tree_state.tree(),
+                    edges,
+                    attr,
+                    &mut legend,
+                );
+            }
+            glyph_legend(tree_state.tree(), edges, node_glyphs, &mut legend);
+        }
+        if let Some(placements) = placements {
+            placement_legend(placements, placements_view, &mut legend);
+        }
+        fill_colored_paths(legend.paths, scaling, pg.graphics())?;
+        write_placed_texts(legend.texts, 0e0, font.clone(), scaling, &mut pg);
+    } // -----------------------------------------------------------------------
+
     match tree_style {
         TreSty::PhyGrm => {
             _ = pg.graphics().translate(tre_vs_f64.x0, -tre_vs_f64.y0);
@@ -143,6 +199,27 @@ pub fn tree_to_pdf<
         pg.graphics(),
     ); // ----------------------------------------------------------------------
 
+    // Tracks ------------------------------------------------------------------
+    if !tracks.is_empty()
+        && let Some(edges) = tree_state.edges()
+    {
+        let geom = TracksGeom {
+            tre_sty: tree_style,
+            h: tre_vs_float.h,
+            offset: tracks_offset
+                + match tree_style {
+                    TreSty::PhyGrm => tre_vs_float.w,
+                    TreSty::Fan => tre_vs_float.radius_min,
+                },
+            opn_angle,
+            lab_size: lab_size_tip as Float,
+            scaling: scaling as Float,
+        };
+        let (shapes, texts) = track_shapes(tracks, edges, &geom);
+        fill_colored_paths(shapes, scaling, pg.graphics())?;
+        write_placed_texts(texts, rot_angle, font.clone(), scaling, &mut pg);
+    } // -----------------------------------------------------------------------
+
     // Text labels -------------------------------------------------------------
     let edges: &Vec<Edge> = tree_state.edges().unwrap();
     let node_data: Vec<NodeData> = edges
@@ -158,9 +235,37 @@ pub fn tree_to_pdf<
         })
         .collect();
 
-    let font_data = JET_BRAINS_MONO_REGULAR.to_vec();
-    let font_name = "JetBrainsMono-Regular".to_string();
-    let font = Font::Custom(font_name.clone());
+    // State changes -----------------------------------------------------------
+    if let Some(attr) = state_change_attr {
+        let paths =
+            state_change_paths(tree_state.tree(), edges, &node_data, attr);
+        fill_colored_paths(paths, scaling, pg.graphics())?;
+    } // -----------------------------------------------------------------------
+
+    // Placements --------------------------------------------------------------
+    if let Some(placements) = placements {
+        let shapes = placement_shapes(
+            tree_state.tree(),
+            edges,
+            &node_data,
+            placements,
+            placements_view,
+            scaling as Float,
+        );
+        fill_colored_paths(shapes, scaling, pg.graphics())?;
+    } // -----------------------------------------------------------------------
+
+    // Node glyphs -------------------------------------------------------------
+    if !node_glyphs.is_empty() {
+        let shapes = glyph_shapes(
+            tree_state.tree(),
+            edges,
+            &node_data,
+            node_glyphs,
+            scaling as Float,
+        );
+        fill_colored_paths(shapes, scaling, pg.graphics())?;
+    } // -----------------------------------------------------------------------
 
     for nd in node_data {
         let edge = &edges[nd.edge_idx];
@@ -243,3 +348,93 @@ pub fn tree_to_pdf<
     doc.set_creator("TreeHouse");
     doc.save(path_buf)
 }
+
+pub fn tanglegram_to_pdf(
+    path_buf: PathBuf,
+    lay_l: &TangleLayout,
+    lay_r: &TangleLayout,
+    w: Float,
+    lab_size: Float,
+    draw_labs: bool,
+) -> Result<(), PdfError> {
+    let tip_count = lay_l.tip_count().max(lay_r.tip_count()) as Float;
+    let h = (tip_count * lab_size * 1.5).max(w / 2e0);
+
+    let dim_max = w.max(h) as f64;
+    let margin = 72.0 / 2.0;
+    let max_page_dim_points = (72.0 * 200.0) - (margin * 2.0);
+    let scaling: f64 = if dim_max >= max_page_dim_points {
+        max_page_dim_points / dim_max
+    } else {
+        1e0
+    };
+
+    let w = w * scaling as Float;
+    let h = h * scaling as Float;
+    let lab_size = lab_size * scaling as Float;
+    let geom = TangleGeom::new(w, h, lab_size, lay_l, lay_r, draw_labs);
+
+    let mut pg = Page::new(w as f64 + margin * 2e0, h as f64 + margin * 2e0);
+    pg.set_margins(margin, margin, margin, margin);
+    _ = pg.graphics().translate(margin, h as f64 + margin);
+
+    // Lines joining matching tips ---------------------------------------------
+    _ = apply_iced_path_to_gc(
+        path_tangle_lines(lay_l, lay_r, &geom, None),
+        apply_iced_stroke_to_gc(STRK_TANGLE_LINE, scaling, pg.graphics()),
+    )
+    .stroke(); // --------------------------------------------------------------
+
+    // Tree edges --------------------------------------------------------------
+    for (side, lay) in [(TangleSide::Left, lay_l), (TangleSide::Right, lay_r)] {
+        _ = apply_iced_path_to_gc(
+            path_tangle_edges(lay, &geom, side),
+            apply_iced_stroke_to_gc(STRK_EDGE, scaling, pg.graphics()),
+        )
+        .stroke();
+    } // -----------------------------------------------------------------------
+
+    let font_data = JET_BRAINS_MONO_REGULAR.to_vec();
+    let font_name = "JetBrainsMono-Regular".to_string();
+    let font = Font::Custom(font_name.clone());
+
+    // Text labels -------------------------------------------------------------
+    if geom.draw_labs {
+        let lab_size = geom.lab_size as f64;
+        let lab_offset = geom.lab_offset as f64;
+        for (side, lay) in
+            [(TangleSide::Left, lay_l), (TangleSide::Right, lay_r)]
+        {
+            for (text, pt) in tangle_labels(lay, &geom, side) {
+                let text_w = measure_text(&text, &font, lab_size);
+                let lab_offset_x = match side {
+                    TangleSide::Left => lab_offset,
+                    TangleSide::Right => -text_w - lab_offset,
+                };
+                write_text(
+                    &text,
+                    pt.x as f64,
+                    -pt.y as f64,
+                    text_w,
+                    lab_size,
+                    lab_offset_x,
+                    lab_size / 4e0,
+                    None,
+                    0e0,
+                    0e0,
+                    font.clone(),
+                    scaling,
+                    &mut pg,
+                );
+            }
+        }
+    } // -----------------------------------------------------------------------
+
+    let mut doc = Document::new();
+    _ = doc.add_font_from_bytes(font_name, font_data);
+    doc.add_page(pg);
+    doc.set_title("TreeHouse Exported Tanglegram");
+    doc.set_producer("TreeHouse");
+    doc.set_creator("TreeHouse");
+    doc.save(path_buf)
+}
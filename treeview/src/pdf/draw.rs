@@ -1,11 +1,11 @@
 use super::*;
 
-use crate::consts::{STRK_EDGE, STRK_ROOT};
+use crate::consts::{STRK_1, STRK_EDGE, STRK_ROOT};
 use crate::path_builders::{
     path_clade_highlight, path_edges_fan, path_edges_phygrm,
     path_root_edge_fan, path_root_edge_phygrm,
 };
-use crate::{Float, Rc, RectVals, TreSty, TreeState};
+use crate::{ColoredPath, Float, Rc, RectVals, TreSty, TreeState};
 use dendros::Edge;
 use oxidize_pdf::{PdfError, graphics::GraphicsContext};
 use riced::{PathBuilder, Rectangle};
@@ -116,3 +116,28 @@ pub(super) fn draw_edges(
         .stroke();
     }
 }
+
+pub(super) fn fill_colored_paths(
+    paths: Vec<ColoredPath>,
+    scaling: f64,
+    gc: &mut GraphicsContext,
+) -> Result<(), PdfError> {
+    for ColoredPath { path, color, filled } in paths {
+        _ = gc.save_state();
+        if filled {
+            _ = apply_iced_path_to_gc(path, gc);
+            _ = gc
+                .set_fill_color(color_from_iced_color(color))
+                .set_alpha_fill(alpha_from_iced_color(color))?;
+            _ = gc.fill();
+        } else {
+            _ = apply_iced_path_to_gc(
+                path,
+                apply_iced_stroke_to_gc(STRK_1.with_color(color), scaling, gc),
+            )
+            .stroke();
+        }
+        _ = gc.restore_state();
+    }
+    Ok(())
+}
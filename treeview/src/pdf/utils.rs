@@ -1,6 +1,7 @@
 use super::*;
 
 use crate::Float;
+use crate::PlacedText;
 use crate::consts::STRK_EDGE_LAB_ALN;
 use oxidize_pdf::{Page, text::Font};
 use riced::{PathBuilder, Point};
@@ -91,3 +92,31 @@ pub(super) fn write_text(
         .end_text()
         .restore_state();
 }
+
+pub(super) fn write_placed_texts(
+    texts: Vec<PlacedText>,
+    rot_angle: f64,
+    font: Font,
+    scaling: f64,
+    pg: &mut Page,
+) {
+    for PlacedText { text, pt, angle, size, .. } in texts {
+        let size = size as f64;
+        let text_w = measure_text(&text, &font, size);
+        write_text(
+            &text,
+            pt.x as f64,
+            -pt.y as f64,
+            text_w,
+            size,
+            0e0,
+            size / 4e0,
+            None,
+            angle as f64,
+            rot_angle,
+            font.clone(),
+            scaling,
+            pg,
+        );
+    }
+}
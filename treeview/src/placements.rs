@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::cnv_utils::{categorical_color, sequential_color};
+use crate::legend::{Legend, fmt_num};
+use crate::*;
+
+const PLACEMENT_R_MIN: Float = SF * 2e0;
+const PLACEMENT_R_MAX: Float = SF * 9e0;
+const PLACEMENT_ALPHA: Float = 0.6;
+const DENSITY_BRANCH_W: Float = SF * 4e0;
+
+/// Branch attribute holding the jplace edge number of a branch.
+pub(crate) const EDGE_NUM_ATTR: &str = "edge_num";
+
+/// What the size of the placement circles shows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementSize {
+    /// One circle for each placement location.
+    #[default]
+    LikeWeightRatio,
+    /// One circle for each branch, for the queries placed best on it.
+    Count,
+}
+
+pub(super) const PLACEMENT_SIZE_OPTS: [PlacementSize; 2] =
+    [PlacementSize::LikeWeightRatio, PlacementSize::Count];
+
+impl Display for PlacementSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            PlacementSize::LikeWeightRatio => "Like Weight Ratio",
+            PlacementSize::Count => "Count",
+        })
+    }
+}
+
+/// One of the locations a query is placed at.
+#[derive(Debug, Clone, PartialEq)]
+struct Location {
+    edge_num: usize,
+    like_weight_ratio: Float,
+    /// Distance from the child end of the branch.
+    distal_length: Option<Float>,
+}
+
+/// Query sequences sharing their placement locations, with their
+/// multiplicities.
+#[derive(Debug, Clone, PartialEq)]
+struct Pquery {
+    names: Vec<(String, Float)>,
+    locations: Vec<Location>,
+}
+
+/// Placements of query sequences on the branches of a reference tree, read
+/// from a jplace file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Placements {
+    pqueries: Vec<Pquery>,
+}
+
+impl Placements {
+    pub(crate) fn pquery_count(&self) -> usize {
+        self.pqueries.len()
+    }
+}
+
+/// How the placements are drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PlacementsView {
+    pub(crate) draw_circles: bool,
+    pub(crate) size: PlacementSize,
+    /// Only queries with a name containing this text (ignoring case) are
+    /// drawn.
+    pub(crate) name_filter: String,
+    /// Colors the branches by the placement mass on them.
+    pub(crate) draw_density: bool,
+}
+
+impl Default for PlacementsView {
+    fn default() -> Self {
+        Self {
+            draw_circles: true,
+            size: PlacementSize::default(),
+            name_filter: String::new(),
+            draw_density: false,
+        }
+    }
+}
+
+impl PlacementsView {
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.draw_circles && !self.draw_density
+    }
+}
+
+// --- Reading -----------------------------------------------------------------
+
+/// Parses a jplace document into its reference tree and the placements on
+/// it. The edge numbers of the tree are kept in the `edge_num` branch
+/// attribute.
+pub fn parse_jplace(text: &str) -> std::result::Result<PreparedTree, String> {
+    let json: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+    let tree_text = json
+        .get("tree")
+        .and_then(Value::as_str)
+        .ok_or_else(|| String::from("jplace document without a tree."))?;
+    let tree = parse_trees(newick_with_edge_nums(tree_text))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| String::from("Empty tree."))?;
+
+    let fields: Vec<&str> = json
+        .get("fields")
+        .and_then(Value::as_array)
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let field = |name: &str| fields.iter().position(|f| *f == name);
+    let edge_num_idx = field("edge_num").ok_or_else(|| {
+        String::from("jplace fields do not include edge_num.")
+    })?;
+    let lwr_idx = field("like_weight_ratio");
+    let distal_idx = field("distal_length");
+
+    let mut pqueries: Vec<Pquery> = Vec::new();
+    for placement in array(&json, "placements") {
+        let locations: Vec<Location> = array(placement, "p")
+            .filter_map(|p| {
+                let p = p.as_array()?;
+                let num = |idx: Option<usize>| {
+                    idx.and_then(|i| p.get(i)?.as_f64()).map(|v| v as Float)
+                };
+                Some(Location {
+                    edge_num: usize::try_from(p.get(edge_num_idx)?.as_u64()?)
+                        .ok()?,
+                    like_weight_ratio: num(lwr_idx).unwrap_or(ONE),
+                    distal_length: num(distal_idx),
+                })
+            })
+            .collect();
+        if locations.is_empty() {
+            continue;
+        }
+        pqueries.push(Pquery { names: pquery_names(placement), locations });
+    }
+
+    Ok(PreparedTree::new(tree).with_placements(Placements { pqueries }))
+}
+
+fn array<'a>(json: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    json.get(key).and_then(Value::as_array).into_iter().flatten()
+}
+
+/// Names of the queries of a placement, from either the `n` or the `nm` key.
+fn pquery_names(placement: &Value) -> Vec<(String, Float)> {
+    if let Some(nm) = placement.get("nm").and_then(Value::as_array) {
+        return nm
+            .iter()
+            .filter_map(|name_mult| {
+                let name_mult = name_mult.as_array()?;
+                let name = name_mult.first()?.as_str()?;
+                let mult = name_mult.get(1).and_then(Value::as_f64);
+                Some((name.to_string(), mult.unwrap_or(1e0) as Float))
+            })
+            .collect();
+    }
+    match placement.get("n") {
+        Some(Value::String(name)) => vec![(name.clone(), ONE)],
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|name| (name.to_string(), ONE))
+            .collect(),
+        _ => vec![(String::new(), ONE)],
+    }
+}
+
+/// NEWICK string with the `{n}` edge numbers of a jplace tree written as
+/// `edge_num` attributes.
+fn newick_with_edge_nums(tree_text: &str) -> String {
+    let mut s = String::with_capacity(tree_text.len() * 2);
+    let mut in_quotes = false;
+    let mut in_comment = false;
+    let mut edge_num: Option<String> = None;
+    for c in tree_text.chars() {
+        if let Some(num) = &mut edge_num {
+            match c {
+                '}' => {
+                    _ = write!(s, "[&{EDGE_NUM_ATTR}={}]", num.trim());
+                    edge_num = None;
+                }
+                c => num.push(c),
+            }
+            continue;
+        }
+        match c {
+            '\'' if !in_comment => in_quotes = !in_quotes,
+            '[' if !in_quotes => in_comment = true,
+            ']' if !in_quotes => in_comment = false,
+            '{' if !in_quotes && !in_comment => {
+                edge_num = Some(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        s.push(c);
+    }
+    s
+}
+
+// --- Drawing -----------------------------------------------------------------
+
+/// Placements on one branch, of the queries passing the name filter.
+#[derive(Debug, Default)]
+struct EdgePlacements {
+    /// Like weight ratio and distal length of each location.
+    locations: Vec<(Float, Option<Float>)>,
+    /// Number of queries placed best on the branch.
+    count: Float,
+    /// Sum of the like weight ratios, weighted by the query multiplicities.
+    mass: Float,
+}
+
+fn edge_placements(
+    placements: &Placements,
+    name_filter: &str,
+) -> HashMap<usize, EdgePlacements> {
+    let name_filter = name_filter.trim().to_lowercase();
+    let mut by_edge: HashMap<usize, EdgePlacements> = HashMap::new();
+    for pquery in &placements.pqueries {
+        let mult: Float = pquery
+            .names
+            .iter()
+            .filter(|(name, _)| name.to_lowercase().contains(&name_filter))
+            .map(|(_, mult)| mult)
+            .sum();
+        if mult <= ZRO {
+            continue;
+        }
+        for loc in &pquery.locations {
+            let ep = by_edge.entry(loc.edge_num).or_default();
+            ep.locations.push((loc.like_weight_ratio, loc.distal_length));
+            ep.mass += loc.like_weight_ratio * mult;
+        }
+        if let Some(best) = pquery
+            .locations
+            .iter()
+            .max_by(|a, b| a.like_weight_ratio.total_cmp(&b.like_weight_ratio))
+        {
+            by_edge.entry(best.edge_num).or_default().count += mult;
+        }
+    }
+    by_edge
+}
+
+fn edge_num(tree: &Tree, node_id: NodeId) -> Option<usize> {
+    let attr = tree
+        .branch_attributes(node_id)
+        .get(EDGE_NUM_ATTR)
+        .or_else(|| tree.node_attributes(node_id).get(EDGE_NUM_ATTR))?;
+    match attr {
+        Attribute::Value(AttributeValue::Integer(v)) => {
+            usize::try_from(*v).ok()
+        }
+        _ => None,
+    }
+}
+
+fn max_of(values: impl Iterator<Item = Float>) -> Float {
+    values.fold(ZRO, Float::max)
+}
+
+fn circle_radius(t: Float) -> Float {
+    PLACEMENT_R_MIN
+        + (PLACEMENT_R_MAX - PLACEMENT_R_MIN) * t.clamp(ZRO, ONE).sqrt()
+}
+
+/// Point on the branch at the given distance from its child end.
+fn point_on_branch(
+    nd: &NodeData,
+    brlen: Float,
+    distal: Option<Float>,
+) -> Point {
+    let Some(distal) = distal.filter(|_| brlen > ZRO) else {
+        return nd.points.p_mid;
+    };
+    let t = (distal / brlen).clamp(ZRO, ONE);
+    let (p0, p1) = (nd.points.p0, nd.points.p1);
+    Point { x: p1.x + (p0.x - p1.x) * t, y: p1.y + (p0.y - p1.y) * t }
+}
+
+/// Filled band along a branch.
+fn branch_band(p0: Point, p1: Point, w: Float) -> IcedPath {
+    let (dx, dy) = (p1.x - p0.x, p1.y - p0.y);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len <= ZRO {
+        return PathBuilder::new().circle(p0, w / TWO).build();
+    }
+    let (nx, ny) = (-dy / len * w / TWO, dx / len * w / TWO);
+    PathBuilder::new()
+        .move_to(Point { x: p0.x + nx, y: p0.y + ny })
+        .line_to(Point { x: p1.x + nx, y: p1.y + ny })
+        .line_to(Point { x: p1.x - nx, y: p1.y - ny })
+        .line_to(Point { x: p0.x - nx, y: p0.y - ny })
+        .close()
+        .build()
+}
+
+/// Placement density branches and placement circles at the given nodes, in
+/// the coordinates of the tree.
+pub(crate) fn placement_shapes(
+    tree: &Tree,
+    edges: &[Edge],
+    nodes: &[NodeData],
+    placements: &Placements,
+    view: &PlacementsView,
+    scaling: Float,
+) -> Vec<ColoredPath> {
+    let by_edge = edge_placements(placements, &view.name_filter);
+    let max_mass = max_of(by_edge.values().map(|ep| ep.mass));
+    let max_count = max_of(by_edge.values().map(|ep| ep.count));
+    let fill = categorical_color(1).scale_alpha(PLACEMENT_ALPHA);
+
+    let mut density: Vec<ColoredPath> = Vec::new();
+    let mut circles: Vec<ColoredPath> = Vec::new();
+    for nd in nodes {
+        let edge = &edges[nd.edge_idx];
+        let Some(ep) =
+            edge_num(tree, edge.node_id).and_then(|num| by_edge.get(&num))
+        else {
+            continue;
+        };
+
+        if view.draw_density && ep.mass > ZRO && max_mass > ZRO {
+            density.push(ColoredPath {
+                path: branch_band(
+                    nd.points.p0,
+                    nd.points.p1,
+                    DENSITY_BRANCH_W * scaling,
+                ),
+                color: sequential_color(ep.mass / max_mass),
+                filled: true,
+            });
+        }
+
+        if !view.draw_circles {
+            continue;
+        }
+        let brlen = edge.branch_length as Float;
+        let mut circle = |center: Point, r: Float| {
+            let path = PathBuilder::new().circle(center, r * scaling).build();
+            circles.push(ColoredPath {
+                path: path.clone(),
+                color: fill,
+                filled: true,
+            });
+            circles.push(ColoredPath { path, color: Clr::BLK, filled: false });
+        };
+        match view.size {
+            PlacementSize::LikeWeightRatio => {
+                for &(lwr, distal) in &ep.locations {
+                    circle(
+                        point_on_branch(nd, brlen, distal),
+                        circle_radius(lwr),
+                    );
+                }
+            }
+            PlacementSize::Count => {
+                if ep.count > ZRO {
+                    circle(
+                        nd.points.p_mid,
+                        circle_radius(ep.count / max_count),
+                    );
+                }
+            }
+        }
+    }
+    density.extend(circles);
+    density
+}
+
+pub(crate) fn placement_legend(
+    placements: &Placements,
+    view: &PlacementsView,
+    legend: &mut Legend,
+) {
+    let by_edge = edge_placements(placements, &view.name_filter);
+    if by_edge.is_empty() {
+        return;
+    }
+    let fill = categorical_color(1).scale_alpha(PLACEMENT_ALPHA);
+
+    if view.draw_circles {
+        legend.title(&format!("Placements ({})", view.size));
+        let mut values: Vec<(Float, Float)> = match view.size {
+            PlacementSize::LikeWeightRatio => {
+                [0.1, 0.5, 1.0].into_iter().map(|v| (v, v)).collect()
+            }
+            PlacementSize::Count => {
+                let max = max_of(by_edge.values().map(|ep| ep.count));
+                [ONE.min(max), (ONE + max) / TWO, max]
+                    .into_iter()
+                    .map(|v| (v, v / max))
+                    .collect()
+            }
+        };
+        values.dedup_by(|a, b| a.0 == b.0);
+        for (v, t) in values {
+            legend.circle(
+                fill,
+                circle_radius(t) * legend.scaling(),
+                fmt_num(v),
+            );
+        }
+        legend.end_section();
+    }
+
+    if view.draw_density {
+        legend.title("Placement Density");
+        legend.color_scale(ZRO, max_of(by_edge.values().map(|ep| ep.mass)));
+        legend.end_section();
+    }
+}
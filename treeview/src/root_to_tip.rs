@@ -0,0 +1,176 @@
+use crate::cnv_plot::{AxisDataType, PlotPoint};
+use crate::*;
+
+/// Date source entry of the root-to-tip date pick list that reads the dates
+/// from the tip labels; the other entries are metadata columns.
+pub(super) const RTT_DATE_SOURCE_LABELS: &str = "Tip Labels";
+
+/// Placeholder marking the date in a tip label pattern.
+const DATE_PLACEHOLDER: &str = "{date}";
+
+/// Root-to-tip distances of the dated tips against their sampling dates, with
+/// the least-squares regression line.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct RootToTip {
+    pub(crate) node_ids: Vec<NodeId>,
+    pub(crate) labels: Vec<String>,
+    pub(crate) dates: Vec<Float>,
+    pub(crate) dists: Vec<Float>,
+    /// Evolutionary rate.
+    pub(crate) slope: Float,
+    pub(crate) intercept: Float,
+    pub(crate) r2: Float,
+}
+
+impl RootToTip {
+    /// Fits the regression; `None` unless at least two tips with different
+    /// dates are given.
+    pub(crate) fn new(
+        node_ids: Vec<NodeId>,
+        labels: Vec<String>,
+        dates: Vec<Float>,
+        dists: Vec<Float>,
+    ) -> Option<Self> {
+        let n = dates.len() as Float;
+        if dates.len() < 2 {
+            return None;
+        }
+        let mean_x = dates.iter().sum::<Float>() / n;
+        let mean_y = dists.iter().sum::<Float>() / n;
+        let (mut sxx, mut sxy, mut syy) = (ZRO, ZRO, ZRO);
+        for (x, y) in dates.iter().zip(&dists) {
+            sxx += (x - mean_x) * (x - mean_x);
+            sxy += (x - mean_x) * (y - mean_y);
+            syy += (y - mean_y) * (y - mean_y);
+        }
+        if sxx <= ZRO {
+            return None;
+        }
+        let slope = sxy / sxx;
+        let r2 = match syy > ZRO {
+            true => sxy * sxy / (sxx * syy),
+            false => ZRO,
+        };
+        Some(Self {
+            node_ids,
+            labels,
+            dates,
+            dists,
+            slope,
+            intercept: mean_y - slope * mean_x,
+            r2,
+        })
+    }
+
+    /// Date at which the regression line reaches zero distance: the estimated
+    /// date of the most recent common ancestor.
+    pub(crate) fn x_intercept(&self) -> Option<Float> {
+        match self.slope > ZRO {
+            true => Some(-self.intercept / self.slope),
+            false => None,
+        }
+    }
+
+    /// Axis ranges of the plot. The date axis reaches back to the
+    /// x-intercept so that the regression line meets the date axis.
+    pub(crate) fn plot_data(&self) -> PlotData {
+        let mut x_min = self.dates.iter().copied().fold(Float::MAX, Float::min);
+        let x_max = self.dates.iter().copied().fold(Float::MIN, Float::max);
+        if let Some(x_intercept) = self.x_intercept() {
+            x_min = x_min.min(x_intercept);
+        }
+        let y_max = self.dists.iter().copied().fold(ZRO, Float::max);
+        PlotData {
+            x_data_type: AxisDataType::Continuous,
+            y_data_type: AxisDataType::Continuous,
+            x_min,
+            x_max,
+            y_min: ZRO,
+            y_max,
+            plot_points: self
+                .dates
+                .iter()
+                .zip(&self.dists)
+                .map(|(&x, &y)| PlotPoint { x, y })
+                .collect(),
+        }
+    }
+}
+
+/// Reads a date as a decimal year. Accepts decimal years ("2014.5") and
+/// "YYYY-MM-DD", "YYYY-MM" or "YYYY/MM/DD" dates.
+pub(crate) fn decimal_year(text: &str) -> Option<Float> {
+    let text = text.trim();
+    if let Ok(year) = text.parse::<Float>() {
+        return year.is_finite().then_some(year);
+    }
+
+    let parts: Vec<&str> = text.split(['-', '/']).collect();
+    let year: i32 = parts.first()?.parse().ok()?;
+    let month: u32 = match parts.get(1) {
+        Some(month) => month.parse().ok().filter(|m| (1..=12).contains(m))?,
+        None => return None,
+    };
+    let day: u32 = match parts.get(2) {
+        Some(day) => day.parse().ok().filter(|d| (1..=31).contains(d))?,
+        None => 1,
+    };
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let month_days =
+        [31, 28 + leap as u32, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let day = day.min(month_days[month as usize - 1]);
+    let day_of_year: u32 =
+        month_days[..month as usize - 1].iter().sum::<u32>() + day - 1;
+    let year_days = 365 + leap as u32;
+    Some(year as Float + day_of_year as Float / year_days as Float)
+}
+
+/// Date embedded in a tip label. The pattern marks the date with `{date}` and
+/// may use `*` for any text, e.g. `*|{date}`. An empty pattern takes the last
+/// field of the label split on `|` or `_`.
+pub(crate) fn date_from_label(label: &str, pattern: &str) -> Option<Float> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return decimal_year(label.rsplit(['|', '_']).next()?);
+    }
+
+    let (prefix, suffix) = pattern.split_once(DATE_PLACEHOLDER)?;
+    let bounds: Vec<usize> = label
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(label.len()))
+        .collect();
+    for &beg in &bounds {
+        if !glob_match(prefix, &label[..beg]) {
+            continue;
+        }
+        for &end in bounds.iter().rev().filter(|&&end| end > beg) {
+            if glob_match(suffix, &label[end..])
+                && let Some(date) = decimal_year(&label[beg..end])
+            {
+                return Some(date);
+            }
+        }
+    }
+    None
+}
+
+/// Matches `text` against a pattern where `*` stands for any text.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((head, rest)) => {
+            let Some(text) = text.strip_prefix(head) else {
+                return false;
+            };
+            text.char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(text.len()))
+                .any(|i| glob_match(rest, &text[i..]))
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::HashSet;
+
+use crate::newick::{NwkNode, nwk_nodes_from_tree, nwk_string};
+use crate::*;
+
+/// Methods for placing the root of a tree without picking an outgroup node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootingMethod {
+    /// Middle of the longest tip-to-tip path.
+    Midpoint,
+    /// Middle of the longest branch.
+    LongestBranch,
+    /// Point minimizing the relative deviation of the ancestors of all tip
+    /// pairs from the midpoints between them (Tria et al. 2017).
+    MinAncestorDeviation,
+    /// Branch above the most recent common ancestor of the tips with these
+    /// labels. Labels missing from a tree are ignored.
+    Outgroup(Vec<String>),
+}
+
+pub(super) const ROOTING_METHOD_OPTS: [RootingMethod; 3] = [
+    RootingMethod::Midpoint,
+    RootingMethod::LongestBranch,
+    RootingMethod::MinAncestorDeviation,
+];
+
+impl Display for RootingMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            RootingMethod::Midpoint => "Midpoint",
+            RootingMethod::LongestBranch => "Longest Branch",
+            RootingMethod::MinAncestorDeviation => "Min. Ancestor Deviation",
+            RootingMethod::Outgroup(_) => "Outgroup",
+        })
+    }
+}
+
+/// Roots the tree with the given method and returns it as a NEWICK string.
+/// Returns `None` when the method cannot place a root on this tree.
+fn rooted_newick(tree: &Tree, method: &RootingMethod) -> Option<String> {
+    let ut = UnrootedTree::new(tree)?;
+    let (branch, pos) = match method {
+        RootingMethod::Midpoint => ut.midpoint(),
+        RootingMethod::LongestBranch => ut.longest_branch(),
+        RootingMethod::MinAncestorDeviation => ut.min_ancestor_deviation(),
+        RootingMethod::Outgroup(labels) => ut.outgroup(labels),
+    }?;
+    Some(ut.rooted_newick(branch, pos))
+}
+
+/// Roots each of the trees, keyed by tree id, and returns those the method
+/// could place a root on.
+pub(crate) fn rooted_trees(
+    trees: Vec<(usize, Tree)>,
+    method: &RootingMethod,
+) -> Vec<(usize, Tree)> {
+    trees
+        .into_iter()
+        .filter_map(|(id, tree)| {
+            let newick = rooted_newick(&tree, method)?;
+            let tre = parse_trees(newick).ok()?.into_iter().next()?;
+            Some((id, tre))
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct Branch {
+    a: usize,
+    b: usize,
+    brlen: TreeFloat,
+    attrs: Vec<(String, String)>,
+}
+
+/// Tree nodes connected by undirected branches. A root with two children is
+/// dissolved, joining its two branches into one. Trees without branch
+/// lengths use unit lengths.
+#[derive(Debug)]
+struct UnrootedTree {
+    nodes: Vec<NwkNode>,
+    is_tip: Vec<bool>,
+    branches: Vec<Branch>,
+    adj: Vec<Vec<usize>>,
+    has_brlen: bool,
+    // Orientation of the input tree, used to find common ancestors.
+    root: usize,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    branch_above: Vec<Option<usize>>,
+}
+
+impl UnrootedTree {
+    fn new(tree: &Tree) -> Option<Self> {
+        let (mut nodes, _, root) = nwk_nodes_from_tree(tree)?;
+        let has_brlen = tree.has_branch_lengths();
+        let n = nodes.len();
+
+        let children: Vec<Vec<usize>> = nodes
+            .iter_mut()
+            .map(|nd| std::mem::take(&mut nd.children))
+            .collect();
+        let is_tip: Vec<bool> = children.iter().map(Vec::is_empty).collect();
+        let mut parents: Vec<Option<usize>> = vec![None; n];
+        for (p, cs) in children.iter().enumerate() {
+            for &c in cs {
+                parents[c] = Some(p);
+            }
+        }
+
+        let take_branch = |nd: &mut NwkNode| {
+            let brlen = nd.brlen.take().unwrap_or(1e0).max(0e0);
+            (brlen, std::mem::take(&mut nd.branch_attrs))
+        };
+
+        let mut branches: Vec<Branch> = Vec::with_capacity(n);
+        let mut branch_above: Vec<Option<usize>> = vec![None; n];
+        let dissolve_root = children[root].len() == 2;
+        for (c, &p) in parents.iter().enumerate() {
+            let Some(p) = p else { continue };
+            if p == root && dissolve_root {
+                continue;
+            }
+            let (brlen, attrs) = take_branch(&mut nodes[c]);
+            branch_above[c] = Some(branches.len());
+            branches.push(Branch { a: c, b: p, brlen, attrs });
+        }
+
+        if let &[c1, c2] = &children[root][..] {
+            let (brlen1, attrs) = take_branch(&mut nodes[c1]);
+            let (brlen2, _) = take_branch(&mut nodes[c2]);
+            branch_above[c1] = Some(branches.len());
+            branch_above[c2] = Some(branches.len());
+            branches.push(Branch {
+                a: c1,
+                b: c2,
+                brlen: brlen1 + brlen2,
+                attrs,
+            });
+        }
+
+        if branches.is_empty() {
+            return None;
+        }
+
+        let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (e, br) in branches.iter().enumerate() {
+            adj[br.a].push(e);
+            adj[br.b].push(e);
+        }
+
+        Some(Self {
+            nodes,
+            is_tip,
+            branches,
+            adj,
+            has_brlen,
+            root,
+            parents,
+            children,
+            branch_above,
+        })
+    }
+
+    fn other_end(&self, branch: usize, node: usize) -> usize {
+        let br = &self.branches[branch];
+        if br.a == node { br.b } else { br.a }
+    }
+
+    /// Converts a distance from `node` along the branch to a distance from
+    /// the branch's `a` end.
+    fn pos_from(
+        &self,
+        branch: usize,
+        node: usize,
+        dist: TreeFloat,
+    ) -> TreeFloat {
+        let br = &self.branches[branch];
+        if br.a == node { dist } else { br.brlen - dist }
+    }
+
+    fn tips(&self) -> Vec<usize> {
+        (0..self.nodes.len()).filter(|&i| self.is_tip[i]).collect()
+    }
+
+    /// Distances from `start` to every node and, for each node, the branch
+    /// leading back towards `start`.
+    fn distances_from(
+        &self,
+        start: usize,
+    ) -> (Vec<TreeFloat>, Vec<Option<usize>>) {
+        let n = self.nodes.len();
+        let mut dist: Vec<TreeFloat> = vec![TreeFloat::INFINITY; n];
+        let mut via: Vec<Option<usize>> = vec![None; n];
+        dist[start] = 0e0;
+        let mut stack: Vec<usize> = vec![start];
+        while let Some(node) = stack.pop() {
+            for &e in &self.adj[node] {
+                let next = self.other_end(e, node);
+                if dist[next].is_infinite() {
+                    dist[next] = dist[node] + self.branches[e].brlen;
+                    via[next] = Some(e);
+                    stack.push(next);
+                }
+            }
+        }
+        (dist, via)
+    }
+
+    fn farthest_tip(&self, dist: &[TreeFloat]) -> Option<usize> {
+        self.tips().into_iter().max_by(|&i, &j| dist[i].total_cmp(&dist[j]))
+    }
+
+    // --- Methods -------------------------------------------------------------
+
+    fn midpoint(&self) -> Option<(usize, TreeFloat)> {
+        let tip = self.tips().into_iter().next()?;
+        let tip_a = self.farthest_tip(&self.distances_from(tip).0)?;
+        let (dist, via) = self.distances_from(tip_a);
+        let tip_b = self.farthest_tip(&dist)?;
+        let half = dist[tip_b] / 2e0;
+
+        let mut node = tip_b;
+        while let Some(e) = via[node] {
+            let next = self.other_end(e, node);
+            if dist[next] <= half {
+                return Some((e, self.pos_from(e, next, half - dist[next])));
+            }
+            node = next;
+        }
+        None
+    }
+
+    fn longest_branch(&self) -> Option<(usize, TreeFloat)> {
+        let (e, br) = self
+            .branches
+            .iter()
+            .enumerate()
+            .max_by(|(_, x), (_, y)| x.brlen.total_cmp(&y.brlen))?;
+        Some((e, br.brlen / 2e0))
+    }
+
+    fn outgroup(&self, labels: &[String]) -> Option<(usize, TreeFloat)> {
+        let labels: HashSet<&str> = labels.iter().map(String::as_str).collect();
+        let tips = self.tips();
+        let (og, ig): (Vec<usize>, Vec<usize>) =
+            tips.into_iter().partition(|&i| {
+                self.nodes[i]
+                    .label
+                    .as_deref()
+                    .is_some_and(|lab| labels.contains(lab))
+            });
+        if og.is_empty() || ig.is_empty() {
+            return None;
+        }
+
+        // When the outgroup spans the current root, the ingroup is a clade.
+        let mrca = self.mrca(&og).or_else(|| self.mrca(&ig))?;
+        let e = self.branch_above[mrca]?;
+        Some((e, self.branches[e].brlen / 2e0))
+    }
+
+    /// Most recent common ancestor of the tips in the orientation of the
+    /// input tree, or `None` if that is the root.
+    fn mrca(&self, tips: &[usize]) -> Option<usize> {
+        let mut counts: Vec<usize> = vec![0; self.nodes.len()];
+        for &tip in tips {
+            let mut node = Some(tip);
+            while let Some(i) = node {
+                counts[i] += 1;
+                node = self.parents[i];
+            }
+        }
+
+        let mut node = self.root;
+        while let Some(&c) =
+            self.children[node].iter().find(|&&c| counts[c] == tips.len())
+        {
+            node = c;
+        }
+        (node != self.root).then_some(node)
+    }
+
+    /// Finds the root position with the smallest mean squared relative
+    /// deviation of pairwise ancestors from the midpoints between tips.
+    fn min_ancestor_deviation(&self) -> Option<(usize, TreeFloat)> {
+        let tips = self.tips();
+        let nt = tips.len();
+        if nt < 2 {
+            return None;
+        }
+
+        let dists: Vec<Vec<TreeFloat>> =
+            tips.iter().map(|&t| self.distances_from(t).0).collect();
+
+        // Branches are oriented away from the first tip; `below[e][t]` tells
+        // whether tip `t` is on the lower side of branch `e`.
+        let (_, via) = self.distances_from(tips[0]);
+        let mut below: Vec<Vec<bool>> =
+            vec![vec![false; nt]; self.branches.len()];
+        for (t, &tip) in tips.iter().enumerate() {
+            let mut node = tip;
+            while let Some(e) = via[node] {
+                below[e][t] = true;
+                node = self.other_end(e, node);
+            }
+        }
+
+        (0..self.branches.len())
+            .into_par_iter()
+            .filter_map(|e| {
+                let br = &self.branches[e];
+                let lower = match via[br.a] == Some(e) {
+                    true => br.a,
+                    false => br.b,
+                };
+                let upper = self.other_end(e, lower);
+                let l = br.brlen;
+
+                let mut n_pairs: usize = 0;
+                let mut dev_sum: TreeFloat = 0e0;
+                let (mut w_sum, mut wk_sum, mut wkk_sum) = (0e0, 0e0, 0e0);
+                for b in 0..nt {
+                    for c in (b + 1)..nt {
+                        let d_bc = dists[b][tips[c]];
+                        if d_bc <= 0e0 {
+                            continue;
+                        }
+                        n_pairs += 1;
+                        match (below[e][b], below[e][c]) {
+                            (true, true) => {
+                                let r =
+                                    (dists[b][lower] - dists[c][lower]) / d_bc;
+                                dev_sum += r * r;
+                            }
+                            (false, false) => {
+                                let r =
+                                    (dists[b][upper] - dists[c][upper]) / d_bc;
+                                dev_sum += r * r;
+                            }
+                            (below_b, _) => {
+                                // Deviation is (2 * (d_bi + x) - d_bc) / d_bc,
+                                // with x the root's distance from `lower`.
+                                let d_bi = match below_b {
+                                    true => dists[b][lower],
+                                    false => dists[c][lower],
+                                };
+                                let w = 1e0 / (d_bc * d_bc);
+                                let k = 2e0 * d_bi - d_bc;
+                                w_sum += w;
+                                wk_sum += w * k;
+                                wkk_sum += w * k * k;
+                            }
+                        }
+                    }
+                }
+
+                if n_pairs == 0 || w_sum <= 0e0 {
+                    return None;
+                }
+
+                let x = (-wk_sum / (2e0 * w_sum)).clamp(0e0, l);
+                let dev =
+                    dev_sum + 4e0 * x * x * w_sum + 4e0 * x * wk_sum + wkk_sum;
+                let score = (dev / n_pairs as TreeFloat).sqrt();
+                Some((score, e, self.pos_from(e, lower, x)))
+            })
+            .min_by(|x, y| x.0.total_cmp(&y.0))
+            .map(|(_, e, pos)| (e, pos))
+    }
+
+    // -------------------------------------------------------------------------
+
+    /// Writes the tree rooted on `branch` at distance `pos` from its `a` end.
+    fn rooted_newick(mut self, branch: usize, pos: TreeFloat) -> String {
+        let has_brlen = self.has_brlen;
+        let Branch { a, b, brlen, ref attrs } = self.branches[branch];
+        let pos = pos.clamp(0e0, brlen);
+
+        let root = self.nodes.len();
+        self.nodes.push(NwkNode { children: vec![a, b], ..Default::default() });
+        for (node, len) in [(a, pos), (b, brlen - pos)] {
+            self.nodes[node].brlen = has_brlen.then_some(len);
+            self.nodes[node].branch_attrs = attrs.clone();
+        }
+
+        let mut stack: Vec<(usize, usize)> = vec![(a, branch), (b, branch)];
+        while let Some((node, from)) = stack.pop() {
+            for &e in &self.adj[node] {
+                if e == from {
+                    continue;
+                }
+                let br = &self.branches[e];
+                let child = if br.a == node { br.b } else { br.a };
+                self.nodes[node].children.push(child);
+                self.nodes[child].brlen = has_brlen.then_some(br.brlen);
+                self.nodes[child].branch_attrs = br.attrs.clone();
+                stack.push((child, e));
+            }
+        }
+
+        nwk_string(&self.nodes, root)
+    }
+}
@@ -1,8 +1,42 @@
 mod attributes;
 mod nodes;
 
+pub(crate) use nodes::NODES_TABLE_NO_ATTR;
+pub(crate) use nodes::NodeEdit;
+pub(crate) use nodes::NodesTableEdit;
 pub(crate) use nodes::NodesTableField;
+pub(crate) use nodes::NodesTableRow;
 pub(crate) use nodes::nodes_table;
+pub(crate) use nodes::nodes_table_text;
 
+pub(crate) use attributes::AttributeEdit;
+pub(crate) use attributes::AttributesTableEdit;
 pub(crate) use attributes::AttributesTableField;
 pub(crate) use attributes::attributes_table;
+pub(crate) use attributes::attributes_table_text;
+
+/// Data shown in a pane that can be exported as CSV or TSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataExport {
+    Plot,
+    NodesTable,
+    AttributesTable,
+}
+
+/// Rows of data written as CSV, or as TSV when `delimiter` is a tab.
+pub(crate) fn delimited_text(
+    header: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+    delimiter: u8,
+) -> String {
+    let mut writer =
+        csv::WriterBuilder::new().delimiter(delimiter).from_writer(Vec::new());
+    _ = writer.write_record(header);
+    for row in rows {
+        _ = writer.write_record(&row);
+    }
+    match writer.into_inner() {
+        Ok(bytes) => String::from_utf8(bytes).unwrap_or_default(),
+        Err(_) => String::new(),
+    }
+}
@@ -1,5 +1,9 @@
 use std::{cmp::Ordering, str::FromStr};
 
+use super::delimited_text;
+use crate::newick::{
+    attribute_text, is_valid_attribute_key, nwk_attribute_from_text,
+};
 use crate::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,6 +27,59 @@ impl From<AttributesTableField> for String {
     }
 }
 
+/// Change made to the attributes of a tree.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AttributeEdit {
+    /// Sets an attribute of the given nodes or their branches; `value` is
+    /// formatted as in NEWICK.
+    Set {
+        node_ids: Vec<NodeId>,
+        selector: AttributeSelector,
+        key: String,
+        value: String,
+    },
+    /// Removes both the node and the branch attribute from the given nodes.
+    Remove { node_ids: Vec<NodeId>, key: String },
+    /// Renames an attribute everywhere in the tree.
+    Rename { from: String, to: String },
+}
+
+/// Cell of the attributes table being edited and the text typed into it so
+/// far. Editing the name renames the attribute in the whole tree.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AttributesTableEdit {
+    pub(crate) tre_id: usize,
+    pub(crate) node_id: NodeId,
+    pub(crate) selector: AttributeSelector,
+    pub(crate) key: String,
+    pub(crate) field: AttributesTableField,
+    pub(crate) text: String,
+}
+
+impl AttributesTableEdit {
+    pub(crate) fn attribute_edit(&self) -> Option<AttributeEdit> {
+        let text = self.text.trim();
+        match self.field {
+            AttributesTableField::Name => {
+                match is_valid_attribute_key(text) && text != self.key {
+                    true => Some(AttributeEdit::Rename {
+                        from: self.key.clone(),
+                        to: text.to_string(),
+                    }),
+                    false => None,
+                }
+            }
+            AttributesTableField::Value => Some(AttributeEdit::Set {
+                node_ids: vec![self.node_id],
+                selector: self.selector,
+                key: self.key.clone(),
+                value: nwk_attribute_from_text(text),
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AttributesTableRowData {
     name: String,
@@ -46,6 +103,96 @@ impl From<(&String, &Attribute, AttributeSelector, NodeId)>
     }
 }
 
+/// Attributes of the selected nodes and their branches, sorted the way the
+/// attributes table is.
+fn attributes_table_rows(
+    tv: &TreeView,
+    ts: &TreeState,
+) -> Vec<AttributesTableRowData> {
+    let node_attr_rows = ts
+        .sel_node_ids()
+        .iter()
+        .flat_map(|&node_id| {
+            ts.tree()
+                .node_attributes(node_id)
+                .iter()
+                .map(move |(k, v)| (k, v, node_id))
+        })
+        .map(|(k, v, node_id)| {
+            AttributesTableRowData::from((
+                k,
+                v,
+                AttributeSelector::Node,
+                node_id,
+            ))
+        });
+
+    let branch_attr_rows = ts
+        .sel_node_ids()
+        .iter()
+        .flat_map(|&node_id| {
+            ts.tree()
+                .branch_attributes(node_id)
+                .iter()
+                .map(move |(k, v)| (k, v, node_id))
+        })
+        .map(|(k, v, node_id)| {
+            AttributesTableRowData::from((
+                k,
+                v,
+                AttributeSelector::Branch,
+                node_id,
+            ))
+        });
+
+    let mut rows: Vec<AttributesTableRowData> = Vec::new();
+    rows.extend(node_attr_rows);
+    rows.extend(branch_attr_rows);
+
+    let sorting_order = |ord: Ordering| match tv.attributes_table_sort_ord {
+        SortOrder::Ascending => ord,
+        SortOrder::Descending => ord.reverse(),
+    };
+
+    match tv.attributes_table_sort_col {
+        AttributesTableField::Selected => (),
+        AttributesTableField::NodeId => {
+            rows.sort_by(|a, b| sorting_order(a.node_id.cmp(&b.node_id)));
+        }
+        AttributesTableField::Selector => {
+            rows.sort_by(|a, b| sorting_order(a.selector.cmp(&b.selector)));
+        }
+        AttributesTableField::Name => {
+            rows.sort_by(|a, b| sorting_order(a.name.cmp(&b.name)));
+        }
+        AttributesTableField::Value => {
+            rows.sort_by(|a, b| {
+                sorting_order(
+                    a.attribute
+                        .partial_cmp(&b.attribute)
+                        .unwrap_or(Ordering::Equal),
+                )
+            });
+        }
+    };
+
+    rows
+}
+
+/// Rows of the attributes table, in its current sort order, as delimited
+/// text.
+pub(crate) fn attributes_table_text(
+    tv: &TreeView,
+    ts: &TreeState,
+    delimiter: u8,
+) -> String {
+    let rows = attributes_table_rows(tv, ts).into_iter().map(|row| {
+        let value = attribute_text(&row.attribute);
+        vec![row.selector.to_string(), row.node_id.to_string(), row.name, value]
+    });
+    delimited_text(&["selector", "node_id", "name", "value"], rows, delimiter)
+}
+
 pub(crate) fn attributes_table<'a>(
     tv: &'a TreeView,
     ts: Rc<TreeState>,
@@ -53,80 +200,10 @@ pub(crate) fn attributes_table<'a>(
     h: f32,
 ) -> Element<'a, TvMsg> {
     let fn_visible_rows = |start_idx: usize, max_to_return: usize| {
-        let node_attr_rows = ts
-            .sel_node_ids()
-            .iter()
-            .flat_map(|&node_id| {
-                ts.tree()
-                    .node_attributes(node_id)
-                    .iter()
-                    .map(move |(k, v)| (k, v, node_id))
-            })
-            .map(|(k, v, node_id)| {
-                AttributesTableRowData::from((
-                    k,
-                    v,
-                    AttributeSelector::Node,
-                    node_id,
-                ))
-            });
-
-        let branch_attr_rows = ts
-            .sel_node_ids()
-            .iter()
-            .flat_map(|&node_id| {
-                ts.tree()
-                    .branch_attributes(node_id)
-                    .iter()
-                    .map(move |(k, v)| (k, v, node_id))
-            })
-            .map(|(k, v, node_id)| {
-                AttributesTableRowData::from((
-                    k,
-                    v,
-                    AttributeSelector::Branch,
-                    node_id,
-                ))
-            });
-
-        let mut visible_rows: Vec<AttributesTableRowData> = Vec::new();
-        visible_rows.extend(node_attr_rows);
-        visible_rows.extend(branch_attr_rows);
-
-        let sorting_order = |ord: Ordering| match tv.attributes_table_sort_ord {
-            SortOrder::Ascending => ord,
-            SortOrder::Descending => ord.reverse(),
-        };
-
-        match tv.attributes_table_sort_col {
-            AttributesTableField::Selected => (),
-            AttributesTableField::NodeId => {
-                visible_rows
-                    .sort_by(|a, b| sorting_order(a.node_id.cmp(&b.node_id)));
-            }
-            AttributesTableField::Selector => {
-                visible_rows
-                    .sort_by(|a, b| sorting_order(a.selector.cmp(&b.selector)));
-            }
-            AttributesTableField::Name => {
-                visible_rows.sort_by(|a, b| sorting_order(a.name.cmp(&b.name)));
-            }
-            AttributesTableField::Value => {
-                visible_rows.sort_by(|a, b| {
-                    sorting_order(
-                        a.attribute
-                            .partial_cmp(&b.attribute)
-                            .unwrap_or(Ordering::Equal),
-                    )
-                });
-            }
-        };
-
-        visible_rows
-            .iter()
+        attributes_table_rows(tv, &ts)
+            .into_iter()
             .skip(start_idx)
             .take(max_to_return)
-            .cloned()
             .collect()
     };
 
@@ -159,8 +236,8 @@ pub(crate) fn attributes_table<'a>(
 }
 
 fn attributes_table_columns_spec<'a>(
-    _ts: Rc<TreeState>,
-    tv: &TreeView,
+    ts: Rc<TreeState>,
+    tv: &'a TreeView,
 ) -> Vec<TableColumnSpecification<'a, TvMsg, AttributesTableRowData>> {
     let mut columns: Vec<TableColumnSpecification<'_, _, _>> = vec![];
 
@@ -182,6 +259,38 @@ fn attributes_table_columns_spec<'a>(
         };
         // ToDo
         let fn_is_selected = |_key: String| (false, None);
+
+        // Clicking a name or a value starts editing it.
+        let fn_edit_msg = move |kv: &AttributesTableRowData| {
+            Some(TvMsg::AttributesTableEditBegin(
+                kv.node_id,
+                kv.selector,
+                kv.name.clone(),
+                f,
+            ))
+        };
+
+        let tre_id = ts.id();
+        let fn_edit_input = move |kv: &AttributesTableRowData| {
+            tv.attributes_table_edit
+                .as_ref()
+                .filter(|edit| {
+                    edit.tre_id == tre_id
+                        && edit.node_id == kv.node_id
+                        && edit.selector == kv.selector
+                        && edit.key == kv.name
+                        && edit.field == f
+                })
+                .map(|edit| -> Element<'a, TvMsg> {
+                    txt_input_table_cell(
+                        &edit.text,
+                        tv.attributes_table_edit_input_id,
+                        TvMsg::AttributesTableEditChanged,
+                    )
+                    .on_submit(TvMsg::AttributesTableEditSubmitted)
+                    .into()
+                })
+        };
         let fn_cell_data: Box<
             dyn Fn(AttributesTableRowData) -> TableCell<'a, TvMsg> + 'a,
         > = match f {
@@ -225,20 +334,34 @@ fn attributes_table_columns_spec<'a>(
             }
             AttributesTableField::Name => {
                 Box::new(move |kv: AttributesTableRowData| {
-                    let (is_selected, select_msg) =
-                        fn_is_selected(kv.name.clone());
+                    if let Some(cell_content) = fn_edit_input(&kv) {
+                        return TableCell {
+                            cell_content,
+                            is_selected: true,
+                            select_msg: None,
+                        };
+                    }
+                    let (is_selected, _) = fn_is_selected(kv.name.clone());
                     TableCell {
                         cell_content: txt(kv.name.clone())
                             .size(TABLE_TXT_SIZE)
                             .into(),
                         is_selected,
-                        select_msg,
+                        select_msg: fn_edit_msg(&kv),
                     }
                 })
             }
             AttributesTableField::Value => {
                 Box::new(move |kv: AttributesTableRowData| {
-                    let (is_selected, select_msg) = fn_is_selected(kv.name);
+                    if let Some(cell_content) = fn_edit_input(&kv) {
+                        return TableCell {
+                            cell_content,
+                            is_selected: true,
+                            select_msg: None,
+                        };
+                    }
+                    let select_msg = fn_edit_msg(&kv);
+                    let (is_selected, _) = fn_is_selected(kv.name);
                     TableCell {
                         cell_content: match kv.attribute {
                             Attribute::Value(attr_val) => {
@@ -1,5 +1,10 @@
+use super::delimited_text;
+use crate::newick::attribute_text;
 use crate::*;
 
+/// Entry of the attribute column pick list that hides the column.
+pub(crate) const NODES_TABLE_NO_ATTR: &str = "None";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NodesTableField {
     Selected,
@@ -7,6 +12,11 @@ pub enum NodesTableField {
     NodeType,
     BranchLength,
     NodeLabel,
+    Depth,
+    Height,
+    TipCount,
+    ParentId,
+    Attribute,
 }
 
 impl From<NodesTableField> for String {
@@ -17,10 +27,118 @@ impl From<NodesTableField> for String {
             NodesTableField::NodeType => "Type".to_string(),
             NodesTableField::BranchLength => "Branch Length".to_string(),
             NodesTableField::NodeLabel => "Label".to_string(),
+            NodesTableField::Depth => "Depth".to_string(),
+            NodesTableField::Height => "Height".to_string(),
+            NodesTableField::TipCount => "Tips".to_string(),
+            NodesTableField::ParentId => "Parent".to_string(),
+            NodesTableField::Attribute => "Attribute".to_string(),
         }
     }
 }
 
+/// Row of the nodes table: an edge with the values derived from its position
+/// in the tree.
+#[derive(Debug, Clone)]
+pub(crate) struct NodesTableRow {
+    pub(crate) edge: Edge,
+    /// Distance from the first node; edge count for trees without branch
+    /// lengths.
+    pub(crate) depth: TreeFloat,
+    /// Distance to the farthest tip of the tree below the depth of this node,
+    /// i.e. its age in ultrametric trees.
+    pub(crate) height: TreeFloat,
+    pub(crate) tip_count: usize,
+    /// Value of the attribute chosen for the attribute column.
+    pub(crate) attribute: Option<Attribute>,
+}
+
+impl NodesTableRow {
+    pub(crate) fn parent_node_id(&self) -> Option<NodeId> {
+        match self.edge.parent_node_id == self.edge.node_id {
+            true => None,
+            false => Some(self.edge.parent_node_id),
+        }
+    }
+
+    pub(crate) fn attribute_text(&self) -> Option<String> {
+        self.attribute.as_ref().map(attribute_text)
+    }
+
+    /// Whether any of the label, node id or attribute value contains the
+    /// (lowercase) query.
+    pub(crate) fn matches(&self, query: &str) -> bool {
+        self.edge
+            .label
+            .as_deref()
+            .is_some_and(|label| label.to_lowercase().contains(query))
+            || self.edge.node_id.to_string().contains(query)
+            || self
+                .attribute_text()
+                .is_some_and(|text| text.to_lowercase().contains(query))
+    }
+}
+
+/// Change made to a node from the nodes table.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum NodeEdit {
+    Label(Option<String>),
+    BranchLength(TreeFloat),
+}
+
+/// Cell of the nodes table being edited and the text typed into it so far.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NodesTableEdit {
+    pub(crate) tre_id: usize,
+    pub(crate) node_id: NodeId,
+    pub(crate) field: NodesTableField,
+    pub(crate) text: String,
+}
+
+impl NodesTableEdit {
+    /// The edit the typed text stands for; `None` for branch lengths that are
+    /// not non-negative numbers.
+    pub(crate) fn node_edit(&self) -> Option<NodeEdit> {
+        let text = self.text.trim();
+        match self.field {
+            NodesTableField::NodeLabel => {
+                Some(NodeEdit::Label(match text.is_empty() {
+                    true => None,
+                    false => Some(text.to_string()),
+                }))
+            }
+            NodesTableField::BranchLength => text
+                .parse::<TreeFloat>()
+                .ok()
+                .filter(|brlen| brlen.is_finite() && *brlen >= 0e0)
+                .map(NodeEdit::BranchLength),
+            _ => None,
+        }
+    }
+}
+
+/// Fields shown in the nodes table, in order.
+fn nodes_table_fields(ts: &TreeState, tv: &TreeView) -> Vec<NodesTableField> {
+    [
+        NodesTableField::Selected,
+        NodesTableField::NodeId,
+        NodesTableField::NodeType,
+        NodesTableField::ParentId,
+        NodesTableField::TipCount,
+        NodesTableField::Depth,
+        NodesTableField::Height,
+        NodesTableField::BranchLength,
+        NodesTableField::Attribute,
+        NodesTableField::NodeLabel,
+    ]
+    .into_iter()
+    .filter(|f| match f {
+        NodesTableField::BranchLength => ts.has_brlen(),
+        NodesTableField::Attribute => tv.nodes_table_attr.is_some(),
+        _ => true,
+    })
+    .collect()
+}
+
 pub(crate) fn nodes_table<'a>(
     tv: &'a TreeView,
     ts: Rc<TreeState>,
@@ -28,10 +146,11 @@ pub(crate) fn nodes_table<'a>(
     h: f32,
 ) -> Element<'a, TvMsg> {
     let fn_visible_rows = |start_idx: usize, max_to_return: usize| {
-        ts.edges_in_range(start_idx, max_to_return).unwrap_or_default()
+        ts.nodes_table_rows_in_range(start_idx, max_to_return)
+            .unwrap_or_default()
     };
 
-    let fn_total_row_count = || ts.edge_count();
+    let fn_total_row_count = || ts.nodes_table_row_count();
 
     table(
         nodes_table_columns_spec(ts.clone(), tv),
@@ -45,128 +164,305 @@ pub(crate) fn nodes_table<'a>(
     )
 }
 
+/// Rows of the nodes table, filtered and in its current sort order, as
+/// delimited text.
+pub(crate) fn nodes_table_text(
+    tv: &TreeView,
+    ts: &TreeState,
+    delimiter: u8,
+) -> String {
+    let fields = nodes_table_fields(ts, tv);
+    let header: Vec<&str> = fields
+        .iter()
+        .map(|f| match f {
+            NodesTableField::Selected => "selected",
+            NodesTableField::NodeId => "node_id",
+            NodesTableField::NodeType => "type",
+            NodesTableField::BranchLength => "branch_length",
+            NodesTableField::NodeLabel => "label",
+            NodesTableField::Depth => "depth",
+            NodesTableField::Height => "height",
+            NodesTableField::TipCount => "tip_count",
+            NodesTableField::ParentId => "parent_id",
+            NodesTableField::Attribute => {
+                tv.nodes_table_attr.as_deref().unwrap_or_default()
+            }
+        })
+        .collect();
+
+    let rows = ts.nodes_table_rows().map(Vec::as_slice).unwrap_or_default();
+    let rows = rows.iter().map(|row| {
+        let e = &row.edge;
+        fields
+            .iter()
+            .map(|f| match f {
+                NodesTableField::Selected => {
+                    ts.sel_node_ids().contains(&e.node_id).to_string()
+                }
+                NodesTableField::NodeId => e.node_id.to_string(),
+                NodesTableField::NodeType => {
+                    match ts.tree().node(Some(e.node_id)) {
+                        Some(node) => node.node_type().to_string(),
+                        None => String::new(),
+                    }
+                }
+                NodesTableField::BranchLength => e.branch_length.to_string(),
+                NodesTableField::NodeLabel => {
+                    e.label.as_deref().unwrap_or_default().to_string()
+                }
+                NodesTableField::Depth => row.depth.to_string(),
+                NodesTableField::Height => row.height.to_string(),
+                NodesTableField::TipCount => row.tip_count.to_string(),
+                NodesTableField::ParentId => row
+                    .parent_node_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+                NodesTableField::Attribute => {
+                    row.attribute_text().unwrap_or_default()
+                }
+            })
+            .collect::<Vec<String>>()
+    });
+
+    delimited_text(&header, rows, delimiter)
+}
+
 fn nodes_table_columns_spec<'a>(
     ts: Rc<TreeState>,
     tv: &'a TreeView,
-) -> Vec<TableColumnSpecification<'a, TvMsg, Edge>> {
-    let mut columns: Vec<TableColumnSpecification<'a, TvMsg, Edge>> = vec![];
+) -> Vec<TableColumnSpecification<'a, TvMsg, NodesTableRow>> {
+    let mut columns: Vec<TableColumnSpecification<'a, TvMsg, NodesTableRow>> =
+        vec![];
 
-    let column_order = [
-        NodesTableField::Selected,
-        NodesTableField::NodeId,
-        NodesTableField::NodeType,
-        NodesTableField::BranchLength,
-        NodesTableField::NodeLabel,
-    ];
+    nodes_table_fields(&ts, tv).into_iter().for_each(|f| {
+        let fn_is_selected = |node_id: NodeId, ts: Rc<TreeState>| {
+            ts.sel_node_ids().contains(&node_id)
+        };
 
-    column_order.iter().for_each(|&f| {
-        let include_field = match f {
-            NodesTableField::BranchLength => ts.has_brlen(),
-            _ => true,
+        let fn_select_msg = |node_id: NodeId| {
+            Some(match tv.cfg.selection_lock {
+                true => TvMsg::SelectDeselectNode(node_id),
+                false => TvMsg::SelectDeselectNodeExclusive(node_id),
+            })
         };
 
-        if include_field {
-            let fn_is_selected = |node_id: NodeId, ts: Rc<TreeState>| {
-                ts.sel_node_ids().contains(&node_id)
+        // Clicking a label or branch length of the only selected node starts
+        // editing it.
+        let fn_edit_or_select_msg =
+            move |node_id: NodeId, ts: Rc<TreeState>| match ts
+                .sel_node_ids()
+                .len()
+                == 1
+                && ts.sel_node_ids().contains(&node_id)
+            {
+                true => Some(TvMsg::NodesTableEditBegin(node_id, f)),
+                false => fn_select_msg(node_id),
             };
 
-            let fn_select_msg = |node_id: NodeId| {
-                Some(match tv.cfg.selection_lock {
-                    true => TvMsg::SelectDeselectNode(node_id),
-                    false => TvMsg::SelectDeselectNodeExclusive(node_id),
+        let fn_edit_input = move |node_id: NodeId, ts: Rc<TreeState>| {
+            tv.nodes_table_edit
+                .as_ref()
+                .filter(|edit| {
+                    edit.tre_id == ts.id()
+                        && edit.node_id == node_id
+                        && edit.field == f
+                        && ts.sel_node_ids().contains(&node_id)
                 })
-            };
+                .map(|edit| -> Element<'a, TvMsg> {
+                    txt_input_table_cell(
+                        &edit.text,
+                        tv.nodes_table_edit_input_id,
+                        TvMsg::NodesTableEditChanged,
+                    )
+                    .on_submit(TvMsg::NodesTableEditSubmitted)
+                    .into()
+                })
+        };
 
-            let fn_cell_data: Box<dyn Fn(Edge) -> TableCell<'a, TvMsg> + 'a>;
-            let mut width: f32 = 8e0 * TABLE_TXT_SIZE;
-            let ts = ts.clone();
-            match f {
-                NodesTableField::Selected => {
-                    width = 3e0 * TABLE_TXT_SIZE;
-                    fn_cell_data = Box::new(move |e: Edge| {
-                        let is_selected = fn_is_selected(e.node_id, ts.clone());
-                        TableCell {
-                            cell_content: txt_bool(is_selected)
-                                .size(TABLE_TXT_SIZE)
-                                .into(),
-                            is_selected,
-                            select_msg: fn_select_msg(e.node_id),
-                        }
-                    });
-                }
+        let fn_cell_data: Box<
+            dyn Fn(NodesTableRow) -> TableCell<'a, TvMsg> + 'a,
+        >;
+        let mut width: f32 = 8e0 * TABLE_TXT_SIZE;
+        let ts = ts.clone();
+        match f {
+            NodesTableField::Selected => {
+                width = 3e0 * TABLE_TXT_SIZE;
+                fn_cell_data = Box::new(move |row: NodesTableRow| {
+                    let is_selected =
+                        fn_is_selected(row.edge.node_id, ts.clone());
+                    TableCell {
+                        cell_content: txt_bool(is_selected)
+                            .size(TABLE_TXT_SIZE)
+                            .into(),
+                        is_selected,
+                        select_msg: fn_select_msg(row.edge.node_id),
+                    }
+                });
+            }
+
+            NodesTableField::NodeId => {
+                width = 6e0 * TABLE_TXT_SIZE;
+                fn_cell_data = Box::new(move |row: NodesTableRow| TableCell {
+                    cell_content: txt(row.edge.node_id)
+                        .size(TABLE_TXT_SIZE)
+                        .into(),
+                    is_selected: fn_is_selected(row.edge.node_id, ts.clone()),
+                    select_msg: fn_select_msg(row.edge.node_id),
+                });
+            }
 
-                NodesTableField::NodeId => {
-                    width = 6e0 * TABLE_TXT_SIZE;
-                    fn_cell_data = Box::new(move |e: Edge| TableCell {
-                        cell_content: txt(e.node_id)
+            NodesTableField::NodeType => {
+                width = 5e0 * TABLE_TXT_SIZE;
+                fn_cell_data = Box::new(move |row: NodesTableRow| {
+                    let node_opt = ts.tree().node(Some(row.edge.node_id));
+                    let node_type = if let Some(node) = node_opt {
+                        node.node_type().to_string()
+                    } else {
+                        "-".to_string()
+                    };
+
+                    TableCell {
+                        cell_content: txt(node_type)
                             .size(TABLE_TXT_SIZE)
                             .into(),
-                        is_selected: fn_is_selected(e.node_id, ts.clone()),
-                        select_msg: fn_select_msg(e.node_id),
-                    });
-                }
+                        is_selected: fn_is_selected(
+                            row.edge.node_id,
+                            ts.clone(),
+                        ),
+                        select_msg: fn_select_msg(row.edge.node_id),
+                    }
+                });
+            }
 
-                NodesTableField::NodeType => {
-                    width = 5e0 * TABLE_TXT_SIZE;
-                    fn_cell_data = Box::new(move |e: Edge| {
-                        let node_opt = ts.tree().node(Some(e.node_id));
-                        let node_type = if let Some(node) = node_opt {
-                            node.node_type().to_string()
-                        } else {
-                            "-".to_string()
-                        };
-
-                        TableCell {
-                            cell_content: txt(node_type)
+            NodesTableField::ParentId => {
+                width = 6e0 * TABLE_TXT_SIZE;
+                fn_cell_data = Box::new(move |row: NodesTableRow| TableCell {
+                    cell_content: txt(match row.parent_node_id() {
+                        Some(node_id) => node_id.to_string(),
+                        None => "-".to_string(),
+                    })
+                    .size(TABLE_TXT_SIZE)
+                    .into(),
+                    is_selected: fn_is_selected(row.edge.node_id, ts.clone()),
+                    select_msg: fn_select_msg(row.edge.node_id),
+                });
+            }
+
+            NodesTableField::TipCount => {
+                width = 5e0 * TABLE_TXT_SIZE;
+                fn_cell_data = Box::new(move |row: NodesTableRow| TableCell {
+                    cell_content: txt_usize(row.tip_count)
+                        .size(TABLE_TXT_SIZE)
+                        .into(),
+                    is_selected: fn_is_selected(row.edge.node_id, ts.clone()),
+                    select_msg: fn_select_msg(row.edge.node_id),
+                });
+            }
+
+            NodesTableField::Depth | NodesTableField::Height => {
+                width = 7e0 * TABLE_TXT_SIZE;
+                fn_cell_data = Box::new(move |row: NodesTableRow| {
+                    let value = match f {
+                        NodesTableField::Depth => row.depth,
+                        _ => row.height,
+                    };
+                    TableCell {
+                        cell_content: txt_float(value, 3)
+                            .size(TABLE_TXT_SIZE)
+                            .into(),
+                        is_selected: fn_is_selected(
+                            row.edge.node_id,
+                            ts.clone(),
+                        ),
+                        select_msg: fn_select_msg(row.edge.node_id),
+                    }
+                });
+            }
+
+            NodesTableField::BranchLength => {
+                width = 7e0 * TABLE_TXT_SIZE;
+                fn_cell_data = Box::new(move |row: NodesTableRow| {
+                    let node_id = row.edge.node_id;
+                    match fn_edit_input(node_id, ts.clone()) {
+                        Some(cell_content) => TableCell {
+                            cell_content,
+                            is_selected: true,
+                            select_msg: None,
+                        },
+                        None => TableCell {
+                            cell_content: txt_float(row.edge.branch_length, 3)
                                 .size(TABLE_TXT_SIZE)
                                 .into(),
-                            is_selected: fn_is_selected(e.node_id, ts.clone()),
-                            select_msg: fn_select_msg(e.node_id),
-                        }
-                    });
-                }
+                            is_selected: fn_is_selected(node_id, ts.clone()),
+                            select_msg: fn_edit_or_select_msg(
+                                node_id,
+                                ts.clone(),
+                            ),
+                        },
+                    }
+                });
+            }
+
+            NodesTableField::Attribute => {
+                fn_cell_data = Box::new(move |row: NodesTableRow| TableCell {
+                    cell_content: txt(row
+                        .attribute_text()
+                        .unwrap_or("-".to_string()))
+                    .size(TABLE_TXT_SIZE)
+                    .into(),
+                    is_selected: fn_is_selected(row.edge.node_id, ts.clone()),
+                    select_msg: fn_select_msg(row.edge.node_id),
+                });
+            }
 
-                NodesTableField::BranchLength => {
-                    width = 7e0 * TABLE_TXT_SIZE;
-                    fn_cell_data = Box::new(move |e: Edge| TableCell {
-                        cell_content: txt_float(e.branch_length, 3)
+            NodesTableField::NodeLabel => {
+                fn_cell_data = Box::new(move |row: NodesTableRow| {
+                    let node_id = row.edge.node_id;
+                    match fn_edit_input(node_id, ts.clone()) {
+                        Some(cell_content) => TableCell {
+                            cell_content,
+                            is_selected: true,
+                            select_msg: None,
+                        },
+                        None => TableCell {
+                            cell_content: txt(row
+                                .edge
+                                .label
+                                .unwrap_or("-".into())
+                                .to_string())
                             .size(TABLE_TXT_SIZE)
                             .into(),
-                        is_selected: fn_is_selected(e.node_id, ts.clone()),
-                        select_msg: fn_select_msg(e.node_id),
-                    });
-                }
+                            is_selected: fn_is_selected(node_id, ts.clone()),
+                            select_msg: fn_edit_or_select_msg(
+                                node_id,
+                                ts.clone(),
+                            ),
+                        },
+                    }
+                });
+            }
+        };
 
-                NodesTableField::NodeLabel => {
-                    fn_cell_data = Box::new(move |e: Edge| TableCell {
-                        cell_content: txt(e
-                            .label
-                            .unwrap_or("-".into())
-                            .to_string())
-                        .size(TABLE_TXT_SIZE)
-                        .into(),
-                        is_selected: fn_is_selected(e.node_id, ts.clone()),
-                        select_msg: fn_select_msg(e.node_id),
-                    });
-                }
-            };
+        let sort_order = if tv.nodes_table_sort_col == f {
+            Some(tv.nodes_table_sort_ord)
+        } else {
+            None
+        };
 
-            let sort_order = if tv.nodes_table_sort_col == f {
-                Some(tv.nodes_table_sort_ord)
-            } else {
-                None
-            };
+        let header_text = match (f, &tv.nodes_table_attr) {
+            (NodesTableField::Attribute, Some(key)) => key.clone(),
+            _ => f.into(),
+        };
 
-            let col_spec = TableColumnSpecification {
-                header_text: f.into(),
-                sort_order,
-                sort_msg: TvMsg::NodesTableSortColumnChanged(f),
-                width,
-                fn_cell_data,
-            };
+        let col_spec = TableColumnSpecification {
+            header_text,
+            sort_order,
+            sort_msg: TvMsg::NodesTableSortColumnChanged(f),
+            width,
+            fn_cell_data,
+        };
 
-            columns.push(col_spec);
-        }
+        columns.push(col_spec);
     });
 
     columns
@@ -0,0 +1,525 @@
+use crate::cnv_utils::{categorical_color, sequential_color};
+use crate::edge_utils::{edge_angle, point_pol};
+use crate::legend::Legend;
+use crate::*;
+use std::collections::HashMap;
+
+/// How a metadata column is drawn beside the tip labels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TrackType {
+    #[default]
+    Off,
+    /// One colored cell per tip. Numeric columns use a continuous color
+    /// scale, other columns one color per distinct value.
+    Heatmap,
+    /// Horizontal bars with lengths proportional to the values.
+    Bar,
+    /// Filled (present) or empty (absent) squares.
+    Binary,
+    /// The values written out as text.
+    Text,
+}
+
+pub(super) const TRACK_TYPE_OPTS: [TrackType; 5] = [
+    TrackType::Off,
+    TrackType::Heatmap,
+    TrackType::Bar,
+    TrackType::Binary,
+    TrackType::Text,
+];
+
+impl Display for TrackType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            TrackType::Off => "Off",
+            TrackType::Heatmap => "Heatmap",
+            TrackType::Bar => "Bars",
+            TrackType::Binary => "Binary",
+            TrackType::Text => "Text",
+        })
+    }
+}
+
+// --- Metadata ----------------------------------------------------------------
+
+/// Table of tip metadata read from a CSV or TSV file. The first column holds
+/// the tip labels.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Metadata {
+    columns: Vec<String>,
+    rows: HashMap<String, Vec<String>>,
+}
+
+impl Metadata {
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let text = text.trim_start_matches('\u{feff}');
+        let delimiter = match text.lines().next()?.contains('\t') {
+            true => b'\t',
+            false => b',',
+        };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+
+        let columns: Vec<String> =
+            reader.headers().ok()?.iter().skip(1).map(String::from).collect();
+        if columns.is_empty() {
+            return None;
+        }
+
+        let mut rows: HashMap<String, Vec<String>> = HashMap::new();
+        for record in reader.records().flatten() {
+            let mut fields = record.iter();
+            let Some(tip) = fields.next().filter(|tip| !tip.is_empty()) else {
+                continue;
+            };
+            let mut values: Vec<String> = fields.map(String::from).collect();
+            values.resize(columns.len(), String::new());
+            _ = rows.insert(tip.to_string(), values);
+        }
+
+        match rows.is_empty() {
+            true => None,
+            false => Some(Self { columns, rows }),
+        }
+    }
+
+    pub(crate) fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub(crate) fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Distinct non-empty values in a column, sorted.
+    pub(crate) fn column_states(&self, col: usize) -> Vec<String> {
+        let mut states: Vec<String> =
+            self.column_values(col).map(|(_, v)| v.to_string()).collect();
+        states.sort();
+        states.dedup();
+        states
+    }
+
+    pub(crate) fn value(&self, tip: &str, col: usize) -> Option<&str> {
+        self.rows
+            .get(tip)?
+            .get(col)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Non-empty values in a column, keyed by tip label.
+    fn column_values(&self, col: usize) -> impl Iterator<Item = (&str, &str)> {
+        self.rows.iter().filter_map(move |(tip, values)| {
+            let value = values.get(col)?.as_str();
+            match value.is_empty() {
+                true => None,
+                false => Some((tip.as_str(), value)),
+            }
+        })
+    }
+}
+
+fn parse_num(value: &str) -> Option<Float> {
+    value.parse::<Float>().ok().filter(|v| v.is_finite())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "yes" | "y" | "true" | "t" | "1" | "present" | "+" => Some(true),
+        "no" | "n" | "false" | "f" | "0" | "absent" | "-" => Some(false),
+        _ => None,
+    }
+}
+
+// --- Tracks ------------------------------------------------------------------
+
+const TRACK_CELL_W: Float = SF * 12e0;
+const TRACK_BAR_W: Float = SF * 60e0;
+const TRACK_GAP: Float = SF * 4e0;
+const TRACK_TXT_SIZE: Float = SF * 10e0;
+const TRACK_LEGEND_CAT_MAX: usize = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TrackValue {
+    Num(Float),
+    Cat(usize),
+    Bool(bool),
+    Text(String),
+}
+
+/// A metadata column prepared for drawing.
+#[derive(Debug, Clone)]
+pub(crate) struct Track {
+    name: String,
+    track_type: TrackType,
+    values: HashMap<String, TrackValue>,
+    categories: Vec<String>,
+    min: Float,
+    max: Float,
+    color: Color,
+    max_nchar: usize,
+}
+
+impl Track {
+    pub(crate) fn new(
+        metadata: &Metadata,
+        col: usize,
+        track_type: TrackType,
+        color_idx: usize,
+    ) -> Option<Self> {
+        if track_type == TrackType::Off {
+            return None;
+        }
+
+        let name = metadata.columns.get(col)?.clone();
+        let numeric =
+            metadata.column_values(col).all(|(_, v)| parse_num(v).is_some());
+
+        let mut categories: Vec<String> = Vec::new();
+        if track_type == TrackType::Heatmap && !numeric {
+            categories =
+                metadata.column_values(col).map(|(_, v)| v.into()).collect();
+            categories.sort();
+            categories.dedup();
+        }
+
+        let values: HashMap<String, TrackValue> = metadata
+            .column_values(col)
+            .filter_map(|(tip, v)| {
+                let value = match track_type {
+                    TrackType::Off => None,
+                    TrackType::Heatmap if !numeric => categories
+                        .binary_search_by(|c| c.as_str().cmp(v))
+                        .ok()
+                        .map(TrackValue::Cat),
+                    TrackType::Heatmap | TrackType::Bar => {
+                        parse_num(v).map(TrackValue::Num)
+                    }
+                    TrackType::Binary => parse_bool(v).map(TrackValue::Bool),
+                    TrackType::Text => Some(TrackValue::Text(v.into())),
+                }?;
+                Some((tip.to_string(), value))
+            })
+            .collect();
+
+        let (mut min, mut max) = (Float::INFINITY, Float::NEG_INFINITY);
+        for value in values.values() {
+            if let TrackValue::Num(v) = value {
+                min = min.min(*v);
+                max = max.max(*v);
+            }
+        }
+        if min > max {
+            (min, max) = (ZRO, ZRO);
+        }
+
+        let max_nchar = values
+            .values()
+            .filter_map(|value| match value {
+                TrackValue::Text(s) => Some(s.chars().count()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        Some(Self {
+            name,
+            track_type,
+            values,
+            categories,
+            min,
+            max,
+            color: categorical_color(color_idx),
+            max_nchar,
+        })
+    }
+
+    fn width(&self, lab_size: Float) -> Float {
+        match self.track_type {
+            TrackType::Off => ZRO,
+            TrackType::Heatmap | TrackType::Binary => TRACK_CELL_W,
+            TrackType::Bar => TRACK_BAR_W,
+            TrackType::Text => self.max_nchar as Float * lab_size * LAB_CHAR_W,
+        }
+    }
+
+    /// Lower end of the bar scale; bars start at zero unless there are
+    /// negative values.
+    fn bar_min(&self) -> Float {
+        self.min.min(ZRO)
+    }
+
+    fn relative(&self, v: Float, min: Float) -> Float {
+        match self.max > min {
+            true => ((v - min) / (self.max - min)).clamp(ZRO, ONE),
+            false => ONE,
+        }
+    }
+}
+
+/// Total width taken up by the tracks, including the gaps.
+pub(crate) fn tracks_w(tracks: &[Track], lab_size: Float) -> Float {
+    match tracks.is_empty() {
+        true => ZRO,
+        false => {
+            tracks
+                .iter()
+                .map(|track| track.width(lab_size) + TRACK_GAP)
+                .sum::<Float>()
+                + TRACK_GAP
+        }
+    }
+}
+
+/// Space needed above the first tip for the rotated headers of a phylogram.
+pub(crate) fn tracks_header_h(tracks: &[Track], lab_size: Float) -> Float {
+    match tracks.is_empty() {
+        true => ZRO,
+        false => {
+            tracks
+                .iter()
+                .map(|track| track.name.chars().count() as Float)
+                .fold(ZRO, Float::max)
+                * TRACK_TXT_SIZE
+                * LAB_CHAR_W
+                + TRACK_GAP
+                + lab_size
+        }
+    }
+}
+
+// --- Shapes ------------------------------------------------------------------
+
+/// Where the tracks go, in the coordinates of the (translated) tree frame.
+pub(crate) struct TracksGeom {
+    pub(crate) tre_sty: TreSty,
+    /// Tree height for phylograms.
+    pub(crate) h: Float,
+    /// Distance from the aligned tips to the first track: the x coordinate
+    /// for phylograms, the radius for fans.
+    pub(crate) offset: Float,
+    pub(crate) opn_angle: Float,
+    pub(crate) lab_size: Float,
+    /// PDF export scales the whole drawing.
+    pub(crate) scaling: Float,
+}
+
+fn square(center: Point, side: Float, angle: Float) -> IcedPath {
+    let (sin, cos) = angle.sin_cos();
+    let h = side / TWO;
+    let corner = |dx: Float, dy: Float| Point {
+        x: center.x + dx * cos - dy * sin,
+        y: center.y + dx * sin + dy * cos,
+    };
+    PathBuilder::new()
+        .move_to(corner(-h, -h))
+        .line_to(corner(h, -h))
+        .line_to(corner(h, h))
+        .line_to(corner(-h, h))
+        .close()
+        .build()
+}
+
+/// Cells, bars, squares and texts of all tracks together with their headers.
+pub(crate) fn track_shapes(
+    tracks: &[Track],
+    edges: &[Edge],
+    geom: &TracksGeom,
+) -> (Vec<ColoredPath>, Vec<PlacedText>) {
+    let mut shapes: Vec<ColoredPath> = Vec::new();
+    let mut texts: Vec<PlacedText> = Vec::new();
+
+    let tips: Vec<&Edge> = edges.iter().filter(|e| e.is_tip).collect();
+    let Some(first_tip) = tips.iter().min_by(|a, b| a.y.total_cmp(&b.y)) else {
+        return (shapes, texts);
+    };
+
+    let sc = geom.scaling;
+    let lab_size = geom.lab_size;
+    let txt_size = TRACK_TXT_SIZE * sc;
+    let n = tips.len().max(2) as Float;
+    // Rows are capped at twice the label size so that the headers clear the
+    // first row; see `tracks_header_h`.
+    let row = match geom.tre_sty {
+        TreSty::PhyGrm => (geom.h / (n - ONE)).min(lab_size * TWO),
+        TreSty::Fan => geom.opn_angle / n,
+    };
+
+    let mut x = geom.offset + TRACK_GAP * sc;
+    for track in tracks {
+        let w = track.width(lab_size / sc) * sc;
+        for tip in &tips {
+            let Some(value) =
+                tip.label.as_deref().and_then(|lab| track.values.get(lab))
+            else {
+                continue;
+            };
+
+            let mut push = |path: IcedPath, color: Color, filled: bool| {
+                shapes.push(ColoredPath { path, color, filled })
+            };
+
+            match geom.tre_sty {
+                TreSty::PhyGrm => {
+                    let y = tip.y as Float * geom.h;
+                    let rect = |x0: Float, w: Float, h: Float| {
+                        PathBuilder::new()
+                            .rectangle(Rectangle {
+                                x: x0,
+                                y: y - h / TWO,
+                                width: w,
+                                height: h,
+                            })
+                            .build()
+                    };
+                    match value {
+                        TrackValue::Num(v)
+                            if track.track_type == TrackType::Heatmap =>
+                        {
+                            let t = track.relative(*v, track.min);
+                            push(rect(x, w, row), sequential_color(t), true);
+                        }
+                        TrackValue::Num(v) => {
+                            let t = track.relative(*v, track.bar_min());
+                            push(rect(x, w * t, row * 0.7), track.color, true);
+                        }
+                        TrackValue::Cat(i) => {
+                            let color = categorical_color(*i);
+                            push(rect(x, w, row), color, true);
+                        }
+                        TrackValue::Bool(present) => {
+                            let side = (row * 0.8).min(w * 0.8);
+                            let center = Point { x: x + w / TWO, y };
+                            push(
+                                square(center, side, ZRO),
+                                track.color,
+                                *present,
+                            );
+                        }
+                        TrackValue::Text(s) => texts.push(PlacedText::new(
+                            s.clone(),
+                            Point { x, y },
+                            ZRO,
+                            lab_size,
+                        )),
+                    }
+                }
+                TreSty::Fan => {
+                    let a = edge_angle(geom.opn_angle, tip);
+                    let ring = |r0: Float, da: Float, w: Float| {
+                        PathBuilder::new()
+                            .thick_arc(
+                                a - da / TWO,
+                                a + da / TWO,
+                                ORIGIN,
+                                r0,
+                                w,
+                            )
+                            .build()
+                    };
+                    match value {
+                        TrackValue::Num(v)
+                            if track.track_type == TrackType::Heatmap =>
+                        {
+                            let t = track.relative(*v, track.min);
+                            push(ring(x, row, w), sequential_color(t), true);
+                        }
+                        TrackValue::Num(v) => {
+                            let t = track.relative(*v, track.bar_min());
+                            push(ring(x, row * 0.7, w * t), track.color, true);
+                        }
+                        TrackValue::Cat(i) => {
+                            let color = categorical_color(*i);
+                            push(ring(x, row, w), color, true);
+                        }
+                        TrackValue::Bool(present) => {
+                            let r = x + w / TWO;
+                            let side = (row * r * 0.8).min(w * 0.8);
+                            let center = point_pol(a, r, ZRO, ONE);
+                            push(
+                                square(center, side, a),
+                                track.color,
+                                *present,
+                            );
+                        }
+                        TrackValue::Text(s) => texts.push(PlacedText::new(
+                            s.clone(),
+                            point_pol(a, x, ZRO, ONE),
+                            a,
+                            lab_size,
+                        )),
+                    }
+                }
+            }
+        }
+
+        // Headers: rotated above the column of a phylogram, along the
+        // tangent just before the first tip of a fan.
+        texts.push(match geom.tre_sty {
+            TreSty::PhyGrm => {
+                let y = first_tip.y as Float * geom.h - row / TWO;
+                PlacedText::new(
+                    track.name.clone(),
+                    Point { x: x + w / TWO, y: y - TRACK_GAP * sc },
+                    -FRAC_PI_2,
+                    txt_size,
+                )
+            }
+            TreSty::Fan => {
+                let a = edge_angle(geom.opn_angle, first_tip) - row;
+                PlacedText::new(
+                    track.name.clone(),
+                    point_pol(a, x + w / TWO, ZRO, ONE),
+                    a - FRAC_PI_2,
+                    txt_size,
+                )
+            }
+        });
+
+        x += w + TRACK_GAP * sc;
+    }
+
+    (shapes, texts)
+}
+
+/// Adds the color scale of each track to the legend.
+pub(crate) fn track_legend(tracks: &[Track], legend: &mut Legend) {
+    for track in tracks {
+        legend.title(&track.name);
+        match track.track_type {
+            TrackType::Heatmap if track.categories.is_empty() => {
+                legend.color_scale(track.min, track.max);
+            }
+            TrackType::Heatmap => {
+                for (i, cat) in track
+                    .categories
+                    .iter()
+                    .enumerate()
+                    .take(TRACK_LEGEND_CAT_MAX)
+                {
+                    legend.swatch(categorical_color(i), true, cat.clone());
+                }
+                if track.categories.len() > TRACK_LEGEND_CAT_MAX {
+                    legend.note(format!(
+                        "... {} more",
+                        track.categories.len() - TRACK_LEGEND_CAT_MAX
+                    ));
+                }
+            }
+            TrackType::Bar => {
+                legend.bar(track.color, track.bar_min(), track.max);
+            }
+            TrackType::Binary => {
+                legend.swatch(track.color, true, "Present".into());
+                legend.swatch(track.color, false, "Absent".into());
+            }
+            TrackType::Text | TrackType::Off => {}
+        }
+        legend.end_section();
+    }
+}
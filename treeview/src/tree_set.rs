@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use crate::newick::{NwkNode, nwk_attribute, nwk_nodes_from_tree, nwk_string};
+use crate::*;
+
+/// One bit per taxon of a [`TreeSet`].
+type Bits = Vec<u64>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsensusType {
+    MajorityRule,
+    Strict,
+}
+
+impl Display for ConsensusType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            ConsensusType::MajorityRule => "Majority-Rule",
+            ConsensusType::Strict => "Strict",
+        })
+    }
+}
+
+/// Robinson–Foulds distances from one tree to every tree of a set. `None`
+/// marks trees whose tips differ from the rest of the set.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TreeSetDist {
+    pub(crate) tre_id: usize,
+    pub(crate) rf: Option<usize>,
+    pub(crate) rf_norm: Option<Float>,
+    pub(crate) wrf: Option<TreeFloat>,
+}
+
+/// Non-trivial splits of one tree with the length of the branch inducing
+/// each split. In rooted sets a split is the cluster of tips below a node;
+/// otherwise it is the side of a bipartition without the first taxon.
+#[derive(Debug, Default)]
+struct TreeSplits {
+    splits: HashMap<Bits, TreeFloat>,
+    tip_brlens: Vec<TreeFloat>,
+}
+
+/// Split summary of all loaded trees, used for tree-to-tree distances and
+/// consensus trees. The taxa are the tip labels of the first tree; trees with
+/// a different set of tip labels are kept but cannot be compared.
+#[derive(Debug, Default)]
+pub(crate) struct TreeSet {
+    taxa: Vec<String>,
+    rooted: bool,
+    has_brlen: bool,
+    tre_ids: Vec<usize>,
+    /// Trees that were not derived from the set itself (e.g. consensus trees)
+    /// and are therefore summarized by a consensus.
+    is_input: Vec<bool>,
+    splits: Vec<Option<TreeSplits>>,
+}
+
+impl TreeSet {
+    pub(crate) fn new(
+        tre_states: &[Rc<TreeState>],
+        derived_tre_ids: &HashSet<usize>,
+    ) -> Self {
+        let mut taxa: Vec<String> = Vec::new();
+        if let Some(ts) = tre_states.first()
+            && let Some(edges) = ts.tree().edges()
+        {
+            taxa = edges
+                .iter()
+                .filter(|edge| edge.is_tip)
+                .filter_map(|edge| edge.label.as_deref().map(String::from))
+                .collect();
+            taxa.sort();
+            taxa.dedup();
+        }
+
+        let rooted = !tre_states.is_empty()
+            && tre_states.iter().all(|ts| ts.is_rooted_tree());
+        let has_brlen = !tre_states.is_empty()
+            && tre_states.iter().all(|ts| ts.has_brlen());
+
+        let mut tree_set = Self {
+            taxa,
+            rooted,
+            has_brlen,
+            tre_ids: tre_states.iter().map(|ts| ts.id()).collect(),
+            is_input: tre_states
+                .iter()
+                .map(|ts| !derived_tre_ids.contains(&ts.id()))
+                .collect(),
+            splits: Vec::new(),
+        };
+
+        let taxon_idxs = tree_set.taxon_idxs();
+        let splits = tre_states
+            .iter()
+            .map(|ts| tree_splits(ts.tree(), &taxon_idxs, rooted))
+            .collect();
+        tree_set.splits = splits;
+        tree_set
+    }
+
+    pub(crate) fn taxon_count(&self) -> usize {
+        self.taxa.len()
+    }
+
+    pub(crate) fn is_rooted(&self) -> bool {
+        self.rooted
+    }
+
+    pub(crate) fn has_brlen(&self) -> bool {
+        self.has_brlen
+    }
+
+    /// Number of comparable input trees a consensus would summarize.
+    pub(crate) fn input_count(&self) -> usize {
+        self.input_splits().count()
+    }
+
+    fn input_splits(&self) -> impl Iterator<Item = &TreeSplits> {
+        self.splits.iter().zip(&self.is_input).filter_map(
+            |(splits, &is_input)| splits.as_ref().filter(|_| is_input),
+        )
+    }
+
+    /// RF distance is normalized by the total number of splits in both trees;
+    /// weighted RF sums the branch length differences over all splits.
+    pub(crate) fn distances_to(&self, tre_id: usize) -> Vec<TreeSetDist> {
+        let reference = self
+            .tre_ids
+            .iter()
+            .position(|&id| id == tre_id)
+            .and_then(|idx| self.splits[idx].as_ref());
+
+        self.tre_ids
+            .iter()
+            .zip(&self.splits)
+            .map(|(&tre_id, splits)| match (reference, splits) {
+                (Some(a), Some(b)) => {
+                    let rf = rf(a, b);
+                    let max = a.splits.len() + b.splits.len();
+                    TreeSetDist {
+                        tre_id,
+                        rf: Some(rf),
+                        rf_norm: Some(match max {
+                            0 => ZRO,
+                            max => rf as Float / max as Float,
+                        }),
+                        wrf: self.has_brlen.then(|| wrf(a, b)),
+                    }
+                }
+                _ => TreeSetDist { tre_id, ..Default::default() },
+            })
+            .collect()
+    }
+
+    /// Writes `tree` as a NEWICK string with the frequency of each of its
+    /// splits among the comparable input trees attached to the internal nodes,
+    /// both as labels and as `support` node attributes. Existing internal node
+    /// labels are kept as `label` attributes.
+    pub(crate) fn annotated_newick(&self, tree: &Tree) -> Option<String> {
+        let trees: Vec<&TreeSplits> = self.input_splits().collect();
+        let n_tre = trees.len();
+        if n_tre == 0 {
+            return None;
+        }
+
+        let taxon_idxs = self.taxon_idxs();
+        let edges = tree.edges()?;
+        let (edge_splits, _) = edge_splits(edges, &taxon_idxs, self.rooted)?;
+        let support_for_id: HashMap<NodeId, Float> = edges
+            .iter()
+            .zip(edge_splits)
+            .filter_map(|(edge, split)| {
+                let split = split?;
+                let count = trees
+                    .iter()
+                    .filter(|tre| tre.splits.contains_key(&split))
+                    .count();
+                Some((edge.node_id, count as Float / n_tre as Float))
+            })
+            .collect();
+
+        let (mut nodes, node_ids, root) = nwk_nodes_from_tree(tree)?;
+        for (nd, node_id) in nodes.iter_mut().zip(node_ids) {
+            let Some(&support) = support_for_id.get(&node_id) else {
+                continue;
+            };
+            if let Some(label) = nd.label.take()
+                && !nd.node_attrs.iter().any(|(k, _)| k == "label")
+            {
+                nd.node_attrs.push((
+                    String::from("label"),
+                    nwk_attribute(&Attribute::Value(AttributeValue::Text(
+                        label,
+                    ))),
+                ));
+            }
+            nd.node_attrs.retain(|(k, _)| k != "support");
+            nd.node_attrs.push(support_attr(support));
+            nd.label = Some(support_label(support));
+        }
+
+        Some(nwk_string(&nodes, root))
+    }
+
+    fn taxon_idxs(&self) -> HashMap<&str, usize> {
+        self.taxa.iter().enumerate().map(|(i, t)| (t.as_str(), i)).collect()
+    }
+
+    /// Builds the consensus of all comparable input trees as a NEWICK string.
+    /// Clade support frequencies are written both as internal node labels and
+    /// as `support` node attributes. Branch lengths are the mean lengths of
+    /// the corresponding branches in the trees that contain the clade.
+    pub(crate) fn consensus_newick(
+        &self,
+        consensus_type: ConsensusType,
+    ) -> Option<String> {
+        let trees: Vec<&TreeSplits> = self.input_splits().collect();
+        let n_tre = trees.len();
+        let n_tax = self.taxa.len();
+        if n_tre == 0 || n_tax < 2 {
+            return None;
+        }
+
+        let mut counts: HashMap<&Bits, (usize, TreeFloat)> = HashMap::new();
+        for tre in &trees {
+            for (bits, &brlen) in &tre.splits {
+                let (count, brlen_sum) = counts.entry(bits).or_default();
+                *count += 1;
+                *brlen_sum += brlen;
+            }
+        }
+
+        let mut clades: Vec<(&Bits, usize, TreeFloat)> = counts
+            .into_iter()
+            .filter(|&(_, (count, _))| match consensus_type {
+                ConsensusType::MajorityRule => count * 2 > n_tre,
+                ConsensusType::Strict => count == n_tre,
+            })
+            .map(|(bits, (count, brlen_sum))| {
+                (bits, count, brlen_sum / count as TreeFloat)
+            })
+            .collect();
+
+        // Larger clades first, so that each clade is inserted below every
+        // clade that contains it.
+        clades.sort_by(|a, b| {
+            popcount(b.0).cmp(&popcount(a.0)).then_with(|| a.0.cmp(b.0))
+        });
+
+        let mut nodes: Vec<NwkNode> = vec![NwkNode::default()];
+        let mut node_bits: Vec<Bits> = vec![all_bits(n_tax)];
+
+        for (bits, count, brlen) in clades {
+            let support = count as Float / n_tre as Float;
+            let parent = deepest_containing(&nodes, &node_bits, bits);
+            nodes[parent].children.push(nodes.len());
+            nodes.push(NwkNode {
+                label: Some(support_label(support)),
+                brlen: self.has_brlen.then_some(brlen),
+                node_attrs: vec![support_attr(support)],
+                ..Default::default()
+            });
+            node_bits.push(bits.clone());
+        }
+
+        for (t, taxon) in self.taxa.iter().enumerate() {
+            let mut bits = empty_bits(n_tax);
+            set_bit(&mut bits, t);
+            let brlen =
+                trees.iter().map(|tre| tre.tip_brlens[t]).sum::<TreeFloat>()
+                    / n_tre as TreeFloat;
+            let parent = deepest_containing(&nodes, &node_bits, &bits);
+            nodes[parent].children.push(nodes.len());
+            nodes.push(NwkNode {
+                label: Some(taxon.clone()),
+                brlen: self.has_brlen.then_some(brlen),
+                ..Default::default()
+            });
+            node_bits.push(bits);
+        }
+
+        Some(nwk_string(&nodes, 0))
+    }
+}
+
+fn tree_splits(
+    tree: &Tree,
+    taxon_idxs: &HashMap<&str, usize>,
+    rooted: bool,
+) -> Option<TreeSplits> {
+    let edges = tree.edges()?;
+    let (edge_splits, tip_brlens) = edge_splits(edges, taxon_idxs, rooted)?;
+
+    let mut splits: HashMap<Bits, TreeFloat> = HashMap::new();
+    for (edge, split) in edges.iter().zip(edge_splits) {
+        if let Some(split) = split {
+            // Both branches at the root of a rooted tree induce the same
+            // unrooted split; their lengths add up.
+            *splits.entry(split).or_default() += edge.branch_length;
+        }
+    }
+
+    Some(TreeSplits { splits, tip_brlens })
+}
+
+/// Non-trivial split induced by each edge (`None` for tip, root and trivial
+/// edges) and the tip branch lengths by taxon. Returns `None` if the tips of
+/// the tree are not exactly the given taxa.
+fn edge_splits(
+    edges: &[Edge],
+    taxon_idxs: &HashMap<&str, usize>,
+    rooted: bool,
+) -> Option<(Vec<Option<Bits>>, Vec<TreeFloat>)> {
+    let n_tax = taxon_idxs.len();
+
+    let idx_for_id: HashMap<NodeId, usize> =
+        edges.iter().enumerate().map(|(i, edge)| (edge.node_id, i)).collect();
+    let mut children: HashMap<NodeId, Vec<usize>> = HashMap::new();
+    let mut stack: Vec<usize> = Vec::new();
+    for (i, edge) in edges.iter().enumerate() {
+        if edge.parent_node_id == edge.node_id
+            || !idx_for_id.contains_key(&edge.parent_node_id)
+        {
+            stack.push(i);
+        }
+        if edge.parent_node_id != edge.node_id {
+            children.entry(edge.parent_node_id).or_default().push(i);
+        }
+    }
+
+    let mut preorder: Vec<usize> = Vec::with_capacity(edges.len());
+    while let Some(i) = stack.pop() {
+        preorder.push(i);
+        if let Some(c) = children.get(&edges[i].node_id) {
+            stack.extend(c);
+        }
+    }
+
+    let mut bits: Vec<Bits> = vec![Vec::new(); edges.len()];
+    let mut tip_brlens: Vec<TreeFloat> = vec![0e0; n_tax];
+    let mut seen = empty_bits(n_tax);
+    for &i in preorder.iter().rev() {
+        let edge = &edges[i];
+        let mut b = empty_bits(n_tax);
+        if edge.is_tip {
+            let t = *taxon_idxs.get(edge.label.as_deref()?)?;
+            if bit_is_set(&seen, t) {
+                return None;
+            }
+            set_bit(&mut seen, t);
+            set_bit(&mut b, t);
+            tip_brlens[t] = edge.branch_length;
+        } else if let Some(c) = children.get(&edge.node_id) {
+            for &c in c {
+                union(&mut b, &bits[c]);
+            }
+        }
+        bits[i] = b;
+    }
+
+    if popcount(&seen) != n_tax {
+        return None;
+    }
+
+    let splits = edges
+        .iter()
+        .zip(bits)
+        .map(|(edge, mut b)| {
+            if edge.is_tip || edge.parent_node_id == edge.node_id {
+                return None;
+            }
+            if !rooted && bit_is_set(&b, 0) {
+                complement(&mut b, n_tax);
+            }
+            let size = popcount(&b);
+            let is_trivial = match rooted {
+                true => size < 2 || size >= n_tax,
+                false => size < 2 || size + 2 > n_tax,
+            };
+            (!is_trivial).then_some(b)
+        })
+        .collect();
+
+    Some((splits, tip_brlens))
+}
+
+fn support_label(support: Float) -> String {
+    format!("{support:.2}")
+}
+
+fn support_attr(support: Float) -> (String, String) {
+    (String::from("support"), format!("{support:.4}"))
+}
+
+fn rf(a: &TreeSplits, b: &TreeSplits) -> usize {
+    a.splits.keys().filter(|k| !b.splits.contains_key(*k)).count()
+        + b.splits.keys().filter(|k| !a.splits.contains_key(*k)).count()
+}
+
+fn wrf(a: &TreeSplits, b: &TreeSplits) -> TreeFloat {
+    let mut d: TreeFloat = 0e0;
+    for (k, &len_a) in &a.splits {
+        d += (len_a - b.splits.get(k).copied().unwrap_or_default()).abs();
+    }
+    for (k, &len_b) in &b.splits {
+        if !a.splits.contains_key(k) {
+            d += len_b.abs();
+        }
+    }
+    d
+}
+
+fn deepest_containing(
+    nodes: &[NwkNode],
+    node_bits: &[Bits],
+    bits: &[u64],
+) -> usize {
+    let mut idx = 0;
+    while let Some(&c) =
+        nodes[idx].children.iter().find(|&&c| is_subset(bits, &node_bits[c]))
+    {
+        idx = c;
+    }
+    idx
+}
+
+// --- Bit Sets ----------------------------------------------------------------
+
+fn empty_bits(n: usize) -> Bits {
+    vec![0; n.div_ceil(64)]
+}
+
+fn all_bits(n: usize) -> Bits {
+    let mut bits = empty_bits(n);
+    complement(&mut bits, n);
+    bits
+}
+
+fn set_bit(bits: &mut [u64], i: usize) {
+    bits[i / 64] |= 1u64 << (i % 64);
+}
+
+fn bit_is_set(bits: &[u64], i: usize) -> bool {
+    bits[i / 64] & (1u64 << (i % 64)) != 0
+}
+
+fn union(bits: &mut [u64], other: &[u64]) {
+    bits.iter_mut().zip(other).for_each(|(a, b)| *a |= b);
+}
+
+fn complement(bits: &mut [u64], n: usize) {
+    bits.iter_mut().for_each(|a| *a = !*a);
+    if n % 64 != 0
+        && let Some(last) = bits.last_mut()
+    {
+        *last &= (1u64 << (n % 64)) - 1;
+    }
+}
+
+fn is_subset(bits: &[u64], of: &[u64]) -> bool {
+    bits.iter().zip(of).all(|(a, b)| a & !b == 0)
+}
+
+fn popcount(bits: &[u64]) -> usize {
+    bits.iter().map(|a| a.count_ones() as usize).sum()
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::*;
+
+/// Tree-shape and diversity statistics of a tree or of the subtree in view.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TreeStats {
+    pub(crate) tip_count: usize,
+    pub(crate) int_node_count: usize,
+    /// Sum of the differences in tip counts of the two clades below each
+    /// internal node; `None` if the tree has polytomies.
+    pub(crate) colless: Option<usize>,
+    /// Sum of the number of internal nodes between each tip and the root.
+    pub(crate) sackin: usize,
+    /// Pybus–Harvey gamma; needs an ultrametric tree with branch lengths.
+    pub(crate) gamma: Option<Float>,
+    /// Internal branch lengths as a fraction of the tree length.
+    pub(crate) treeness: Option<Float>,
+    pub(crate) tree_len: Option<Float>,
+    pub(crate) sel_tip_count: usize,
+    /// Length of the branches connecting the selected tips to the root.
+    pub(crate) sel_pd: Option<Float>,
+    pub(crate) mean_patristic: Option<Float>,
+    /// Number of polytomies of each size (number of children).
+    pub(crate) polytomies: Vec<(usize, usize)>,
+}
+
+/// Nodes of the tree with parents placed before their children.
+struct Topology {
+    children: Vec<Vec<usize>>,
+    brlen: Vec<Float>,
+    is_tip: Vec<bool>,
+    node_ids: Vec<Option<NodeId>>,
+    /// The root is an added node joining the base of an unrooted tree.
+    unrooted_base: bool,
+    order: Vec<usize>,
+}
+
+impl Topology {
+    /// Builds the topology below the first node of `edges`. The branch
+    /// leading to the first node (the root or the root of a subtree view) is
+    /// left out.
+    fn new(edges: &[Edge]) -> Option<Self> {
+        let idx_for_id: HashMap<NodeId, usize> = edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge)| (edge.node_id, i))
+            .collect();
+
+        let n = edges.len();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut base: Vec<usize> = Vec::new();
+        for (i, edge) in edges.iter().enumerate() {
+            match idx_for_id.get(&edge.parent_node_id) {
+                Some(&p) if edge.parent_node_id != edge.node_id => {
+                    children[p].push(i)
+                }
+                _ => base.push(i),
+            }
+        }
+
+        let mut brlen: Vec<Float> =
+            edges.iter().map(|edge| edge.branch_length).collect();
+        let mut is_tip: Vec<bool> = edges.iter().map(|e| e.is_tip).collect();
+        let mut node_ids: Vec<Option<NodeId>> =
+            edges.iter().map(|edge| Some(edge.node_id)).collect();
+
+        let unrooted_base = base.len() > 1;
+        let root = match base.len() {
+            0 => return None,
+            1 => base[0],
+            _ => {
+                children.push(base);
+                brlen.push(ZRO);
+                is_tip.push(false);
+                node_ids.push(None);
+                n
+            }
+        };
+        brlen[root] = ZRO;
+
+        let mut order: Vec<usize> = Vec::with_capacity(children.len());
+        let mut stack: Vec<usize> = vec![root];
+        while let Some(i) = stack.pop() {
+            order.push(i);
+            stack.extend(children[i].iter().rev());
+        }
+
+        Some(Self { children, brlen, is_tip, node_ids, unrooted_base, order })
+    }
+
+    fn root(&self) -> usize {
+        self.order[0]
+    }
+
+    /// Number of tips below each node.
+    fn tip_counts(&self) -> Vec<usize> {
+        let mut counts: Vec<usize> = vec![0; self.children.len()];
+        for &i in self.order.iter().rev() {
+            counts[i] = match self.is_tip[i] {
+                true => 1,
+                false => self.children[i].iter().map(|&c| counts[c]).sum(),
+            };
+        }
+        counts
+    }
+
+    /// Distance from the root to each node, in branches and in length.
+    fn depths(&self) -> (Vec<usize>, Vec<Float>) {
+        let n = self.children.len();
+        let mut steps: Vec<usize> = vec![0; n];
+        let mut heights: Vec<Float> = vec![ZRO; n];
+        for &i in &self.order {
+            for &c in &self.children[i] {
+                steps[c] = steps[i] + 1;
+                heights[c] = heights[i] + self.brlen[c];
+            }
+        }
+        (steps, heights)
+    }
+}
+
+impl TreeStats {
+    pub(crate) fn new(
+        edges: &[Edge],
+        has_brlen: bool,
+        sel_node_ids: &HashSet<NodeId>,
+    ) -> Option<Self> {
+        let topo = Topology::new(edges)?;
+        let root = topo.root();
+        let tip_counts = topo.tip_counts();
+        let (steps, heights) = topo.depths();
+        let nodes = || topo.order.iter().copied();
+        let int_nodes = || nodes().filter(|&i| !topo.is_tip[i]);
+        let tips = || nodes().filter(|&i| topo.is_tip[i]);
+
+        let mut stats = Self {
+            tip_count: tip_counts[root],
+            int_node_count: int_nodes().count(),
+            sackin: tips().map(|i| steps[i]).sum(),
+            ..Default::default()
+        };
+
+        let mut polytomies: HashMap<usize, usize> = HashMap::new();
+        for i in int_nodes() {
+            let k = topo.children[i].len();
+            let base_trifurcation = i == root && topo.unrooted_base && k == 3;
+            if k > 2 && !base_trifurcation {
+                *polytomies.entry(k).or_default() += 1;
+            }
+        }
+        stats.polytomies = polytomies.into_iter().collect();
+        stats.polytomies.sort();
+
+        if stats.polytomies.is_empty() {
+            stats.colless = Some(
+                int_nodes()
+                    .filter_map(|i| match topo.children[i][..] {
+                        [a, b] => Some(tip_counts[a].abs_diff(tip_counts[b])),
+                        _ => None,
+                    })
+                    .sum(),
+            );
+        }
+
+        if !has_brlen {
+            return Some(stats);
+        }
+
+        let n = stats.tip_count;
+        let tree_len: Float = nodes().map(|i| topo.brlen[i]).sum();
+        let int_len: Float = int_nodes().map(|i| topo.brlen[i]).sum();
+        stats.tree_len = Some(tree_len);
+        if tree_len > ZRO {
+            stats.treeness = Some(int_len / tree_len);
+        }
+
+        if n > 1 {
+            let pairs = (n * (n - 1) / 2) as Float;
+            let sum: Float = nodes()
+                .map(|i| {
+                    let below = tip_counts[i] as Float;
+                    topo.brlen[i] * below * (n as Float - below)
+                })
+                .sum();
+            stats.mean_patristic = Some(sum / pairs);
+        }
+
+        let mut on_path: Vec<bool> = vec![false; topo.children.len()];
+        for &i in topo.order.iter().rev() {
+            on_path[i] = match topo.is_tip[i] {
+                true => topo.node_ids[i]
+                    .is_some_and(|id| sel_node_ids.contains(&id)),
+                false => topo.children[i].iter().any(|&c| on_path[c]),
+            };
+        }
+        stats.sel_tip_count = tips().filter(|&i| on_path[i]).count();
+        if stats.sel_tip_count > 0 {
+            stats.sel_pd = Some(
+                nodes().filter(|&i| on_path[i]).map(|i| topo.brlen[i]).sum(),
+            );
+        }
+
+        stats.gamma = gamma(
+            int_nodes().flat_map(|i| {
+                std::iter::repeat_n(heights[i], topo.children[i].len() - 1)
+            }),
+            tips().map(|i| heights[i]),
+        );
+
+        Some(stats)
+    }
+
+    /// Label and formatted value of each statistic.
+    pub(crate) fn rows(&self) -> Vec<(&'static str, String)> {
+        let num = |v: Option<Float>| match v {
+            Some(v) => format!("{v:.4}"),
+            None => String::from("-"),
+        };
+        let polytomies = match self.polytomies.is_empty() {
+            true => String::from("0"),
+            false => self
+                .polytomies
+                .iter()
+                .map(|(size, count)| format!("{count} × {size}"))
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+        vec![
+            ("Tips", self.tip_count.to_string()),
+            ("Internal Nodes", self.int_node_count.to_string()),
+            (
+                "Colless Index",
+                match self.colless {
+                    Some(v) => v.to_string(),
+                    None => String::from("-"),
+                },
+            ),
+            ("Sackin Index", self.sackin.to_string()),
+            ("Gamma", num(self.gamma)),
+            ("Treeness", num(self.treeness)),
+            ("Tree Length", num(self.tree_len)),
+            ("Selected Tips", self.sel_tip_count.to_string()),
+            ("Selected Tips PD", num(self.sel_pd)),
+            ("Mean Patristic Distance", num(self.mean_patristic)),
+            ("Polytomies (Count × Size)", polytomies),
+        ]
+    }
+
+    /// Statistics as tab-separated lines with a header.
+    pub(crate) fn tsv(&self) -> String {
+        let mut s = String::from("statistic\tvalue\n");
+        for (label, value) in self.rows() {
+            _ = writeln!(s, "{label}\t{value}");
+        }
+        s
+    }
+}
+
+/// Distances from the first node of `edges` to the start and to the end of the
+/// branch of each edge, or `None` for the branch leading to the first node.
+pub(crate) fn edge_spans(
+    edges: &[Edge],
+) -> Option<Vec<Option<(Float, Float)>>> {
+    let topo = Topology::new(edges)?;
+    let (_, heights) = topo.depths();
+    let root = topo.root();
+    Some(
+        (0..edges.len())
+            .map(|i| match i == root {
+                true => None,
+                false => Some((heights[i] - topo.brlen[i], heights[i])),
+            })
+            .collect(),
+    )
+}
+
+/// Pybus–Harvey gamma from the heights of the branching events (a node with
+/// `k` children branches `k - 1` times) and of the tips. `None` unless the
+/// tips are contemporaneous and there are at least three of them.
+fn gamma(
+    branching: impl Iterator<Item = Float>,
+    tips: impl Iterator<Item = Float>,
+) -> Option<Float> {
+    let tips: Vec<Float> = tips.collect();
+    let (min, max) = tips
+        .iter()
+        .fold((Float::INFINITY, Float::NEG_INFINITY), |(min, max), &h| {
+            (min.min(h), max.max(h))
+        });
+    let n = tips.len();
+    if n < 3 || max <= ZRO || (max - min) / max > 1e-4 {
+        return None;
+    }
+
+    let mut times: Vec<Float> = branching.collect();
+    times.sort_by(Float::total_cmp);
+    times.push(max);
+    if times.len() != n {
+        return None;
+    }
+
+    // Internode interval `g[k]` is spent with `k + 2` lineages.
+    let g: Vec<Float> = times.windows(2).map(|w| w[1] - w[0]).collect();
+    let total: Float =
+        g.iter().enumerate().map(|(k, gk)| (k + 2) as Float * gk).sum();
+    if total <= ZRO {
+        return None;
+    }
+
+    let mut cumulative = ZRO;
+    let mut inner = ZRO;
+    for (k, gk) in g.iter().enumerate().take(n - 2) {
+        cumulative += (k + 2) as Float * gk;
+        inner += cumulative;
+    }
+    let m = (n - 2) as Float;
+    Some((inner / m - total / TWO) / (total * (ONE / (12e0 * m)).sqrt()))
+}
@@ -0,0 +1,126 @@
+mod nexml;
+mod phyloxml;
+
+pub(crate) use nexml::nexml_string;
+pub(crate) use phyloxml::phyloxml_string;
+
+use crate::newick::{
+    NwkNode, attribute_text, nwk_attribute_from_text, nwk_string,
+};
+use crate::*;
+
+type XmlNode<'a, 'input> = roxmltree::Node<'a, 'input>;
+
+/// Parses a PhyloXML or a NeXML document, whichever its root element is.
+/// Annotations are read into node and branch attributes.
+pub fn parse_xml_trees(text: &str) -> std::result::Result<Vec<Tree>, String> {
+    let doc = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+    let root = doc.root_element();
+    match root.tag_name().name() {
+        "phyloxml" => phyloxml::trees(root),
+        "nexml" => nexml::trees(root),
+        name => Err(format!("Not a PhyloXML or NeXML document: <{name}>.")),
+    }
+}
+
+fn children<'a, 'input>(
+    node: XmlNode<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = XmlNode<'a, 'input>> {
+    node.children()
+        .filter(move |c| c.is_element() && c.tag_name().name() == name)
+}
+
+fn child<'a, 'input>(
+    node: XmlNode<'a, 'input>,
+    name: &'static str,
+) -> Option<XmlNode<'a, 'input>> {
+    children(node, name).next()
+}
+
+fn child_text<'a>(
+    node: XmlNode<'a, '_>,
+    name: &'static str,
+) -> Option<&'a str> {
+    child(node, name).and_then(|c| c.text()).map(str::trim)
+}
+
+/// Builds a tree out of nodes read from XML. Attributes of branches without a
+/// length cannot be written to NEWICK, so they are kept on the nodes instead.
+fn tree_from_nwk_nodes(
+    mut nodes: Vec<NwkNode>,
+    root: usize,
+) -> std::result::Result<Tree, String> {
+    for nd in &mut nodes {
+        if nd.brlen.is_none() && !nd.branch_attrs.is_empty() {
+            let branch_attrs = std::mem::take(&mut nd.branch_attrs);
+            nd.node_attrs.extend(branch_attrs);
+        }
+        nd.node_attrs.sort();
+        nd.node_attrs.dedup_by(|a, b| a.0 == b.0);
+        nd.branch_attrs.sort();
+        nd.branch_attrs.dedup_by(|a, b| a.0 == b.0);
+    }
+    parse_trees(nwk_string(&nodes, root))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| String::from("Empty tree."))
+}
+
+/// Attribute name with the characters that cannot be written in NEWICK
+/// replaced.
+fn attribute_key(text: &str) -> String {
+    text.trim()
+        .chars()
+        .map(|c| {
+            match c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '!') {
+                true => c,
+                false => '_',
+            }
+        })
+        .collect()
+}
+
+/// Typed attributes of a node or branch as (name, text) pairs.
+fn attribute_texts<'a>(
+    attrs: impl Iterator<Item = (&'a String, &'a Attribute)>,
+) -> Vec<(String, String)> {
+    let mut attrs: Vec<(String, String)> =
+        attrs.map(|(k, v)| (k.clone(), attribute_text(v))).collect();
+    attrs.sort();
+    attrs
+}
+
+fn is_numeric(text: &str) -> bool {
+    text.trim().parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+/// Reads `#rrggbb` (an alpha component, `#rrggbbaa`, is ignored).
+pub(crate) fn rgb_from_hex(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let c = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([c(0)?, c(2)?, c(4)?])
+}
+
+fn hex_from_rgb(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => s.push_str("&amp;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '"' => s.push_str("&quot;"),
+            '\'' => s.push_str("&apos;"),
+            c => s.push(c),
+        }
+    }
+    s
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::*;
+use crate::newick::{nwk_nodes_from_tree, preorder};
+
+const META_PREFIX: &str = "th";
+const COLOR_META_KEY: &str = "color";
+
+// --- Reading -----------------------------------------------------------------
+
+pub(super) fn trees(root: XmlNode) -> std::result::Result<Vec<Tree>, String> {
+    let otu_labels: HashMap<&str, &str> = root
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "otu")
+        .filter_map(|n| Some((n.attribute("id")?, n.attribute("label")?)))
+        .collect();
+
+    let mut trees: Vec<Tree> = Vec::new();
+    for trees_el in children(root, "trees") {
+        for tree_el in children(trees_el, "tree") {
+            trees.push(tree(tree_el, &otu_labels)?);
+        }
+    }
+    Ok(trees)
+}
+
+fn tree(
+    tree_el: XmlNode,
+    otu_labels: &HashMap<&str, &str>,
+) -> std::result::Result<Tree, String> {
+    let mut nodes: Vec<NwkNode> = Vec::new();
+    let mut idx_for_id: HashMap<&str, usize> = HashMap::new();
+    let mut root: Option<usize> = None;
+
+    for node_el in children(tree_el, "node") {
+        let id = node_el
+            .attribute("id")
+            .ok_or_else(|| String::from("NeXML node without an id."))?;
+        let label = node_el.attribute("label").or_else(|| {
+            node_el
+                .attribute("otu")
+                .and_then(|otu| otu_labels.get(otu).copied())
+        });
+        if node_el.attribute("root") == Some("true") {
+            root = Some(nodes.len());
+        }
+        _ = idx_for_id.insert(id, nodes.len());
+        nodes.push(NwkNode {
+            label: label.map(String::from),
+            node_attrs: meta_attrs(node_el),
+            ..Default::default()
+        });
+    }
+
+    let idx = |el: XmlNode, name: &str| {
+        el.attribute(name)
+            .and_then(|id| idx_for_id.get(id).copied())
+            .ok_or_else(|| format!("NeXML edge with an unknown {name} node."))
+    };
+    let length = |el: XmlNode| {
+        el.attribute("length").and_then(|text| text.trim().parse().ok())
+    };
+
+    let mut has_parent: Vec<bool> = vec![false; nodes.len()];
+    for edge_el in children(tree_el, "edge") {
+        let source = idx(edge_el, "source")?;
+        let target = idx(edge_el, "target")?;
+        nodes[source].children.push(target);
+        nodes[target].brlen = length(edge_el);
+        nodes[target].branch_attrs = meta_attrs(edge_el);
+        has_parent[target] = true;
+    }
+    for edge_el in children(tree_el, "rootedge") {
+        let target = idx(edge_el, "target")?;
+        nodes[target].brlen = length(edge_el);
+        nodes[target].branch_attrs = meta_attrs(edge_el);
+    }
+
+    let root = root
+        .or_else(|| has_parent.iter().position(|&p| !p))
+        .ok_or_else(|| String::from("NeXML tree without a root node."))?;
+    tree_from_nwk_nodes(nodes, root)
+}
+
+/// Literal `meta` annotations of a node or an edge, named after the property
+/// without its namespace prefix.
+fn meta_attrs(el: XmlNode) -> Vec<(String, String)> {
+    children(el, "meta")
+        .filter_map(|meta| {
+            let property = meta.attribute("property")?;
+            let key = property.rsplit(':').next().unwrap_or(property);
+            let content = meta.attribute("content").or_else(|| meta.text())?;
+            Some(match rgb_from_hex(content) {
+                Some(rgb) if key.eq_ignore_ascii_case(COLOR_META_KEY) => {
+                    (BRANCH_COLOR_ATTR.to_string(), hex_from_rgb(rgb))
+                }
+                _ => (attribute_key(key), nwk_attribute_from_text(content)),
+            })
+        })
+        .collect()
+}
+
+// --- Writing -----------------------------------------------------------------
+
+/// NeXML document with the tips of all trees in one set of OTUs.
+pub(crate) fn nexml_string(trees: &[Tree]) -> String {
+    let mut s = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<nex:nexml version=\"0.9\" generator=\"TreeHouse\" ",
+        "xmlns=\"http://www.nexml.org/2009\" ",
+        "xmlns:nex=\"http://www.nexml.org/2009\" ",
+        "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+        "xmlns:xsd=\"http://www.w3.org/2001/XMLSchema#\" ",
+        "xmlns:th=\"https://github.com/karolisr/treehouse#\">\n"
+    ));
+
+    let trees: Vec<(&Tree, Vec<NwkNode>, Vec<NodeId>, usize)> = trees
+        .iter()
+        .filter_map(|tree| {
+            let (nodes, node_ids, root) = nwk_nodes_from_tree(tree)?;
+            Some((tree, nodes, node_ids, root))
+        })
+        .collect();
+
+    let mut otu_for_label: HashMap<&str, usize> = HashMap::new();
+    s.push_str("  <otus id=\"otus1\">\n");
+    for (_, nodes, _, _) in &trees {
+        for nd in nodes.iter().filter(|nd| nd.children.is_empty()) {
+            if let Some(label) = &nd.label
+                && !otu_for_label.contains_key(label.as_str())
+            {
+                let otu = otu_for_label.len() + 1;
+                _ = otu_for_label.insert(label, otu);
+                _ = writeln!(
+                    s,
+                    "    <otu id=\"otu{otu}\" label=\"{}\"/>",
+                    xml_escape(label)
+                );
+            }
+        }
+    }
+    s.push_str("  </otus>\n");
+
+    let mut writer = MetaWriter { s, count: 0 };
+    writer.s.push_str("  <trees id=\"trees1\" otus=\"otus1\">\n");
+    for (t, (tree, nodes, node_ids, root)) in (1..).zip(&trees) {
+        let root = *root;
+        let node_attrs = |i: usize| {
+            attribute_texts(tree.node_attributes(node_ids[i]).iter())
+        };
+        let branch_attrs = |i: usize| {
+            attribute_texts(tree.branch_attributes(node_ids[i]).iter())
+        };
+        let s = &mut writer.s;
+        _ = writeln!(
+            s,
+            "    <tree id=\"tree{t}\" label=\"tree{t}\" xsi:type=\"nex:FloatTree\">"
+        );
+
+        let order = preorder(nodes, root);
+        for &i in &order {
+            let nd = &nodes[i];
+            let s = &mut writer.s;
+            _ = write!(s, "      <node id=\"t{t}n{i}\"");
+            if let Some(label) = &nd.label {
+                _ = write!(s, " label=\"{}\"", xml_escape(label));
+                if nd.children.is_empty()
+                    && let Some(otu) = otu_for_label.get(label.as_str())
+                {
+                    _ = write!(s, " otu=\"otu{otu}\"");
+                }
+            }
+            if i == root {
+                s.push_str(" root=\"true\"");
+            }
+            writer.close_element("node", &node_attrs(i));
+        }
+
+        if let Some(brlen) = nodes[root].brlen {
+            _ = write!(
+                writer.s,
+                "      <rootedge id=\"t{t}re\" target=\"t{t}n{root}\" length=\"{brlen}\""
+            );
+            writer.close_element("rootedge", &branch_attrs(root));
+        }
+
+        for &i in &order {
+            for &c in &nodes[i].children {
+                let s = &mut writer.s;
+                _ = write!(
+                    s,
+                    "      <edge id=\"t{t}e{c}\" source=\"t{t}n{i}\" target=\"t{t}n{c}\""
+                );
+                if let Some(brlen) = nodes[c].brlen {
+                    _ = write!(s, " length=\"{brlen}\"");
+                }
+                writer.close_element("edge", &branch_attrs(c));
+            }
+        }
+        writer.s.push_str("    </tree>\n");
+    }
+    writer.s.push_str("  </trees>\n");
+    writer.s.push_str("</nex:nexml>\n");
+    writer.s
+}
+
+/// Writes node and edge elements, numbering the `meta` annotations in them.
+struct MetaWriter {
+    s: String,
+    count: usize,
+}
+
+impl MetaWriter {
+    /// Ends the start tag of an element whose attributes have been written and
+    /// adds its annotations.
+    fn close_element(&mut self, name: &str, attrs: &[(String, String)]) {
+        if attrs.is_empty() {
+            self.s.push_str("/>\n");
+            return;
+        }
+        self.s.push_str(">\n");
+        for (key, value) in attrs {
+            self.count += 1;
+            let (property, value) = match key.as_str() {
+                BRANCH_COLOR_ATTR => (COLOR_META_KEY, value.as_str()),
+                key => (key, value.as_str()),
+            };
+            let datatype = match is_numeric(value) {
+                true => "xsd:double",
+                false => "xsd:string",
+            };
+            _ = writeln!(
+                self.s,
+                "        <meta id=\"meta{}\" xsi:type=\"nex:LiteralMeta\" property=\"{META_PREFIX}:{}\" datatype=\"{datatype}\" content=\"{}\"/>",
+                self.count,
+                xml_escape(property),
+                xml_escape(value)
+            );
+        }
+        _ = writeln!(self.s, "      </{name}>");
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt::Write;
+
+use super::*;
+use crate::newick::nwk_nodes_from_tree;
+
+/// PhyloXML taxonomy elements and the node attributes they are read into.
+const TAXONOMY_FIELDS: [(&str, &str); 5] = [
+    ("id", "taxonomy_id"),
+    ("code", "taxonomy_code"),
+    ("scientific_name", "scientific_name"),
+    ("common_name", "common_name"),
+    ("rank", "rank"),
+];
+
+/// Node attributes written as PhyloXML confidence elements. A confidence of
+/// any type is read into a node attribute named after the type.
+const CONFIDENCE_TYPES: [&str; 5] =
+    ["confidence", "bootstrap", "posterior", "probability", "support"];
+
+const WIDTH_ATTR: &str = "width";
+const DATE_ATTR: &str = "date";
+const PROPERTY_REF_PREFIX: &str = "treehouse";
+
+// --- Reading -----------------------------------------------------------------
+
+pub(super) fn trees(root: XmlNode) -> std::result::Result<Vec<Tree>, String> {
+    let mut trees: Vec<Tree> = Vec::new();
+    for phylogeny in children(root, "phylogeny") {
+        let clade = child(phylogeny, "clade").ok_or_else(|| {
+            String::from("PhyloXML phylogeny without a clade.")
+        })?;
+        let mut nodes: Vec<NwkNode> = Vec::new();
+        let root = add_clade(clade, &mut nodes);
+        trees.push(tree_from_nwk_nodes(nodes, root)?);
+    }
+    Ok(trees)
+}
+
+fn add_clade(clade: XmlNode, nodes: &mut Vec<NwkNode>) -> usize {
+    let idx = nodes.len();
+    nodes.push(NwkNode::default());
+
+    let mut nd = NwkNode {
+        label: child_text(clade, "name").map(String::from),
+        brlen: clade
+            .attribute("branch_length")
+            .or_else(|| child_text(clade, "branch_length"))
+            .and_then(|text| text.trim().parse().ok()),
+        ..Default::default()
+    };
+
+    for el in clade.children().filter(XmlNode::is_element) {
+        match el.tag_name().name() {
+            "confidence" => {
+                if let Some(value) = el.text().filter(|text| is_numeric(text)) {
+                    let key =
+                        el.attribute("type").unwrap_or(CONFIDENCE_TYPES[0]);
+                    nd.node_attrs
+                        .push((attribute_key(key), value.trim().to_string()));
+                }
+            }
+            "width" => {
+                if let Some(value) = el.text().filter(|text| is_numeric(text)) {
+                    nd.branch_attrs.push((
+                        WIDTH_ATTR.to_string(),
+                        value.trim().to_string(),
+                    ));
+                }
+            }
+            "color" => {
+                let rgb = ["red", "green", "blue"].map(|c| {
+                    child_text(el, c).and_then(|v| v.parse::<u8>().ok())
+                });
+                if let [Some(r), Some(g), Some(b)] = rgb {
+                    nd.branch_attrs.push((
+                        BRANCH_COLOR_ATTR.to_string(),
+                        hex_from_rgb([r, g, b]),
+                    ));
+                }
+            }
+            "taxonomy" => {
+                for (tag, key) in TAXONOMY_FIELDS {
+                    if let Some(value) = child_text(el, tag) {
+                        nd.node_attrs.push((
+                            key.to_string(),
+                            nwk_attribute_from_text(value),
+                        ));
+                    }
+                }
+                if nd.label.is_none() {
+                    nd.label = child_text(el, "scientific_name")
+                        .or_else(|| child_text(el, "code"))
+                        .map(String::from);
+                }
+            }
+            "date" => {
+                if let Some(value) = child_text(el, "value") {
+                    nd.node_attrs.push((
+                        DATE_ATTR.to_string(),
+                        nwk_attribute_from_text(value),
+                    ));
+                }
+            }
+            "property" => {
+                if let Some(key) = el.attribute("ref")
+                    && let Some(value) = el.text()
+                {
+                    let key = key.rsplit(':').next().unwrap_or(key);
+                    let attr =
+                        (attribute_key(key), nwk_attribute_from_text(value));
+                    match el.attribute("applies_to") {
+                        Some("parent_branch" | "branch") => {
+                            nd.branch_attrs.push(attr);
+                        }
+                        _ => nd.node_attrs.push(attr),
+                    }
+                }
+            }
+            "clade" => {
+                let c = add_clade(el, nodes);
+                nd.children.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    nodes[idx] = nd;
+    idx
+}
+
+// --- Writing -----------------------------------------------------------------
+
+/// PhyloXML document with one phylogeny per tree.
+pub(crate) fn phyloxml_string(trees: &[Tree]) -> String {
+    let mut s = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<phyloxml xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+        "xmlns=\"http://www.phyloxml.org\" ",
+        "xsi:schemaLocation=\"http://www.phyloxml.org ",
+        "http://www.phyloxml.org/1.20/phyloxml.xsd\">\n"
+    ));
+    for tree in trees {
+        if let Some((nodes, node_ids, root)) = nwk_nodes_from_tree(tree) {
+            _ = writeln!(s, "  <phylogeny rooted=\"{}\">", tree.is_rooted());
+            write_clade(tree, &nodes, &node_ids, root, 2, &mut s);
+            s.push_str("  </phylogeny>\n");
+        }
+    }
+    s.push_str("</phyloxml>\n");
+    s
+}
+
+fn write_clade(
+    tree: &Tree,
+    nodes: &[NwkNode],
+    node_ids: &[NodeId],
+    idx: usize,
+    depth: usize,
+    s: &mut String,
+) {
+    let nd = &nodes[idx];
+    let ind = "  ".repeat(depth);
+    let node_attrs =
+        attribute_texts(tree.node_attributes(node_ids[idx]).iter());
+    let branch_attrs =
+        attribute_texts(tree.branch_attributes(node_ids[idx]).iter());
+    let node_attr = |key: &str| {
+        node_attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    };
+    let attr = |key: &str| {
+        branch_attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .or_else(|| node_attr(key))
+    };
+
+    _ = writeln!(s, "{ind}<clade>");
+    if let Some(label) = &nd.label {
+        _ = writeln!(s, "{ind}  <name>{}</name>", xml_escape(label));
+    }
+    if let Some(brlen) = nd.brlen {
+        _ = writeln!(s, "{ind}  <branch_length>{brlen}</branch_length>");
+    }
+    for key in CONFIDENCE_TYPES {
+        if let Some(value) = node_attr(key).filter(|v| is_numeric(v)) {
+            _ = writeln!(
+                s,
+                "{ind}  <confidence type=\"{key}\">{value}</confidence>"
+            );
+        }
+    }
+    if let Some(value) = attr(WIDTH_ATTR).filter(|v| is_numeric(v)) {
+        _ = writeln!(s, "{ind}  <width>{value}</width>");
+    }
+    if let Some([r, g, b]) = attr(BRANCH_COLOR_ATTR).and_then(rgb_from_hex) {
+        _ = writeln!(
+            s,
+            "{ind}  <color><red>{r}</red><green>{g}</green><blue>{b}</blue></color>"
+        );
+    }
+    if TAXONOMY_FIELDS.iter().any(|(_, key)| node_attr(key).is_some()) {
+        _ = writeln!(s, "{ind}  <taxonomy>");
+        for (tag, key) in TAXONOMY_FIELDS {
+            if let Some(value) = node_attr(key) {
+                _ = writeln!(
+                    s,
+                    "{ind}    <{tag}>{}</{tag}>",
+                    xml_escape(value)
+                );
+            }
+        }
+        _ = writeln!(s, "{ind}  </taxonomy>");
+    }
+    if let Some(value) = node_attr(DATE_ATTR) {
+        _ = writeln!(
+            s,
+            "{ind}  <date><value>{}</value></date>",
+            xml_escape(value)
+        );
+    }
+
+    let written = |key: &str| {
+        CONFIDENCE_TYPES.contains(&key)
+            || TAXONOMY_FIELDS.iter().any(|(_, k)| *k == key)
+            || [WIDTH_ATTR, BRANCH_COLOR_ATTR, DATE_ATTR].contains(&key)
+    };
+    for (attrs, applies_to) in
+        [(&node_attrs, "node"), (&branch_attrs, "parent_branch")]
+    {
+        for (key, value) in attrs.iter().filter(|(k, _)| !written(k)) {
+            let datatype = match is_numeric(value) {
+                true => "xsd:double",
+                false => "xsd:string",
+            };
+            _ = writeln!(
+                s,
+                "{ind}  <property ref=\"{PROPERTY_REF_PREFIX}:{}\" datatype=\"{datatype}\" applies_to=\"{applies_to}\">{}</property>",
+                xml_escape(key),
+                xml_escape(value)
+            );
+        }
+    }
+
+    for &c in &nd.children {
+        write_clade(tree, nodes, node_ids, c, depth + 1, s);
+    }
+    _ = writeln!(s, "{ind}</clade>");
+}
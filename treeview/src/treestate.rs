@@ -1,19 +1,33 @@
+use crate::AttributeEdit;
+use crate::BrlenTransform;
 use crate::CladeHighlight;
 use crate::CladeHighlightType;
+use crate::Float;
+use crate::NodeEdit;
 use crate::NodesTableField;
+use crate::NodesTableRow;
 use crate::SortOrder;
 use crate::TreNodeOrd;
+use crate::brlen_transform::transformed_newick;
+use crate::consts::{BRANCH_COLOR_ATTR, UNDO_LIMIT};
+use crate::newick::{attribute_text, attributes_edited_newick, edited_newick};
+use crate::node_glyphs::numeric_node_attr_keys;
+use crate::parsimony::reconstructed_newick;
+use crate::placements::Placements;
+use crate::tree_xml::rgb_from_hex;
 
 use dendros::IndexRange;
 use rayon::slice::ParallelSliceMut;
 use riced::CnvCache;
 use riced::Color;
 
+use dendros::Attribute;
 use dendros::Edge;
 use dendros::Node;
 use dendros::NodeId;
 use dendros::Tree;
 use dendros::TreeFloat;
+use dendros::parse_trees;
 
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
@@ -21,6 +35,7 @@ use rayon::iter::ParallelIterator;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
 fn normalize_value<T>(
     min: impl Into<T>,
@@ -70,9 +85,13 @@ pub(super) struct TreeState {
     cache_cnv_sel_nodes: CnvCache,
     cache_cnv_filtered_nodes: CnvCache,
     cache_cnv_clade_highlights: CnvCache,
+    cache_cnv_tracks: CnvCache,
+    cache_cnv_node_glyphs: CnvCache,
+    cache_cnv_state_changes: CnvCache,
+    cache_cnv_placements: CnvCache,
 
-    // --- Caches of Edges Sorted by the Fields in the Edge Struct -------------
-    cache_edges_nodes_table: Option<Vec<Edge>>,
+    // --- Cache of Filtered and Sorted Rows of the Nodes Table ---------------
+    cache_nodes_table_rows: Option<Vec<NodesTableRow>>,
 
     // --- Caches of Values for Memoized Accessor Functions --------------------
     cache_tip_count: Option<usize>,
@@ -81,6 +100,8 @@ pub(super) struct TreeState {
     cache_has_tip_labs: Option<bool>,
     cache_has_int_labs: Option<bool>,
     cache_has_brlen: Option<bool>,
+    cache_num_node_attr_keys: Vec<String>,
+    cache_node_attr_keys: Vec<String>,
     cache_is_ultrametric: Option<Option<bool>>,
     cache_is_rooted: Option<bool>,
 
@@ -104,6 +125,86 @@ pub(super) struct TreeState {
     subtree_view_edges_tip: Vec<Edge>,
     subtree_view_edges_tip_tallest: Vec<Edge>,
     subtree_view_sel_edge_idxs: Vec<usize>,
+
+    // --- Undo ----------------------------------------------------------------
+    /// Trees from before the last edits, with their revisions.
+    undo_stack: Vec<(Tree, usize)>,
+    /// Changes with every edit and returns to the earlier value when the edit
+    /// is undone, for telling whether the tree differs from a saved version.
+    revision: usize,
+    revision_count: usize,
+
+    // --- Phylogenetic Placements ---------------------------------------------
+    placements: Option<Placements>,
+}
+
+/// A parsed tree together with the values [`TreeState`] would otherwise
+/// compute when it is initialized. Preparing a tree does not touch any UI
+/// state, so large trees can be prepared on a background thread.
+#[derive(Debug, Clone)]
+pub struct PreparedTree {
+    tre: Tree,
+    tre_srtd_asc: Tree,
+    tip_count: usize,
+    node_count: usize,
+    max_first_node_to_tip_distance: TreeFloat,
+    has_tip_labs: bool,
+    has_int_labs: bool,
+    has_brlen: bool,
+    is_ultrametric: Option<bool>,
+    is_rooted: bool,
+    num_node_attr_keys: Vec<String>,
+    node_attr_keys: Vec<String>,
+    placements: Option<Placements>,
+}
+
+impl PreparedTree {
+    pub fn new(tre: Tree) -> Self {
+        let tre_srtd_asc = Tree::sorted_clone(&tre, false);
+        let max_first_node_to_tip_distance =
+            tre.max_first_node_to_tip_distance();
+        let epsilon = max_first_node_to_tip_distance / 1e2;
+
+        let (num_node_attr_keys, node_attr_keys) = match tre_srtd_asc.edges() {
+            Some(edges) => {
+                let mut node_attr_keys: Vec<String> = edges
+                    .iter()
+                    .flat_map(|edge| {
+                        tre_srtd_asc
+                            .node_attributes(edge.node_id)
+                            .iter()
+                            .map(|(key, _)| key.clone())
+                            .collect::<Vec<String>>()
+                    })
+                    .collect();
+                node_attr_keys.sort();
+                node_attr_keys.dedup();
+                (numeric_node_attr_keys(&tre_srtd_asc, edges), node_attr_keys)
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Self {
+            tip_count: tre.tip_count_all(),
+            node_count: tre.node_count_all(),
+            max_first_node_to_tip_distance,
+            has_tip_labs: tre.has_tip_labels(),
+            has_int_labs: tre.has_internal_node_labels(),
+            has_brlen: tre.has_branch_lengths(),
+            is_ultrametric: tre.is_ultrametric(epsilon),
+            is_rooted: tre.is_rooted(),
+            num_node_attr_keys,
+            node_attr_keys,
+            placements: None,
+            tre,
+            tre_srtd_asc,
+        }
+    }
+
+    pub(crate) fn with_placements(mut self, placements: Placements) -> Self {
+        self.placements = Some(placements);
+        self
+    }
 }
 
 impl TreeState {
@@ -114,37 +215,38 @@ impl TreeState {
     }
 
     pub(super) fn init(&mut self, tre: Tree) {
-        self.t_orig = tre;
-        self.t_srtd_asc = None;
+        self.init_prepared(PreparedTree::new(tre));
+    }
+
+    pub(super) fn init_prepared(&mut self, prepared: PreparedTree) {
+        self.t_orig = prepared.tre;
+        self.t_srtd_asc = Some(prepared.tre_srtd_asc);
         self.t_srtd_desc = None;
 
         self.edges_tip = vec![];
 
-        self.cache_has_brlen = None;
-        self.cache_has_int_labs = None;
-        self.cache_has_tip_labs = None;
-        self.cache_is_rooted = None;
-        self.cache_is_ultrametric = None;
-        self.cache_node_count = None;
-        self.cache_tip_count = None;
-        self.cache_max_first_node_to_tip_distance = None;
-
-        self.cache_has_brlen = Some(self.has_brlen());
-        self.cache_has_int_labs = Some(self.has_int_labels());
-        self.cache_has_tip_labs = Some(self.has_tip_labels());
-        self.cache_is_ultrametric = Some(self.is_ultrametric());
-        self.cache_is_rooted = Some(self.is_rooted_tree());
-        self.cache_node_count = Some(self.node_count_tree());
-        self.cache_tip_count = Some(self.tip_count_tree());
+        self.cache_has_brlen = Some(prepared.has_brlen);
+        self.cache_has_int_labs = Some(prepared.has_int_labs);
+        self.cache_has_tip_labs = Some(prepared.has_tip_labs);
+        self.cache_is_ultrametric = Some(prepared.is_ultrametric);
+        self.cache_is_rooted = Some(prepared.is_rooted);
+        self.cache_node_count = Some(prepared.node_count);
+        self.cache_tip_count = Some(prepared.tip_count);
         self.cache_max_first_node_to_tip_distance =
-            Some(self.max_first_node_to_tip_distance_tree());
+            Some(prepared.max_first_node_to_tip_distance);
+        self.cache_num_node_attr_keys = prepared.num_node_attr_keys;
+        self.cache_node_attr_keys = prepared.node_attr_keys;
+        // Edited copies of a tree keep the placements, which refer to the
+        // branches by their edge numbers.
+        if prepared.placements.is_some() {
+            self.placements = prepared.placements;
+        }
 
         self.clear_cache_of_edges_for_nodes_table();
         self.clear_caches_cnv();
 
         self.update_selected_node_ids();
 
-        self.sort_asc();
         self.sort(self.node_ord_opt);
 
         if let Some(subtree_view_node_id) = self.subtree_view_node_id {
@@ -152,6 +254,120 @@ impl TreeState {
         }
     }
 
+    /// Replaces the tree with an edited copy of it, e.g. one written out and
+    /// parsed back. Node ids are not stable across such copies, so the
+    /// selection, clade highlights and subtree view are carried over by clade.
+    /// The replaced tree can be restored with [`TreeState::undo`].
+    pub(super) fn replace_tree(&mut self, tre: Tree) {
+        self.push_undo();
+        self.swap_tree(tre);
+    }
+
+    /// Keeps a copy of the current tree for [`TreeState::undo`] before it is
+    /// edited.
+    fn push_undo(&mut self) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            _ = self.undo_stack.remove(0);
+        }
+        self.undo_stack.push((self.tree().clone(), self.revision));
+        self.next_revision();
+    }
+
+    fn next_revision(&mut self) {
+        self.revision_count += 1;
+        self.revision = self.revision_count;
+    }
+
+    pub(super) fn revision(&self) -> usize {
+        self.revision
+    }
+
+    /// Records an edit that is not undoable, e.g. a clade highlight. Undo
+    /// keeps it, so none of the earlier revisions can be returned to.
+    pub(super) fn mark_edited(&mut self) {
+        for (_, revision) in &mut self.undo_stack {
+            self.revision_count += 1;
+            *revision = self.revision_count;
+        }
+        self.next_revision();
+    }
+
+    pub(super) fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Restores the tree from before the last edit, i.e. the last call to
+    /// [`TreeState::replace_tree`], [`TreeState::root`],
+    /// [`TreeState::unroot`] or [`TreeState::remove_node`].
+    pub(super) fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some((tre, revision)) => {
+                self.swap_tree(tre);
+                self.revision = revision;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the tree with a new version of it read from the same file.
+    /// The selection, clade highlights and subtree view are carried over by
+    /// clade as in [`TreeState::replace_tree`], but the edits made to the
+    /// previous version can no longer be undone.
+    pub(super) fn reload(&mut self, prepared: PreparedTree) {
+        self.undo_stack.clear();
+        self.next_revision();
+        self.swap_prepared(prepared);
+    }
+
+    fn swap_tree(&mut self, tre: Tree) {
+        self.swap_prepared(PreparedTree::new(tre));
+    }
+
+    fn swap_prepared(&mut self, prepared: PreparedTree) {
+        let keys_old = clade_keys(self.tree());
+        let sel_node_ids = std::mem::take(&mut self.sel_node_ids);
+        let highlighted_clades = std::mem::take(&mut self.highlighted_clades);
+        let subtree_view_node_id = self.subtree_view_node_id;
+        self.close_subtree_view();
+        self.found_node_ids.clear();
+        self.found_edge_idxs.clear();
+        self.tmp_found_node_id = None;
+
+        self.init_prepared(prepared);
+
+        let id_for_key: HashMap<(u64, usize), NodeId> = clade_keys(self.tree())
+            .into_iter()
+            .map(|(id, k)| (k, id))
+            .collect();
+        let new_id = |node_id: NodeId| {
+            keys_old.get(&node_id).and_then(|k| id_for_key.get(k)).copied()
+        };
+
+        self.sel_node_ids =
+            sel_node_ids.into_iter().filter_map(&new_id).collect();
+        self.sel_edge_idxs = self.sel_edge_idxs_prep_tree();
+
+        for (node_id, clade_highlight) in highlighted_clades {
+            if let Some(node_id) = new_id(node_id) {
+                self.add_clade_highlight(
+                    node_id, clade_highlight.color, clade_highlight.label,
+                    clade_highlight.highlight_type,
+                );
+            }
+        }
+
+        if let Some(node_id) = subtree_view_node_id.and_then(&new_id) {
+            self.set_subtree_view(node_id);
+        }
+
+        if let Some(search_query) = &self.search_query.clone()
+            && let Some(tip_only_search) = self.tip_only_search
+        {
+            self.filter_nodes(search_query, tip_only_search);
+        }
+    }
+
     // -------------------------------------------------------------------------
 
     // =========================================================================
@@ -283,6 +499,20 @@ impl TreeState {
         }
     }
 
+    /// Node attributes with numeric values that can be drawn as node glyphs.
+    pub(super) fn num_node_attr_keys(&self) -> &[String] {
+        &self.cache_num_node_attr_keys
+    }
+
+    pub(super) fn node_attr_keys(&self) -> &[String] {
+        &self.cache_node_attr_keys
+    }
+
+    /// Placements read together with the tree from a jplace file.
+    pub(super) fn placements(&self) -> Option<&Placements> {
+        self.placements.as_ref()
+    }
+
     pub(super) fn has_brlen(&self) -> bool {
         if let Some(cached) = self.cache_has_brlen {
             cached
@@ -318,25 +548,30 @@ impl TreeState {
 
     // --- Utilities -----------------------------------------------------------
 
-    pub(super) fn edges_in_range(
+    pub(super) fn nodes_table_rows_in_range(
         &self,
         start_idx: usize,
         max_to_return: usize,
-    ) -> Option<Vec<Edge>> {
-        if let Some(edges) = &self.cache_edges_nodes_table {
-            let end_idx = (start_idx + max_to_return).min(edges.len());
+    ) -> Option<Vec<NodesTableRow>> {
+        if let Some(rows) = &self.cache_nodes_table_rows {
+            let end_idx = (start_idx + max_to_return).min(rows.len());
             Some(if start_idx < end_idx {
-                edges[start_idx..end_idx].to_vec()
+                rows[start_idx..end_idx].to_vec()
             } else {
-                edges.iter().take(max_to_return).cloned().collect()
+                rows.iter().take(max_to_return).cloned().collect()
             })
         } else {
             None
         }
     }
 
-    pub(super) fn edge_count(&self) -> usize {
-        if let Some(edges) = self.edges() { edges.len() } else { 0 }
+    /// All rows of the nodes table, filtered and in its current sort order.
+    pub(super) fn nodes_table_rows(&self) -> Option<&Vec<NodesTableRow>> {
+        self.cache_nodes_table_rows.as_ref()
+    }
+
+    pub(super) fn nodes_table_row_count(&self) -> usize {
+        self.cache_nodes_table_rows.as_ref().map(Vec::len).unwrap_or_default()
     }
 
     pub(super) fn edges(&self) -> Option<&Vec<Edge>> {
@@ -660,6 +895,7 @@ impl TreeState {
         let rslt = tre.remove_node(node_id);
         match rslt {
             Ok(node_id) => {
+                self.push_undo();
                 self.init(tre);
                 Some(node_id)
             }
@@ -687,6 +923,7 @@ impl TreeState {
         let rslt = tre.root(node_id);
         match rslt {
             Ok(node_id) => {
+                self.push_undo();
                 self.init(tre);
                 Some(node_id)
             }
@@ -706,6 +943,7 @@ impl TreeState {
             {
                 self.deselect_node(yanked_node_id);
             }
+            self.push_undo();
             self.init(tre);
             Some(yanked_node)
         } else {
@@ -717,6 +955,97 @@ impl TreeState {
 
     // =========================================================================
 
+    // --- Branch Lengths ------------------------------------------------------
+
+    pub(super) fn transform_brlens(
+        &mut self,
+        transform: BrlenTransform,
+        param: Option<TreeFloat>,
+    ) -> bool {
+        let tre_opt = transformed_newick(self.tree(), transform, param)
+            .and_then(|newick| parse_trees(newick).ok())
+            .and_then(|trees| trees.into_iter().next());
+        match tre_opt {
+            Some(tre) => {
+                self.replace_tree(tre);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stores the parsimony reconstruction of a categorical trait as `attr`
+    /// node attributes and returns the tree length. See
+    /// [`reconstructed_newick`] for the arguments.
+    pub(super) fn reconstruct_states(
+        &mut self,
+        attr: &str,
+        states: &[String],
+        tip_state: &dyn Fn(&str) -> Option<usize>,
+        costs: Option<&[Vec<Float>]>,
+    ) -> Option<Float> {
+        let (newick, tree_len) =
+            reconstructed_newick(self.tree(), attr, states, tip_state, costs)?;
+        let tre = parse_trees(newick).ok()?.into_iter().next()?;
+        self.replace_tree(tre);
+        Some(tree_len)
+    }
+
+    // -------------------------------------------------------------------------
+
+    // =========================================================================
+
+    // --- Node Edits ----------------------------------------------------------
+
+    /// Changes the label or branch length of a node. A selected tip stays
+    /// selected after its label is changed if the new label is unique.
+    pub(super) fn edit_node(
+        &mut self,
+        node_id: NodeId,
+        edit: &NodeEdit,
+    ) -> bool {
+        let tre_opt = edited_newick(self.tree(), node_id, edit)
+            .and_then(|newick| parse_trees(newick).ok())
+            .and_then(|trees| trees.into_iter().next());
+        let Some(tre) = tre_opt else {
+            return false;
+        };
+
+        let was_sel_tip =
+            self.is_tip(node_id) && self.sel_node_ids.contains(&node_id);
+        self.replace_tree(tre);
+
+        if was_sel_tip && let NodeEdit::Label(Some(label)) = edit {
+            let mut matching = self
+                .edges_tip_tree()
+                .iter()
+                .filter(|edge| edge.label.as_deref() == Some(label.as_str()))
+                .map(|edge| edge.node_id);
+            if let (Some(node_id), None) = (matching.next(), matching.next()) {
+                self.select_node(node_id);
+            }
+        }
+        true
+    }
+
+    /// Sets, removes or renames node or branch attributes.
+    pub(super) fn edit_attributes(&mut self, edit: &AttributeEdit) -> bool {
+        let tre_opt = attributes_edited_newick(self.tree(), edit)
+            .and_then(|newick| parse_trees(newick).ok())
+            .and_then(|trees| trees.into_iter().next());
+        match tre_opt {
+            Some(tre) => {
+                self.replace_tree(tre);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // -------------------------------------------------------------------------
+
+    // =========================================================================
+
     // --- Sorting -------------------------------------------------------------
 
     pub(super) fn sort(&mut self, node_ord_opt: TreNodeOrd) {
@@ -872,6 +1201,29 @@ impl TreeState {
         &self.sel_edge_idxs
     }
 
+    /// Sorted labels of the tips in the clades of the given nodes.
+    pub(super) fn clade_tip_labels(
+        &self,
+        node_ids: impl IntoIterator<Item = NodeId>,
+    ) -> Vec<String> {
+        let Some(edges) = self.tree().edges() else { return Vec::new() };
+        let mut labels: Vec<String> = node_ids
+            .into_iter()
+            .flat_map(|node_id| {
+                let mut ids = self.tree().descending_node_ids(node_id, true);
+                ids.push(node_id);
+                ids
+            })
+            .filter_map(|node_id| self.tree().edge_index_for_node_id(node_id))
+            .map(|edge_idx| &edges[edge_idx])
+            .filter(|edge| edge.is_tip)
+            .filter_map(|edge| edge.label.as_deref().map(String::from))
+            .collect();
+        labels.sort();
+        labels.dedup();
+        labels
+    }
+
     pub(super) fn select_deselect_node(&mut self, node_id: NodeId) {
         if self.sel_node_ids.contains(&node_id) {
             self.deselect_node(node_id);
@@ -892,7 +1244,7 @@ impl TreeState {
         };
 
         self.clear_cache_cnv_sel_nodes();
-        self.cache_edges_nodes_table = None;
+        self.cache_nodes_table_rows = None;
     }
 
     pub(super) fn deselect_node(&mut self, node_id: NodeId) {
@@ -914,7 +1266,7 @@ impl TreeState {
         };
 
         self.clear_cache_cnv_sel_nodes();
-        self.cache_edges_nodes_table = None;
+        self.cache_nodes_table_rows = None;
     }
 
     pub(super) fn select_deselect_node_exclusive(&mut self, node_id: NodeId) {
@@ -1166,23 +1518,93 @@ impl TreeState {
         !self.highlighted_clades.is_empty()
     }
 
+    /// Highlights every clade whose branch colour ([`BRANCH_COLOR_ATTR`])
+    /// differs from that of the branch above it.
+    pub(super) fn highlight_colored_clades(&mut self) {
+        let tree = self.tree();
+        let color = |node_id: NodeId| {
+            tree.branch_attributes(node_id)
+                .get(BRANCH_COLOR_ATTR)
+                .or_else(|| {
+                    tree.node_attributes(node_id).get(BRANCH_COLOR_ATTR)
+                })
+                .and_then(|attr| rgb_from_hex(&attribute_text(attr)))
+        };
+        let colored: Vec<(NodeId, [u8; 3])> = match self.edges() {
+            Some(edges) => edges
+                .iter()
+                .filter_map(|edge| {
+                    let rgb = color(edge.node_id)?;
+                    match edge.parent_node_id != edge.node_id
+                        && color(edge.parent_node_id) == Some(rgb)
+                    {
+                        true => None,
+                        false => Some((edge.node_id, rgb)),
+                    }
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        for (node_id, [r, g, b]) in colored {
+            self.add_clade_highlight(
+                node_id,
+                Color::from_rgb8(r, g, b),
+                node_id,
+                CladeHighlightType::Inside,
+            );
+        }
+    }
+
     // -------------------------------------------------------------------------
 
     // =========================================================================
 
-    // --- Cache of Edges for Nodes Table --------------------------------------
+    // --- Cache of Rows of the Nodes Table -----------------------------------
 
     fn clear_cache_of_edges_for_nodes_table(&mut self) {
-        self.cache_edges_nodes_table = None;
+        self.cache_nodes_table_rows = None;
     }
 
+    /// Rows of the nodes table whose label, node id or `attr` value contains
+    /// `filter`, sorted by the given column.
     pub(super) fn populate_cache_of_edges_for_nodes_table(
         &mut self,
         sort_column: NodesTableField,
         sort_direction: SortOrder,
+        filter: &str,
+        attr: Option<&str>,
     ) {
         if let Some(edges) = self.edges() {
-            let mut edges = edges.clone();
+            let has_brlen = self.has_brlen();
+            let depths_and_tip_counts =
+                node_depths_and_tip_counts(edges, has_brlen);
+            let max_depth = depths_and_tip_counts
+                .iter()
+                .map(|(depth, _)| *depth)
+                .fold(0e0, TreeFloat::max);
+            let attribute = |node_id: NodeId| -> Option<Attribute> {
+                let tree = self.tree();
+                let attr = attr?;
+                tree.node_attributes(node_id)
+                    .get(attr)
+                    .or_else(|| tree.branch_attributes(node_id).get(attr))
+                    .cloned()
+            };
+
+            let query = filter.trim().to_lowercase();
+            let mut rows: Vec<NodesTableRow> = edges
+                .iter()
+                .zip(depths_and_tip_counts)
+                .map(|(edge, (depth, tip_count))| NodesTableRow {
+                    edge: edge.clone(),
+                    depth,
+                    height: max_depth - depth,
+                    tip_count,
+                    attribute: attribute(edge.node_id),
+                })
+                .filter(|row| query.is_empty() || row.matches(&query))
+                .collect();
+
             let sel_node_ids = &self.sel_node_ids;
 
             let sorting_order = |ord: Ordering| match sort_direction {
@@ -1192,33 +1614,35 @@ impl TreeState {
 
             match sort_column {
                 NodesTableField::NodeId => {
-                    edges.par_sort_by(|a, b| {
-                        sorting_order(a.node_id.cmp(&b.node_id))
+                    rows.par_sort_by(|a, b| {
+                        sorting_order(a.edge.node_id.cmp(&b.edge.node_id))
                     });
                 }
 
                 NodesTableField::NodeLabel => {
-                    edges.par_sort_by(|a, b| {
-                        let label_a = a.label.as_deref().unwrap_or_default();
-                        let label_b = b.label.as_deref().unwrap_or_default();
+                    rows.par_sort_by(|a, b| {
+                        let label_a =
+                            a.edge.label.as_deref().unwrap_or_default();
+                        let label_b =
+                            b.edge.label.as_deref().unwrap_or_default();
                         sorting_order(label_a.cmp(label_b))
                     });
                 }
 
                 NodesTableField::Selected => {
-                    edges.par_sort_by(|a, b| {
-                        let sel_a = sel_node_ids.contains(&a.node_id);
-                        let sel_b = sel_node_ids.contains(&b.node_id);
+                    rows.par_sort_by(|a, b| {
+                        let sel_a = sel_node_ids.contains(&a.edge.node_id);
+                        let sel_b = sel_node_ids.contains(&b.edge.node_id);
                         sorting_order(sel_a.cmp(&sel_b))
                     });
                 }
 
                 NodesTableField::NodeType => {
-                    edges.par_sort_by(|a, b| {
+                    rows.par_sort_by(|a, b| {
                         if let Some(node_id_a) =
-                            self.t_orig.node(Some(a.node_id))
+                            self.t_orig.node(Some(a.edge.node_id))
                             && let Some(node_id_b) =
-                                self.t_orig.node(Some(b.node_id))
+                                self.t_orig.node(Some(b.edge.node_id))
                         {
                             let type_a = node_id_a.node_type();
                             let type_b = node_id_b.node_type();
@@ -1230,15 +1654,53 @@ impl TreeState {
                 }
 
                 NodesTableField::BranchLength => {
-                    edges.par_sort_by(|a, b| {
+                    rows.par_sort_by(|a, b| {
                         sorting_order(
-                            a.branch_length.total_cmp(&b.branch_length),
+                            a.edge
+                                .branch_length
+                                .total_cmp(&b.edge.branch_length),
+                        )
+                    });
+                }
+
+                NodesTableField::Depth => {
+                    rows.par_sort_by(|a, b| {
+                        sorting_order(a.depth.total_cmp(&b.depth))
+                    });
+                }
+
+                NodesTableField::Height => {
+                    rows.par_sort_by(|a, b| {
+                        sorting_order(a.height.total_cmp(&b.height))
+                    });
+                }
+
+                NodesTableField::TipCount => {
+                    rows.par_sort_by(|a, b| {
+                        sorting_order(a.tip_count.cmp(&b.tip_count))
+                    });
+                }
+
+                NodesTableField::ParentId => {
+                    rows.par_sort_by(|a, b| {
+                        sorting_order(
+                            a.parent_node_id().cmp(&b.parent_node_id()),
+                        )
+                    });
+                }
+
+                NodesTableField::Attribute => {
+                    rows.par_sort_by(|a, b| {
+                        sorting_order(
+                            a.attribute
+                                .partial_cmp(&b.attribute)
+                                .unwrap_or(Ordering::Equal),
                         )
                     });
                 }
             }
 
-            self.cache_edges_nodes_table = Some(edges);
+            self.cache_nodes_table_rows = Some(rows);
         }
     }
 
@@ -1276,6 +1738,22 @@ impl TreeState {
         &self.cache_cnv_clade_highlights
     }
 
+    pub(super) fn cache_cnv_tracks(&self) -> &CnvCache {
+        &self.cache_cnv_tracks
+    }
+
+    pub(super) fn cache_cnv_node_glyphs(&self) -> &CnvCache {
+        &self.cache_cnv_node_glyphs
+    }
+
+    pub(super) fn cache_cnv_state_changes(&self) -> &CnvCache {
+        &self.cache_cnv_state_changes
+    }
+
+    pub(super) fn cache_cnv_placements(&self) -> &CnvCache {
+        &self.cache_cnv_placements
+    }
+
     // -------------------------------------------------------------------------
 
     pub(super) fn clear_cache_cnv_edge(&self) {
@@ -1306,6 +1784,22 @@ impl TreeState {
         self.cache_cnv_clade_highlights.clear();
     }
 
+    pub(super) fn clear_cache_cnv_tracks(&self) {
+        self.cache_cnv_tracks.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_node_glyphs(&self) {
+        self.cache_cnv_node_glyphs.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_state_changes(&self) {
+        self.cache_cnv_state_changes.clear();
+    }
+
+    pub(super) fn clear_cache_cnv_placements(&self) {
+        self.cache_cnv_placements.clear();
+    }
+
     pub(super) fn clear_caches_cnv(&self) {
         self.clear_cache_cnv_edge();
         self.clear_cache_cnv_lab_tip();
@@ -1314,9 +1808,86 @@ impl TreeState {
         self.clear_cache_cnv_sel_nodes();
         self.clear_cache_cnv_filtered_nodes();
         self.clear_cache_cnv_clade_highlights();
+        self.clear_cache_cnv_tracks();
+        self.clear_cache_cnv_node_glyphs();
+        self.clear_cache_cnv_state_changes();
+        self.clear_cache_cnv_placements();
     }
 
     // -------------------------------------------------------------------------
 
     // =========================================================================
 }
+
+/// Order-independent key of the set of tip labels below each node.
+fn clade_keys(tree: &Tree) -> HashMap<NodeId, (u64, usize)> {
+    let mut keys: HashMap<NodeId, (u64, usize)> = HashMap::new();
+    if let Some(edges) = tree.edges() {
+        for edge in edges.iter().filter(|edge| edge.is_tip) {
+            let mut hasher = DefaultHasher::new();
+            edge.label.as_deref().hash(&mut hasher);
+            let hash = hasher.finish();
+
+            let mut node_id = Some(edge.node_id);
+            while let Some(id) = node_id {
+                let (h, n) = keys.entry(id).or_default();
+                *h = h.wrapping_add(hash);
+                *n += 1;
+                node_id = tree.parent_node_id(id).filter(|&p| p != id);
+            }
+        }
+    }
+    keys
+}
+
+/// Distance from the first node and number of descendant tips of the node of
+/// each edge. Every branch counts as one for trees without branch lengths.
+fn node_depths_and_tip_counts(
+    edges: &[Edge],
+    has_brlen: bool,
+) -> Vec<(TreeFloat, usize)> {
+    let idx_for_id: HashMap<NodeId, usize> =
+        edges.iter().enumerate().map(|(i, edge)| (edge.node_id, i)).collect();
+    let brlen = |edge: &Edge| match has_brlen {
+        true => edge.branch_length,
+        false => 1e0,
+    };
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); edges.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut depths: Vec<TreeFloat> = vec![0e0; edges.len()];
+    for (i, edge) in edges.iter().enumerate() {
+        match idx_for_id.get(&edge.parent_node_id) {
+            Some(&parent_idx) if parent_idx != i => {
+                children[parent_idx].push(i)
+            }
+            // Edges whose parent has no edge: the root, the children of the
+            // first node of an unrooted tree and the top of a subtree view.
+            _ => {
+                if edge.parent_node_id != edge.node_id {
+                    depths[i] = brlen(edge);
+                }
+                stack.push(i);
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = Vec::with_capacity(edges.len());
+    while let Some(i) = stack.pop() {
+        order.push(i);
+        for &c in &children[i] {
+            depths[c] = depths[i] + brlen(&edges[c]);
+            stack.push(c);
+        }
+    }
+
+    let mut tip_counts: Vec<usize> = vec![0; edges.len()];
+    for &i in order.iter().rev() {
+        tip_counts[i] = match children[i].is_empty() {
+            true => 1,
+            false => children[i].iter().map(|&c| tip_counts[c]).sum(),
+        };
+    }
+
+    depths.into_iter().zip(tip_counts).collect()
+}
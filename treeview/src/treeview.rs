@@ -1,7 +1,17 @@
 use crate::edge_utils::*;
-use crate::pdf::tree_to_pdf;
+use crate::newick::{
+    attribute_text, is_valid_attribute_key, nwk_attribute_from_text,
+};
+use crate::node_glyphs::attr_num;
+use crate::parsimony::{parse_cost_matrix, state_attr_key, unit_cost_matrix};
+use crate::pdf::{tanglegram_to_pdf, tree_to_pdf};
+use crate::root_to_tip::{date_from_label, decimal_year};
+use crate::tree_stats::edge_spans;
 use crate::*;
 
+use std::collections::HashMap;
+use std::time::Instant;
+
 use serde::{Deserialize, Serialize};
 
 #[allow(missing_debug_implementations)]
@@ -15,29 +25,86 @@ pub struct TreeView {
     pub(super) tre_pane_id: Option<Pane>,
     pub(super) plot_pane_id: Option<Pane>,
     pub(super) nodes_table_pane_id: Option<Pane>,
+    pub(super) tangle_pane_id: Option<Pane>,
+    pub(super) tree_set_pane_id: Option<Pane>,
+    pub(super) msa_pane_id: Option<Pane>,
+    pub(super) tree_stats_pane_id: Option<Pane>,
     // -------------------------------------------------------------------------
     pub(super) tre_cnv: TreeCnv,
     pub(super) plot_cnv: PlotCnv,
+    pub(super) tangle_cnv: TangleCnv,
+    pub(super) msa_cnv: MsaCnv,
+    // -------------------------------------------------------------------------
+    tangle_tre_idx: Option<usize>,
+    pub(super) tangle_tre_opts: Vec<usize>,
+    // -------------------------------------------------------------------------
+    pub(super) tree_set: TreeSet,
+    pub(super) tree_set_dists: Vec<TreeSetDist>,
+    derived_tre_ids: HashSet<usize>,
+    // -------------------------------------------------------------------------
+    pub(super) tree_stats: Option<TreeStats>,
+    // -------------------------------------------------------------------------
+    pub(super) brlen_transform: BrlenTransform,
+    pub(super) brlen_transform_param: String,
+    // -------------------------------------------------------------------------
+    pub(super) metadata: Option<Metadata>,
+    pub(super) track_types: Vec<TrackType>,
+    // -------------------------------------------------------------------------
+    pub(super) parsimony_col: Option<usize>,
+    pub(super) parsimony_states: Vec<String>,
+    pub(super) parsimony_method: ParsimonyMethod,
+    pub(super) parsimony_costs: String,
+    pub(super) parsimony: Option<Parsimony>,
+    // -------------------------------------------------------------------------
+    pub(super) plot_attr: Option<String>,
+    pub(super) rtt_date_col: Option<usize>,
+    pub(super) rtt_date_pattern: String,
     // -------------------------------------------------------------------------
     pub(super) tre_cnv_w_idx: u16,
     pub(super) tre_cnv_h_idx: u16,
     pub(super) tre_cnv_z_idx: u16,
+    pub(super) msa_col_w_idx: u16,
     // -------------------------------------------------------------------------
     pub plot_scrollable_id: &'static str,
     pub tre_scrollable_id: &'static str,
+    pub msa_scrollable_id: &'static str,
     pub nodes_table_scrollable_id: &'static str,
     pub attributes_table_scrollable_id: &'static str,
     pub search_text_input_id: &'static str,
+    pub outgroup_text_input_id: &'static str,
+    pub nodes_table_edit_input_id: &'static str,
+    pub attributes_table_edit_input_id: &'static str,
     // -------------------------------------------------------------------------
     pub(super) nodes_table_sort_col: NodesTableField,
     pub(super) nodes_table_sort_ord: SortOrder,
     pub(super) nodes_table_scroll_y_offset: Float,
+    pub(super) nodes_table_filter: String,
+    pub(super) nodes_table_attr: Option<String>,
+    pub(super) nodes_table_edit: Option<NodesTableEdit>,
     // -------------------------------------------------------------------------
     pub(super) attributes_table_sort_col: AttributesTableField,
     pub(super) attributes_table_sort_ord: SortOrder,
     pub(super) attributes_table_scroll_y_offset: Float,
+    pub(super) attributes_table_edit: Option<AttributesTableEdit>,
+    pub(super) new_attribute_key: String,
+    pub(super) new_attribute_value: String,
     // -------------------------------------------------------------------------
     keep_scroll_position_requested: bool,
+    /// Where to scroll the tree canvas to once its size is known, relative
+    /// to the size of the canvas.
+    pending_scroll_rel: Option<(Float, Float)>,
+    // -------------------------------------------------------------------------
+    /// When the trees were last replaced by new versions read from the file.
+    pub(super) reloaded_at: Option<Instant>,
+    /// Number of edits made to the trees, e.g. reroots, removed nodes and
+    /// clade highlights.
+    edit_count: usize,
+    /// [`TreeState::revision`] of each tree, by tree id, when the trees were
+    /// last saved.
+    saved_revisions: HashMap<usize, usize>,
+    /// Edit count when the trees were handed to a background thread to be
+    /// rooted. The rooted trees are dropped if anything was edited meanwhile.
+    pub(super) root_all_pending: Option<usize>,
     // -------------------------------------------------------------------------
     plot_cnv_needs_to_be_scrolled: bool,
     plot_cnv_scrolled: bool,
@@ -49,6 +116,7 @@ pub struct TreeView {
     is_new: bool,
     // -------------------------------------------------------------------------
     pub(super) search_string: String,
+    pub(super) outgroup_string: String,
     // -------------------------------------------------------------------------
     text_w_tip: TextWidth<'static>,
     // -------------------------------------------------------------------------
@@ -63,6 +131,12 @@ pub enum TvMsg {
     ContextMenuChosenIdx(usize),
     // -------------------------------------------------------------------------
     ExportPdf(std::path::PathBuf),
+    ExportTanglegramPdf(std::path::PathBuf),
+    ExportTreeStats(std::path::PathBuf),
+    ExportDataRequested(DataExport),
+    ExportData(DataExport, std::path::PathBuf),
+    /// An export could not be written: the path and the reason.
+    ExportFailed(std::path::PathBuf, String),
     // -------------------------------------------------------------------------
     TreeRectNoLongerStale,
     CursorLineVisChanged(bool),
@@ -94,6 +168,10 @@ pub enum TvMsg {
     OpnAngleChanged(u16),
     PaneResized(ResizeEvent),
     Root(NodeId),
+    RootAll(RootingMethod),
+    TreesRooted(Vec<(usize, Tree)>),
+    OutgroupChanged(String),
+    RootAllOnOutgroup,
     Unroot,
     RemoveNode(NodeId),
     SetSubtreeView(NodeId),
@@ -102,18 +180,76 @@ pub enum TvMsg {
     SelectDeselectNode(NodeId),
     SelectDeselectNodeExclusive(NodeId),
     TreesLoaded(Vec<Tree>),
+    PreparedTreesLoaded(Vec<PreparedTree>),
+    PreparedTreesAppended(Vec<PreparedTree>),
+    PreparedTreesAppendFinished,
+    PreparedTreesReloaded(Vec<PreparedTree>),
     TreStyOptChanged(TreSty),
     RootVisChanged(bool),
     RootLenSelChanged(u16),
     TogglePlot(bool),
     ToggleLtt(bool),
+    PlotTypeChanged(PlotType),
+    PlotAttrChanged(String),
+    RttDateSourceChanged(String),
+    RttDatePatternChanged(String),
     ToggleGts(bool),
     TreUnitChanged(TreUnit),
+    BrlenTransformChanged(BrlenTransform),
+    BrlenTransformParamChanged(String),
+    TransformBrlens,
+    MetadataLoaded(String),
+    MetadataParseFailed,
+    TrackTypeChanged(usize, TrackType),
+    ClearMetadata,
+    ParsimonyColumnChanged(String),
+    ParsimonyMethodChanged(ParsimonyMethod),
+    ParsimonyCostsChanged(String),
+    ReconstructStates,
+    StateChangesVisChanged(bool),
+    PieAttrToggled(String, bool),
+    SymbolAttrChanged(Option<String>),
+    SymbolShapeChanged(SymbolShape),
+    SymbolEncodingChanged(SymbolEncoding),
+    ClearNodeGlyphs,
+    PlacementCirclesVisChanged(bool),
+    PlacementSizeChanged(PlacementSize),
+    PlacementNameFilterChanged(String),
+    PlacementDensityVisChanged(bool),
+    AlignmentLoaded(String),
+    ClearAlignment,
+    MsaColWidthChanged(u16),
     ToggleNodesTable,
+    ToggleTanglegram,
+    TanglegramTreeChanged(usize),
+    ToggleTreeSet,
+    ToggleTreeStats,
+    CopyTreeStats,
+    AddConsensusTree(ConsensusType),
+    DerivedTreeAdded(Box<PreparedTree>),
+    AnnotateCladeSupport,
     NodesTableSortColumnChanged(NodesTableField),
     NodesTableScrolledOrResized(ScrollableViewport),
+    NodesTableFilterChanged(String),
+    NodesTableAttrChanged(String),
+    NodesTableEditBegin(NodeId, NodesTableField),
+    NodesTableEditChanged(String),
+    NodesTableEditSubmitted,
+    Undo,
     AttributesTableSortColumnChanged(AttributesTableField),
     AttributesTableScrolledOrResized(ScrollableViewport),
+    AttributesTableEditBegin(
+        NodeId,
+        AttributeSelector,
+        String,
+        AttributesTableField,
+    ),
+    AttributesTableEditChanged(String),
+    AttributesTableEditSubmitted,
+    NewAttributeKeyChanged(String),
+    NewAttributeValueChanged(String),
+    AddAttributeToSelected,
+    RemoveAttributeFromSelected,
     // -------------------------------------------------------------------------
     AddCladeHighlight((NodeId, Color)),
     RemoveCladeHighlight(NodeId),
@@ -122,9 +258,14 @@ pub enum TvMsg {
     // -------------------------------------------------------------------------
     TreCnvScrolledOrResized(ScrollableViewport),
     PlotCnvScrolledOrResized(ScrollableViewport),
+    MsaCnvScrolledOrResized(ScrollableViewport),
     // -------------------------------------------------------------------------
-    CursorOnTreCnv { x: Option<Float> },
-    CursorOnPlotCnv { x: Option<Float> },
+    CursorOnTreCnv {
+        x: Option<Float>,
+    },
+    CursorOnPlotCnv {
+        x: Option<Float>,
+    },
     // -------------------------------------------------------------------------
     ToggleSearchBar,
     ShowSearchBar,
@@ -138,6 +279,21 @@ pub enum TvMsg {
     // -------------------------------------------------------------------------
     TipLabWidthSetByUser(Option<Float>),
     SelectionLockChanged(bool),
+    // -------------------------------------------------------------------------
+    ViewportRestored(TvViewport),
+}
+
+/// The tree in view, the size of the tree canvas and where it is scrolled to,
+/// so that a view can be restored later.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct TvViewport {
+    pub tre_idx: usize,
+    pub cnv_w_idx: u16,
+    pub cnv_h_idx: u16,
+    pub cnv_z_idx: u16,
+    /// Centre of the visible part of the canvas relative to its size.
+    pub x_mid_rel: Float,
+    pub y_mid_rel: Float,
 }
 
 impl Default for TreeView {
@@ -146,25 +302,38 @@ impl Default for TreeView {
         Self {
             plot_cnv: PlotCnv::new(cfg.clone(), false),
             tre_cnv: TreeCnv::new(cfg.clone(), false),
+            tangle_cnv: TangleCnv::new(cfg.clone()),
+            msa_cnv: MsaCnv::default(),
             cfg,
             // -----------------------------------------------------------------
             tre_cnv_w_idx: 1,
             tre_cnv_h_idx: 1,
             tre_cnv_z_idx: 1,
+            msa_col_w_idx: 8,
             // -----------------------------------------------------------------
             tre_scrollable_id: "tre_scrollable",
             plot_scrollable_id: "plot_scrollable",
+            msa_scrollable_id: "msa_scrollable",
             nodes_table_scrollable_id: "nodes_table",
             attributes_table_scrollable_id: "attributes_table",
             search_text_input_id: "search_text_input",
+            outgroup_text_input_id: "outgroup_text_input",
+            nodes_table_edit_input_id: "nodes_table_edit_input",
+            attributes_table_edit_input_id: "attributes_table_edit_input",
             // -----------------------------------------------------------------
             nodes_table_sort_col: NodesTableField::NodeId,
             nodes_table_sort_ord: SortOrder::Ascending,
             nodes_table_scroll_y_offset: ZRO,
+            nodes_table_filter: String::new(),
+            nodes_table_attr: None,
+            nodes_table_edit: None,
             // -----------------------------------------------------------------
             attributes_table_sort_col: AttributesTableField::Name,
             attributes_table_sort_ord: SortOrder::Ascending,
             attributes_table_scroll_y_offset: ZRO,
+            attributes_table_edit: None,
+            new_attribute_key: String::new(),
+            new_attribute_value: String::new(),
             // -----------------------------------------------------------------
             is_new: true,
             // -----------------------------------------------------------------
@@ -174,13 +343,42 @@ impl Default for TreeView {
             tre_pane_id: None,
             plot_pane_id: None,
             nodes_table_pane_id: None,
+            tangle_pane_id: None,
+            tangle_tre_idx: None,
+            tangle_tre_opts: Vec::new(),
+            tree_set_pane_id: None,
+            msa_pane_id: None,
+            tree_stats_pane_id: None,
+            tree_set: TreeSet::default(),
+            tree_set_dists: Vec::new(),
+            derived_tre_ids: HashSet::new(),
+            tree_stats: None,
+            brlen_transform: BrlenTransform::default(),
+            brlen_transform_param: String::new(),
+            metadata: None,
+            track_types: Vec::new(),
+            // -----------------------------------------------------------------
+            parsimony_col: None,
+            parsimony_states: Vec::new(),
+            parsimony_method: ParsimonyMethod::default(),
+            parsimony_costs: String::new(),
+            parsimony: None,
+            plot_attr: None,
+            rtt_date_col: None,
+            rtt_date_pattern: String::new(),
             keep_scroll_position_requested: false,
+            pending_scroll_rel: None,
+            reloaded_at: None,
+            edit_count: 0,
+            saved_revisions: HashMap::new(),
+            root_all_pending: None,
             plot_cnv_needs_to_be_scrolled: false,
             plot_cnv_scrolled: false,
             tre_scr_h: ZRO,
             tre_scr_w: ZRO,
             tre_cnv_scrolled: false,
             search_string: String::new(),
+            outgroup_string: String::new(),
             // -----------------------------------------------------------------
             text_w_tip: text_width(SF, FNT_NAME_LAB),
             // -----------------------------------------------------------------
@@ -197,7 +395,9 @@ impl TreeView {
         let cfg = Rc::new(cfg);
         let plot_cnv = PlotCnv::new(cfg.clone(), draw_debug);
         let tre_cnv = TreeCnv::new(cfg.clone(), draw_debug);
-        Self { cfg, plot_cnv, tre_cnv, ..Default::default() }
+        let mut tangle_cnv = TangleCnv::new(cfg.clone());
+        tangle_cnv.lab_size = tre_cnv.lab_size_tip;
+        Self { cfg, plot_cnv, tre_cnv, tangle_cnv, ..Default::default() }
     }
 
     pub fn config(&self) -> TreeViewConfig {
@@ -219,10 +419,12 @@ impl TreeView {
                 self.with_exclusive_sel_tre_mut(&mut |tre| {
                     _ = tre.remove_node(node_id);
                 });
+                self.edit_count += 1;
+                self.update_tree_set();
                 self.update_root_len_frac();
                 self.populate_cache_of_edges_sorted_by_field();
                 self.update_draw_labs_allowed();
-                self.set_ltt_plot_data();
+                self.set_plot_data();
 
                 self.tre_cnv.stale_tre_rect = true;
                 self.clear_caches_cnv_ts_all();
@@ -236,7 +438,7 @@ impl TreeView {
                 self.update_root_len_frac();
                 self.populate_cache_of_edges_sorted_by_field();
                 self.update_draw_labs_allowed();
-                self.set_ltt_plot_data();
+                self.set_plot_data();
 
                 self.tre_cnv.stale_tre_rect = true;
                 self.clear_caches_cnv_ts_all();
@@ -250,7 +452,7 @@ impl TreeView {
                 self.update_root_len_frac();
                 self.populate_cache_of_edges_sorted_by_field();
                 self.update_draw_labs_allowed();
-                self.set_ltt_plot_data();
+                self.set_plot_data();
 
                 self.tre_cnv.stale_tre_rect = true;
                 self.clear_caches_cnv_ts_all();
@@ -276,22 +478,89 @@ impl TreeView {
                     let cnv_vs = RectVals::wh(cnv_w, cnv_h);
 
                     _ = tree_to_pdf(
-                        path_buf, tre_vs, cnv_vs, tree_state, self.cfg.tre_sty,
-                        opn_angle, rot_angle, root_len,
-                        self.tre_cnv.lab_size_tip, self.tre_cnv.lab_size_int,
+                        path_buf,
+                        tre_vs,
+                        cnv_vs,
+                        tree_state,
+                        self.cfg.tre_sty,
+                        opn_angle,
+                        rot_angle,
+                        root_len,
+                        self.tre_cnv.lab_size_tip,
+                        self.tre_cnv.lab_size_int,
                         self.tre_cnv.lab_size_brnch,
                         self.tre_cnv.lab_offset_tip,
                         self.tre_cnv.lab_offset_int,
-                        self.tre_cnv.lab_offset_brnch, self.cfg.align_tip_labs,
+                        self.tre_cnv.lab_offset_brnch,
+                        self.cfg.align_tip_labs,
                         self.cfg.trim_tip_labs,
-                        self.tre_cnv.trim_tip_labs_to_nchar, draw_labs_tip,
-                        draw_labs_int, draw_labs_brnch,
+                        self.tre_cnv.trim_tip_labs_to_nchar,
+                        draw_labs_tip,
+                        draw_labs_int,
+                        draw_labs_brnch,
                         self.tre_cnv.draw_clade_highlights,
-                        self.cfg.show_scale_bar, self.tre_cnv.draw_debug,
+                        self.cfg.show_scale_bar,
+                        self.tre_cnv.draw_debug,
+                        &self.tre_cnv.tracks,
+                        self.tre_cnv.tracks_offset,
+                        &self.tre_cnv.node_glyphs,
+                        self.tre_cnv.state_change_attr.as_deref(),
+                        &self.tre_cnv.placements_view,
                     );
                 }
             }
 
+            TvMsg::ExportTreeStats(path_buf) => {
+                if let Some(stats) = self.sel_tre_stats() {
+                    task = write_export(path_buf, stats.tsv());
+                }
+            }
+            TvMsg::ExportDataRequested(_)
+            | TvMsg::MetadataParseFailed
+            | TvMsg::ExportFailed(..) => {}
+            TvMsg::ExportData(data, path_buf) => {
+                let delimiter = match path_buf
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+                {
+                    true => b',',
+                    false => b'\t',
+                };
+                let text = match data {
+                    DataExport::Plot => {
+                        Some(self.plot_cnv.export_text(delimiter))
+                    }
+                    DataExport::NodesTable => self
+                        .sel_tre()
+                        .map(|ts| nodes_table_text(self, &ts, delimiter)),
+                    DataExport::AttributesTable => self
+                        .sel_tre()
+                        .map(|ts| attributes_table_text(self, &ts, delimiter)),
+                };
+                if let Some(text) = text {
+                    task = write_export(path_buf, text);
+                }
+            }
+            TvMsg::ExportTanglegramPdf(path_buf) => {
+                let tc = &self.tangle_cnv;
+                if tc.tre_l.is_some()
+                    && tc.tre_r.is_some()
+                    && let Err(e) = tanglegram_to_pdf(
+                        path_buf.clone(),
+                        &tc.lay_l,
+                        &tc.lay_r,
+                        self.tre_scr_w.max(SIDE_BAR_W * TWO),
+                        tc.lab_size,
+                        self.cfg.draw_labs_tip,
+                    )
+                {
+                    task = Some(Task::done(TvMsg::ExportFailed(
+                        path_buf,
+                        e.to_string(),
+                    )));
+                }
+            }
+
             TvMsg::SelectionLockChanged(state) => {
                 self.with_exclusive_config_mut(&mut |cfg| {
                     cfg.selection_lock = state;
@@ -326,7 +595,9 @@ impl TreeView {
                         node_id,
                         CladeHighlightType::Inside,
                     );
+                    tre.mark_edited();
                 });
+                self.edit_count += 1;
                 self.tre_cnv.stale_tre_rect = true;
                 self.clear_caches_cnv_ts_all();
                 self.tre_cnv.clear_caches_cnv_all();
@@ -335,7 +606,9 @@ impl TreeView {
             TvMsg::RemoveCladeHighlight(node_id) => {
                 self.with_exclusive_sel_tre_mut(&mut |tre| {
                     tre.remove_clade_highlight(node_id);
+                    tre.mark_edited();
                 });
+                self.edit_count += 1;
                 self.tre_cnv.stale_tre_rect = true;
                 self.clear_caches_cnv_ts_all();
                 self.tre_cnv.clear_caches_cnv_all();
@@ -349,7 +622,9 @@ impl TreeView {
                         node_id,
                         CladeHighlightType::Inside,
                     );
+                    tre.mark_edited();
                 });
+                self.edit_count += 1;
                 self.tre_cnv.stale_tre_rect = true;
                 self.clear_caches_cnv_ts_all();
                 self.tre_cnv.clear_caches_cnv_all();
@@ -391,6 +666,14 @@ impl TreeView {
                     );
                     self.tre_cnv_scrolled = true;
                     self.keep_scroll_position_requested = false;
+                } else if self.tre_scr_w > ZRO
+                    && let Some((x_rel, y_rel)) = self.pending_scroll_rel.take()
+                {
+                    task = self.scroll_tre_cnv(
+                        self.calc_tre_cnv_w(self.tre_scr_w) * x_rel,
+                        self.calc_tre_cnv_h(self.tre_scr_h) * y_rel,
+                    );
+                    self.tre_cnv_scrolled = true;
                 }
 
                 self.tre_cnv.vis_x_mid =
@@ -417,8 +700,28 @@ impl TreeView {
                 self.update_draw_labs_allowed();
                 self.clear_cache_cnv_ts_sel_nodes();
                 self.clear_cache_cnv_ts_filtered_nodes();
+                self.clear_cache_cnv_ts_node_glyphs();
+                self.clear_cache_cnv_ts_state_changes();
                 self.tre_cnv.clear_cache_cnv_scale_bar();
                 self.tre_cnv.clear_cache_cnv_height_axis();
+                self.tre_cnv.clear_cache_cnv_legend();
+
+                if self.msa_pane_id.is_some()
+                    && self.cfg.tre_sty == TreSty::PhyGrm
+                    && (self.tre_cnv.vis_y0 - self.msa_cnv.vis_y0).abs() > ONE
+                {
+                    let msa_task = scroll_to(
+                        self.msa_scrollable_id,
+                        AbsoluteOffset {
+                            x: self.msa_cnv.vis_x0,
+                            y: self.tre_cnv.vis_y0,
+                        },
+                    );
+                    task = Some(match task {
+                        Some(task) => Task::batch([task, msa_task]),
+                        None => msa_task,
+                    });
+                }
             }
 
             TvMsg::PlotCnvScrolledOrResized(vp) => {
@@ -444,10 +747,158 @@ impl TreeView {
                 self.nodes_table_scroll_y_offset = vp.absolute_offset().y;
             }
 
+            TvMsg::NodesTableFilterChanged(filter) => {
+                self.nodes_table_filter = filter;
+                self.populate_cache_of_edges_sorted_by_field();
+            }
+
+            TvMsg::NodesTableAttrChanged(attr) => {
+                self.nodes_table_attr = match attr == NODES_TABLE_NO_ATTR {
+                    true => None,
+                    false => Some(attr),
+                };
+                if self.nodes_table_attr.is_none()
+                    && self.nodes_table_sort_col == NodesTableField::Attribute
+                {
+                    self.nodes_table_sort_col = NodesTableField::NodeId;
+                }
+                self.populate_cache_of_edges_sorted_by_field();
+            }
+
+            TvMsg::NodesTableEditBegin(node_id, field) => {
+                if let Some(ts) = self.sel_tre()
+                    && let Some(edges) = ts.tree().edges()
+                    && let Some(edge_idx) =
+                        ts.tree().edge_index_for_node_id(node_id)
+                {
+                    let edge = &edges[edge_idx];
+                    let text = match field {
+                        NodesTableField::NodeLabel => edge
+                            .label
+                            .as_deref()
+                            .unwrap_or_default()
+                            .to_string(),
+                        _ => edge.branch_length.to_string(),
+                    };
+                    self.nodes_table_edit = Some(NodesTableEdit {
+                        tre_id: ts.id(),
+                        node_id,
+                        field,
+                        text,
+                    });
+                    task = Some(focus(self.nodes_table_edit_input_id));
+                }
+            }
+
+            TvMsg::NodesTableEditChanged(text) => {
+                if let Some(edit) = &mut self.nodes_table_edit {
+                    edit.text = text;
+                }
+            }
+
+            TvMsg::NodesTableEditSubmitted => {
+                if let Some(edit) = self.nodes_table_edit.take()
+                    && self.sel_tre().is_some_and(|ts| ts.id() == edit.tre_id)
+                    && let Some(node_edit) = edit.node_edit()
+                {
+                    self.edit_node(edit.node_id, &node_edit);
+                }
+            }
+
+            TvMsg::Undo => {
+                self.undo();
+            }
+
             TvMsg::AttributesTableScrolledOrResized(vp) => {
                 self.attributes_table_scroll_y_offset = vp.absolute_offset().y;
             }
 
+            TvMsg::AttributesTableEditBegin(node_id, selector, key, field) => {
+                if let Some(ts) = self.sel_tre() {
+                    let attrs = match selector {
+                        AttributeSelector::Node => {
+                            ts.tree().node_attributes(node_id)
+                        }
+                        AttributeSelector::Branch => {
+                            ts.tree().branch_attributes(node_id)
+                        }
+                    };
+                    let text = match field {
+                        AttributesTableField::Name => key.clone(),
+                        _ => attrs
+                            .get(&key)
+                            .map(attribute_text)
+                            .unwrap_or_default(),
+                    };
+                    self.attributes_table_edit = Some(AttributesTableEdit {
+                        tre_id: ts.id(),
+                        node_id,
+                        selector,
+                        key,
+                        field,
+                        text,
+                    });
+                    task = Some(focus(self.attributes_table_edit_input_id));
+                }
+            }
+
+            TvMsg::AttributesTableEditChanged(text) => {
+                if let Some(edit) = &mut self.attributes_table_edit {
+                    edit.text = text;
+                }
+            }
+
+            TvMsg::AttributesTableEditSubmitted => {
+                if let Some(edit) = self.attributes_table_edit.take()
+                    && self.sel_tre().is_some_and(|ts| ts.id() == edit.tre_id)
+                    && let Some(attribute_edit) = edit.attribute_edit()
+                {
+                    self.edit_attributes(&attribute_edit);
+                }
+            }
+
+            TvMsg::NewAttributeKeyChanged(key) => {
+                self.new_attribute_key = key;
+            }
+
+            TvMsg::NewAttributeValueChanged(value) => {
+                self.new_attribute_value = value;
+            }
+
+            TvMsg::AddAttributeToSelected => {
+                let key = self.new_attribute_key.trim().to_string();
+                if is_valid_attribute_key(&key)
+                    && let Some(ts) = self.sel_tre()
+                    && !ts.sel_node_ids().is_empty()
+                {
+                    let edit = AttributeEdit::Set {
+                        node_ids: ts.sel_node_ids().iter().copied().collect(),
+                        selector: AttributeSelector::Node,
+                        key,
+                        value: nwk_attribute_from_text(
+                            &self.new_attribute_value,
+                        ),
+                    };
+                    drop(ts);
+                    self.edit_attributes(&edit);
+                }
+            }
+
+            TvMsg::RemoveAttributeFromSelected => {
+                let key = self.new_attribute_key.trim().to_string();
+                if is_valid_attribute_key(&key)
+                    && let Some(ts) = self.sel_tre()
+                    && !ts.sel_node_ids().is_empty()
+                {
+                    let edit = AttributeEdit::Remove {
+                        node_ids: ts.sel_node_ids().iter().copied().collect(),
+                        key,
+                    };
+                    drop(ts);
+                    self.edit_attributes(&edit);
+                }
+            }
+
             TvMsg::TogglePlot(state) => {
                 self.with_exclusive_config_mut(&mut |cfg| {
                     cfg.show_plot = state;
@@ -467,6 +918,29 @@ impl TreeView {
                 });
             }
 
+            TvMsg::PlotTypeChanged(plot_type) => {
+                self.plot_cnv.plot_type = plot_type;
+                self.set_plot_data();
+            }
+
+            TvMsg::PlotAttrChanged(key) => {
+                self.plot_attr = Some(key);
+                self.set_hist_plot_data();
+            }
+
+            TvMsg::RttDateSourceChanged(source) => {
+                self.rtt_date_col =
+                    self.metadata.as_ref().and_then(|metadata| {
+                        metadata.columns().iter().position(|c| *c == source)
+                    });
+                self.set_rtt_plot_data();
+            }
+
+            TvMsg::RttDatePatternChanged(pattern) => {
+                self.rtt_date_pattern = pattern;
+                self.set_rtt_plot_data();
+            }
+
             TvMsg::ToggleGts(state) => {
                 self.with_exclusive_config_mut(&mut |cfg| {
                     cfg.draw_gts = state;
@@ -474,19 +948,161 @@ impl TreeView {
             }
 
             TvMsg::TreUnitChanged(unit) => {
-                self.with_exclusive_config_mut(&mut |cfg| {
-                    cfg.tre_unit = unit;
+                self.set_tre_unit(unit);
+            }
+
+            TvMsg::BrlenTransformChanged(transform) => {
+                self.brlen_transform = transform;
+                self.brlen_transform_param = transform
+                    .default_param()
+                    .map(|param| param.to_string())
+                    .unwrap_or_default();
+            }
+
+            TvMsg::BrlenTransformParamChanged(param) => {
+                self.brlen_transform_param = param;
+            }
+
+            TvMsg::TransformBrlens => {
+                self.transform_brlens(
+                    self.brlen_transform,
+                    self.brlen_transform_param(),
+                );
+                task = self.scroll_to_current_found_edge();
+            }
+
+            TvMsg::MetadataLoaded(text) => {
+                if !self.load_metadata(&text) {
+                    task = Some(Task::done(TvMsg::MetadataParseFailed));
+                }
+            }
+
+            TvMsg::TrackTypeChanged(col, track_type) => {
+                self.set_track_type(col, track_type);
+            }
+
+            TvMsg::ClearMetadata => {
+                self.metadata = None;
+                self.track_types.clear();
+                self.set_parsimony_column(None);
+                self.rtt_date_col = None;
+                self.set_rtt_plot_data();
+                self.update_tracks();
+            }
+
+            TvMsg::ParsimonyColumnChanged(column) => {
+                let col = self.metadata.as_ref().and_then(|metadata| {
+                    metadata.columns().iter().position(|c| *c == column)
                 });
+                self.set_parsimony_column(col);
+            }
 
-                let x_axis_is_reversed = self.cfg.x_axis_is_reversed();
-                self.plot_cnv.x_axis_is_reversed = x_axis_is_reversed;
-                self.tre_cnv.height_axis_is_reversed = x_axis_is_reversed;
+            TvMsg::ParsimonyMethodChanged(method) => {
+                self.parsimony_method = method;
+            }
 
-                self.show_hide_plot();
+            TvMsg::ParsimonyCostsChanged(costs) => {
+                self.parsimony_costs = costs;
+            }
 
-                self.tre_cnv.clear_cache_cnv_scale_bar();
-                self.tre_cnv.clear_cache_cnv_height_axis();
-                self.plot_cnv.clear_caches_cnv_all();
+            TvMsg::ReconstructStates => {
+                self.reconstruct_states();
+            }
+
+            TvMsg::StateChangesVisChanged(state) => {
+                let attr = match state {
+                    true => self.parsimony.as_ref().map(|p| p.attr.clone()),
+                    false => None,
+                };
+                self.set_state_change_attr(attr);
+            }
+
+            TvMsg::PieAttrToggled(key, state) => {
+                let mut glyphs = self.tre_cnv.node_glyphs.clone();
+                glyphs.pie_attrs.retain(|k| *k != key);
+                if state {
+                    glyphs.pie_attrs.push(key);
+                }
+                self.set_node_glyphs(glyphs);
+            }
+
+            TvMsg::SymbolAttrChanged(key) => {
+                let mut glyphs = self.tre_cnv.node_glyphs.clone();
+                glyphs.symbol_attr = key;
+                self.set_node_glyphs(glyphs);
+            }
+
+            TvMsg::SymbolShapeChanged(shape) => {
+                let mut glyphs = self.tre_cnv.node_glyphs.clone();
+                glyphs.symbol_shape = shape;
+                self.set_node_glyphs(glyphs);
+            }
+
+            TvMsg::SymbolEncodingChanged(encoding) => {
+                let mut glyphs = self.tre_cnv.node_glyphs.clone();
+                glyphs.symbol_encoding = encoding;
+                self.set_node_glyphs(glyphs);
+            }
+
+            TvMsg::ClearNodeGlyphs => {
+                self.set_node_glyphs(NodeGlyphs::default());
+            }
+
+            TvMsg::PlacementCirclesVisChanged(state) => {
+                let mut view = self.tre_cnv.placements_view.clone();
+                view.draw_circles = state;
+                self.set_placements_view(view);
+            }
+
+            TvMsg::PlacementSizeChanged(size) => {
+                let mut view = self.tre_cnv.placements_view.clone();
+                view.size = size;
+                self.set_placements_view(view);
+            }
+
+            TvMsg::PlacementNameFilterChanged(name_filter) => {
+                let mut view = self.tre_cnv.placements_view.clone();
+                view.name_filter = name_filter;
+                self.set_placements_view(view);
+            }
+
+            TvMsg::PlacementDensityVisChanged(state) => {
+                let mut view = self.tre_cnv.placements_view.clone();
+                view.draw_density = state;
+                self.set_placements_view(view);
+            }
+
+            TvMsg::AlignmentLoaded(text) => {
+                _ = self.load_alignment(&text);
+            }
+
+            TvMsg::ClearAlignment => {
+                self.msa_cnv.set_alignment(None);
+                self.show_hide_msa();
+            }
+
+            TvMsg::MsaColWidthChanged(idx) => {
+                self.msa_col_w_idx =
+                    idx.clamp(MSA_COL_W_IDX_MIN, MSA_COL_W_IDX_MAX);
+            }
+
+            TvMsg::MsaCnvScrolledOrResized(vp) => {
+                self.msa_cnv.vis_x0 = vp.absolute_offset().x;
+                self.msa_cnv.vis_y0 = vp.absolute_offset().y;
+                self.msa_cnv.vis_w = vp.bounds().width;
+                self.msa_cnv.vis_h = vp.bounds().height;
+                self.msa_cnv.clear_caches_cnv_all();
+                if self.cfg.tre_sty == TreSty::PhyGrm
+                    && (self.msa_cnv.vis_y0 - self.tre_cnv.vis_y0).abs() > ONE
+                {
+                    task = Some(scroll_to(
+                        self.tre_scrollable_id,
+                        AbsoluteOffset {
+                            x: self.tre_cnv.vis_x0,
+                            y: self.msa_cnv.vis_y0,
+                        },
+                    ));
+                }
             }
 
             TvMsg::NodesTableSortColumnChanged(sort_col) => {
@@ -524,22 +1140,103 @@ impl TreeView {
                 self.show_hide_data_table();
             }
 
+            TvMsg::ToggleTanglegram => {
+                self.with_exclusive_config_mut(&mut |cfg| {
+                    cfg.show_tanglegram = !cfg.show_tanglegram;
+                });
+                self.show_hide_tanglegram();
+            }
+
+            TvMsg::TanglegramTreeChanged(tre_id) => {
+                self.tangle_tre_idx =
+                    self.tre_states.iter().position(|ts| ts.id() == tre_id);
+                self.update_tangle_cnv();
+            }
+
+            TvMsg::ToggleTreeSet => {
+                self.with_exclusive_config_mut(&mut |cfg| {
+                    cfg.show_tree_set = !cfg.show_tree_set;
+                });
+                self.show_hide_tree_set();
+            }
+
+            TvMsg::ToggleTreeStats => {
+                self.with_exclusive_config_mut(&mut |cfg| {
+                    cfg.show_tree_stats = !cfg.show_tree_stats;
+                });
+                self.show_hide_tree_stats();
+            }
+
+            TvMsg::CopyTreeStats => {
+                if let Some(stats) = &self.tree_stats {
+                    task = Some(clipboard::write(stats.tsv()));
+                }
+            }
+
+            TvMsg::AddConsensusTree(consensus_type) => {
+                if let Some(newick) =
+                    self.tree_set.consensus_newick(consensus_type)
+                    && let Ok(trees) = parse_trees(newick)
+                    && let Some(tre) = trees.into_iter().next()
+                {
+                    self.add_derived_tree(PreparedTree::new(tre));
+                }
+            }
+
+            TvMsg::DerivedTreeAdded(tre) => {
+                self.add_derived_tree(*tre);
+            }
+
+            TvMsg::AnnotateCladeSupport => {
+                self.annotate_clade_support();
+            }
+
             TvMsg::PrevTre => {
                 _ = self.prev_tre();
                 self.sort();
-                self.set_ltt_plot_data();
+                self.set_plot_data();
                 self.update_draw_labs_allowed();
                 self.tre_cnv.stale_tre_rect = true;
                 self.populate_cache_of_edges_sorted_by_field();
+                self.update_tree_set_dists();
+            }
+
+            TvMsg::ViewportRestored(viewport) => {
+                let idx = viewport
+                    .tre_idx
+                    .min(self.tre_states.len().saturating_sub(1));
+                if self.are_any_trees_loaded()
+                    && self.update_sel_tre_st_idx(Some(idx))
+                {
+                    self.sort();
+                    self.set_plot_data();
+                    self.populate_cache_of_edges_sorted_by_field();
+                    self.update_tree_set_dists();
+                }
+                let size_idx = |idx: u16| {
+                    idx.clamp(TRE_CNV_SIZE_IDX_MIN, TRE_CNV_SIZE_IDX_MAX)
+                };
+                self.tre_cnv_w_idx = size_idx(viewport.cnv_w_idx);
+                self.tre_cnv_h_idx = size_idx(viewport.cnv_h_idx);
+                self.tre_cnv_z_idx = size_idx(viewport.cnv_z_idx);
+                self.pending_scroll_rel =
+                    Some((viewport.x_mid_rel, viewport.y_mid_rel));
+                self.update_tree_rect_padding();
+                self.update_draw_labs_allowed();
+                self.tre_cnv.stale_tre_rect = true;
+                self.clear_cache_cnv_ts_edge();
+                self.tre_cnv.clear_cache_cnv_scale_bar();
+                self.tre_cnv.clear_cache_cnv_height_axis();
             }
 
             TvMsg::NextTre => {
                 _ = self.next_tre();
                 self.sort();
-                self.set_ltt_plot_data();
+                self.set_plot_data();
                 self.update_draw_labs_allowed();
                 self.tre_cnv.stale_tre_rect = true;
                 self.populate_cache_of_edges_sorted_by_field();
+                self.update_tree_set_dists();
             }
 
             TvMsg::TreNodeOrdOptChanged(node_ord_opt) => {
@@ -559,7 +1256,9 @@ impl TreeView {
                 self.with_exclusive_sel_tre_mut(&mut |tre| {
                     yanked_node = tre.unroot();
                 });
-                self.set_ltt_plot_data();
+                self.edit_count += 1;
+                self.update_tree_set();
+                self.set_plot_data();
                 self.update_draw_labs_allowed();
                 task = self.scroll_to_current_found_edge();
                 self.tre_cnv.clear_cache_cnv_scale_bar();
@@ -573,7 +1272,9 @@ impl TreeView {
                 self.with_exclusive_sel_tre_mut(&mut |tre| {
                     node_id_new_root = tre.root(node_id);
                 });
-                self.set_ltt_plot_data();
+                self.edit_count += 1;
+                self.update_tree_set();
+                self.set_plot_data();
                 self.update_draw_labs_allowed();
                 task = self.scroll_to_current_found_edge();
                 self.tre_cnv.clear_cache_cnv_scale_bar();
@@ -582,47 +1283,57 @@ impl TreeView {
                 self.populate_cache_of_edges_sorted_by_field();
             }
 
-            TvMsg::TreesLoaded(trees) => {
-                self.tre_cnv.drawing_enabled = false;
-                self.tre_states = Vec::new();
+            TvMsg::RootAll(rooting_method) => {
+                task = self.root_all_in_background(rooting_method);
+            }
 
-                for (i, tre) in (1..).zip(trees) {
-                    let mut ts = TreeState::new(i);
-                    ts.init(tre);
-                    self.tre_states.push(Rc::new(ts));
+            TvMsg::TreesRooted(trees) => {
+                if self.root_all_pending.take() == Some(self.edit_count) {
+                    self.rooted_trees_replaced(trees);
+                    task = self.scroll_to_current_found_edge();
                 }
+            }
 
-                if !self.tre_states.is_empty() {
-                    self.tre_state_idx = Some(0);
-                } else {
-                    self.tre_state_idx = None;
-                }
+            TvMsg::OutgroupChanged(s) => {
+                self.outgroup_string = s;
+            }
 
-                if let Some(_tre_pane_id) = &self.tre_pane_id {
-                } else {
-                    let (pane_grid, tre_pane_id) =
-                        PgState::new(TreeViewPane::Tree);
-                    self.pane_grid = Some(pane_grid);
-                    self.tre_pane_id = Some(tre_pane_id);
+            TvMsg::RootAllOnOutgroup => {
+                let labels: Vec<String> = self
+                    .outgroup_string
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|label| !label.is_empty())
+                    .map(String::from)
+                    .collect();
+                if !labels.is_empty() {
+                    task = self.root_all_in_background(
+                        RootingMethod::Outgroup(labels),
+                    );
                 }
-                self.sort();
-                self.set_ltt_plot_data();
-                self.populate_cache_of_edges_sorted_by_field();
-                self.show_hide_plot();
+            }
 
-                if self.is_new {
-                    self.update_root_len_frac();
-                    self.show_hide_data_table();
-                    self.update_tree_rect_padding();
-                    self.is_new = false;
-                }
+            TvMsg::TreesLoaded(trees) => {
+                self.load_trees(
+                    trees.into_iter().map(PreparedTree::new).collect(),
+                );
+            }
 
-                self.update_draw_labs_allowed();
-                self.tre_cnv.drawing_enabled = true;
-                self.tre_cnv.stale_tre_rect = true;
+            TvMsg::PreparedTreesLoaded(trees) => {
+                self.load_trees(trees);
+            }
 
-                self.clear_caches_cnv_ts_all();
-                self.tre_cnv.clear_caches_cnv_all();
+            TvMsg::PreparedTreesAppended(trees) => {
+                self.append_trees(trees);
+            }
+
+            TvMsg::PreparedTreesAppendFinished => {
+                self.set_ltt_set();
+                self.update_tree_set();
+            }
+
+            TvMsg::PreparedTreesReloaded(trees) => {
+                self.reload_trees(trees);
             }
 
             TvMsg::PlotXAxisScaleTypeChanged(axis_scale_type) => {
@@ -791,6 +1502,7 @@ impl TreeView {
                 });
                 self.tre_cnv.lab_size_tip =
                     self.tre_cnv.lab_size_min * idx as Float;
+                self.tangle_cnv.lab_size = self.tre_cnv.lab_size_tip;
                 // -------------------------------------------------------------
                 if self.text_w_tip.font_size() != self.tre_cnv.lab_size_tip {
                     self.text_w_tip.set_font_size(self.tre_cnv.lab_size_tip);
@@ -1059,6 +1771,14 @@ impl TreeView {
             }
         }
 
+        self.update_msa_geom();
+        self.update_tree_stats();
+        if self.plot_cnv.plot_type == PlotType::RootToTip
+            && let Some(ts) = self.sel_tre()
+        {
+            self.plot_cnv.set_rtt_sel_node_ids(ts.sel_node_ids());
+        }
+
         match task {
             Some(task) => task,
             None => Task::none(),
@@ -1068,11 +1788,135 @@ impl TreeView {
     fn populate_cache_of_edges_sorted_by_field(&mut self) {
         let sort_col = self.nodes_table_sort_col;
         let sort_dir = self.nodes_table_sort_ord;
+        let filter = self.nodes_table_filter.clone();
+        let attr = self.nodes_table_attr.clone();
         self.with_exclusive_sel_tre_mut(&mut |tre| {
-            tre.populate_cache_of_edges_for_nodes_table(sort_col, sort_dir);
+            tre.populate_cache_of_edges_for_nodes_table(
+                sort_col,
+                sort_dir,
+                &filter,
+                attr.as_deref(),
+            );
         });
     }
 
+    fn set_plot_data(&mut self) {
+        self.set_ltt_plot_data();
+        self.set_ltt_set();
+        self.set_hist_plot_data();
+        self.set_rtt_plot_data();
+    }
+
+    /// LTT curves of all loaded trees, other than consensus trees, over the
+    /// time span of the LTT plot of the selected tree.
+    fn set_ltt_set(&mut self) {
+        if self.plot_cnv.plot_type != PlotType::LttAll {
+            return;
+        }
+        let spans: Vec<Vec<(Float, Float)>> = self
+            .tre_states
+            .iter()
+            .filter(|ts| !self.derived_tre_ids.contains(&ts.id()))
+            .filter_map(|ts| edge_spans(ts.tree().edges()?))
+            .map(|spans| spans.into_iter().flatten().collect())
+            .collect();
+        let ltt_set = LttSet::new(
+            &spans, self.tre_cnv.height_axis_min, self.tre_cnv.height_axis_max,
+            LTT_SET_POINT_COUNT,
+        );
+        self.plot_cnv.set_ltt_set(ltt_set);
+    }
+
+    /// Histogram of the branch lengths, node depths or values of the chosen
+    /// numeric attribute in the tree (or subtree) in view.
+    fn set_hist_plot_data(&mut self) {
+        let plot_type = self.plot_cnv.plot_type;
+        if !plot_type.is_histogram() {
+            return;
+        }
+        let mut values: Vec<Float> = Vec::new();
+        if let Some(ts) = self.sel_tre()
+            && let Some(edges) = ts.edges()
+        {
+            match plot_type {
+                PlotType::AttrHistogram => {
+                    if let Some(key) = &self.plot_attr {
+                        values = edges
+                            .iter()
+                            .filter_map(|e| attr_num(ts.tree(), e.node_id, key))
+                            .collect();
+                    }
+                }
+                _ => {
+                    let spans = edge_spans(edges).unwrap_or_default();
+                    values = match plot_type {
+                        PlotType::BrlenHistogram => spans
+                            .iter()
+                            .flatten()
+                            .map(|(start, end)| end - start)
+                            .collect(),
+                        _ => {
+                            let heights = edges.iter().zip(&spans).filter_map(
+                                |(edge, span)| match edge.is_tip {
+                                    true => None,
+                                    false => Some(span.map_or(ZRO, |s| s.1)),
+                                },
+                            );
+                            match self.cfg.x_axis_is_reversed() {
+                                true => {
+                                    let height = spans
+                                        .iter()
+                                        .flatten()
+                                        .map(|(_, end)| *end)
+                                        .fold(ZRO, Float::max);
+                                    heights.map(|h| height - h).collect()
+                                }
+                                false => heights.collect(),
+                            }
+                        }
+                    };
+                }
+            }
+        }
+        values.retain(|v| v.is_finite());
+        self.plot_cnv.set_hist_plot_data(plot_data_from_histogram(&values));
+    }
+
+    /// Root-to-tip distances of the dated tips of the whole tree. Dates come
+    /// from the chosen metadata column, or from the tip labels.
+    fn set_rtt_plot_data(&mut self) {
+        let rtt = match (self.plot_cnv.plot_type, self.sel_tre()) {
+            (PlotType::RootToTip, Some(ts)) => {
+                let mut node_ids: Vec<NodeId> = Vec::new();
+                let mut labels: Vec<String> = Vec::new();
+                let mut dates: Vec<Float> = Vec::new();
+                let mut dists: Vec<Float> = Vec::new();
+                for edge in ts.edges_tip_tree() {
+                    let Some(label) = edge.label.as_deref() else {
+                        continue;
+                    };
+                    let date = match (&self.metadata, self.rtt_date_col) {
+                        (Some(metadata), Some(col)) => {
+                            metadata.value(label, col).and_then(decimal_year)
+                        }
+                        _ => date_from_label(label, &self.rtt_date_pattern),
+                    };
+                    if let Some(date) = date {
+                        node_ids.push(edge.node_id);
+                        labels.push(label.to_string());
+                        dates.push(date);
+                        dists.push(
+                            ts.tree().first_node_to_node_distance(edge.node_id),
+                        );
+                    }
+                }
+                RootToTip::new(node_ids, labels, dates, dists)
+            }
+            _ => None,
+        };
+        self.plot_cnv.set_rtt(rtt);
+    }
+
     fn set_ltt_plot_data(&mut self) {
         if let Some(ts) = self.sel_tre()
             && let Some(edges) = ts.edges()
@@ -1130,6 +1974,20 @@ impl TreeView {
         }
     }
 
+    pub(super) fn sel_tre_node_attr_keys(&self) -> &[String] {
+        match self.tre_state_idx.and_then(|idx| self.tre_states.get(idx)) {
+            Some(ts) => ts.node_attr_keys(),
+            None => &[],
+        }
+    }
+
+    pub(super) fn sel_tre_num_node_attr_keys(&self) -> &[String] {
+        match self.tre_state_idx.and_then(|idx| self.tre_states.get(idx)) {
+            Some(ts) => ts.num_node_attr_keys(),
+            None => &[],
+        }
+    }
+
     pub(crate) fn sel_tre(&self) -> Option<Rc<TreeState>> {
         if let Some(sel_tre_state_idx) = self.tre_state_idx {
             let sel_tre_state = &self.tre_states[sel_tre_state_idx];
@@ -1159,6 +2017,7 @@ impl TreeView {
         let detached_cfg = Rc::new(self.cfg.as_ref().clone());
         self.tre_cnv.cfg = detached_cfg.clone();
         self.plot_cnv.cfg = detached_cfg.clone();
+        self.tangle_cnv.cfg = detached_cfg.clone();
 
         if let Some(cfg) = self.config_mut() {
             f(cfg);
@@ -1166,6 +2025,166 @@ impl TreeView {
 
         self.tre_cnv.cfg = self.cfg.clone();
         self.plot_cnv.cfg = self.cfg.clone();
+        self.tangle_cnv.cfg = self.cfg.clone();
+    }
+
+    fn load_trees(&mut self, trees: Vec<PreparedTree>) {
+        self.tre_cnv.drawing_enabled = false;
+        self.tre_states = Vec::new();
+
+        for (i, tre) in (1..).zip(trees) {
+            let mut ts = TreeState::new(i);
+            ts.init_prepared(tre);
+            ts.highlight_colored_clades();
+            self.tre_states.push(Rc::new(ts));
+        }
+
+        if !self.tre_states.is_empty() {
+            self.tre_state_idx = Some(0);
+        } else {
+            self.tre_state_idx = None;
+        }
+
+        self.tangle_tre_idx = None;
+        self.derived_tre_ids.clear();
+        self.saved_revisions.clear();
+        self.root_all_pending = None;
+
+        if let Some(_tre_pane_id) = &self.tre_pane_id {
+        } else {
+            let (pane_grid, tre_pane_id) = PgState::new(TreeViewPane::Tree);
+            self.pane_grid = Some(pane_grid);
+            self.tre_pane_id = Some(tre_pane_id);
+        }
+        self.sort();
+        self.set_plot_data();
+        self.populate_cache_of_edges_sorted_by_field();
+        self.show_hide_plot();
+        self.show_hide_tanglegram();
+        self.show_hide_tree_set();
+        self.show_hide_tree_stats();
+        self.show_hide_msa();
+
+        if self.is_new {
+            self.update_root_len_frac();
+            self.show_hide_data_table();
+            self.update_tree_rect_padding();
+            self.is_new = false;
+        }
+
+        self.update_draw_labs_allowed();
+        self.tre_cnv.drawing_enabled = true;
+        self.tre_cnv.stale_tre_rect = true;
+
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+    }
+
+    /// Adds trees read after those passed to [`TreeView::load_trees`] without
+    /// changing the tree in view. The LTT curves and the tree set, which
+    /// cover all the trees, are recomputed only once the last batch is in, on
+    /// [`TvMsg::PreparedTreesAppendFinished`].
+    fn append_trees(&mut self, trees: Vec<PreparedTree>) {
+        if self.tre_states.is_empty() {
+            return self.load_trees(trees);
+        }
+        let id_last =
+            self.tre_states.iter().map(|ts| ts.id()).max().unwrap_or_default();
+        for (id, tre) in (id_last + 1..).zip(trees) {
+            let mut ts = TreeState::new(id);
+            ts.init_prepared(tre);
+            ts.highlight_colored_clades();
+            self.tre_states.push(Rc::new(ts));
+        }
+        self.show_hide_tanglegram();
+    }
+
+    /// Replaces the trees with new versions of them read from the same file.
+    /// Trees are matched by their position in the file. The tree in view and
+    /// the scroll position are kept, and so are the selection and clade
+    /// highlights of the clades found in the new versions.
+    fn reload_trees(&mut self, trees: Vec<PreparedTree>) {
+        if self.tre_states.is_empty() {
+            return self.load_trees(trees);
+        }
+
+        self.tre_cnv.drawing_enabled = false;
+        self.tre_cnv.tree_state = None;
+        self.tangle_cnv.release_trees();
+        self.msa_cnv.release_tree();
+
+        self.root_all_pending = None;
+        let sel_id = self.sel_tre().map(|ts| ts.id());
+        let mut id_next =
+            self.tre_states.iter().map(|ts| ts.id()).max().unwrap_or_default()
+                + 1;
+        let (derived, loaded): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.tre_states)
+                .into_iter()
+                .partition(|ts| self.derived_tre_ids.contains(&ts.id()));
+
+        let mut loaded = loaded.into_iter().map(Rc::try_unwrap);
+        for tre in trees {
+            let ts = match loaded.next() {
+                Some(Ok(mut ts)) => {
+                    ts.reload(tre);
+                    ts
+                }
+                _ => {
+                    let mut ts = TreeState::new(id_next);
+                    id_next += 1;
+                    ts.init_prepared(tre);
+                    ts.highlight_colored_clades();
+                    ts
+                }
+            };
+            self.tre_states.push(Rc::new(ts));
+        }
+        self.tre_states.extend(derived);
+
+        self.tre_state_idx = sel_id
+            .and_then(|id| self.tre_states.iter().position(|ts| ts.id() == id))
+            .or(Some(0));
+        self.tangle_tre_idx =
+            self.tangle_tre_idx.filter(|&idx| idx < self.tre_states.len());
+
+        self.sort();
+        self.set_plot_data();
+        self.populate_cache_of_edges_sorted_by_field();
+        self.show_hide_tanglegram();
+        self.update_tree_set();
+        self.update_draw_labs_allowed();
+
+        self.tre_cnv.drawing_enabled = true;
+        self.tre_cnv.stale_tre_rect = true;
+        self.keep_scroll_position_requested = true;
+        self.reloaded_at = Some(Instant::now());
+
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+    }
+
+    /// Adds a tree built from the loaded ones, or from other data, and shows
+    /// it. Derived trees are not part of the tree set.
+    fn add_derived_tree(&mut self, tre: PreparedTree) {
+        if self.tre_states.is_empty() {
+            return self.load_trees(vec![tre]);
+        }
+        let id =
+            self.tre_states.iter().map(|ts| ts.id()).max().unwrap_or_default()
+                + 1;
+        let mut ts = TreeState::new(id);
+        ts.init_prepared(tre);
+        self.tre_states.push(Rc::new(ts));
+        _ = self.derived_tre_ids.insert(id);
+        _ = self.update_sel_tre_st_idx(Some(self.tre_states.len() - 1));
+        self.sort();
+        self.set_plot_data();
+        self.update_draw_labs_allowed();
+        self.tre_cnv.stale_tre_rect = true;
+        self.populate_cache_of_edges_sorted_by_field();
+        self.show_hide_plot();
+        self.update_tree_set();
     }
 
     fn sort(&mut self) {
@@ -1453,13 +2472,14 @@ impl TreeView {
         let cnv_vs = RectVals::wh(cnv_w, cnv_h);
         let mut root_len = ZRO;
         if let Some(sel_tre) = self.sel_tre() {
-            (self.tre_cnv.tre_vs, root_len) = self.tre_cnv.calc_tre_vs(
-                &cnv_vs,
-                sel_tre.edges_tip_tallest(),
-                self.is_rooted(),
-                self.tree_has_clade_highlights(),
-                &mut self.text_w_tip,
-            );
+            (self.tre_cnv.tre_vs, root_len, self.tre_cnv.tracks_offset) =
+                self.tre_cnv.calc_tre_vs(
+                    &cnv_vs,
+                    sel_tre.edges_tip_tallest(),
+                    self.is_rooted(),
+                    self.tree_has_clade_highlights(),
+                    &mut self.text_w_tip,
+                );
         }
         root_len
     }
@@ -1502,6 +2522,18 @@ impl TreeView {
         }
     }
 
+    pub(super) fn clear_cache_cnv_ts_node_glyphs(&self) {
+        if let Some(ts) = self.sel_tre() {
+            ts.clear_cache_cnv_node_glyphs();
+        }
+    }
+
+    pub(super) fn clear_cache_cnv_ts_state_changes(&self) {
+        if let Some(ts) = self.sel_tre() {
+            ts.clear_cache_cnv_state_changes();
+        }
+    }
+
     pub(super) fn clear_cache_cnv_ts_edge(&self) {
         if let Some(ts) = self.sel_tre() {
             ts.clear_cache_cnv_edge();
@@ -1602,6 +2634,196 @@ impl TreeView {
         }
     }
 
+    fn show_hide_tanglegram(&mut self) {
+        let can_show = self.tre_states.len() > 1;
+        if let Some(pane_grid) = &mut self.pane_grid {
+            if let Some(tangle_pane_id) = self.tangle_pane_id {
+                if !self.cfg.show_tanglegram || !can_show {
+                    _ = pane_grid.close(tangle_pane_id);
+                    self.tangle_pane_id = None;
+                }
+            } else if self.cfg.show_tanglegram
+                && can_show
+                && let Some(tre_pane_id) = self.tre_pane_id
+                && let Some((tangle_pane_id, split)) = pane_grid.split(
+                    PgAxis::Horizontal,
+                    tre_pane_id,
+                    TreeViewPane::Tanglegram,
+                )
+            {
+                pane_grid.resize(split, ONE / TWO);
+                self.tangle_pane_id = Some(tangle_pane_id);
+            }
+        }
+        self.update_tangle_cnv();
+    }
+
+    fn tangle_partner_idx(&self) -> Option<usize> {
+        let sel_idx = self.tre_state_idx?;
+        let count = self.tre_states.len();
+        if count < 2 {
+            return None;
+        }
+        match self.tangle_tre_idx {
+            Some(idx) if idx != sel_idx && idx < count => Some(idx),
+            _ => Some((sel_idx + 1) % count),
+        }
+    }
+
+    pub(super) fn tangle_partner_id(&self) -> Option<usize> {
+        self.tangle_partner_idx().map(|idx| self.tre_states[idx].id())
+    }
+
+    fn update_tangle_cnv(&mut self) {
+        if self.tangle_pane_id.is_some()
+            && let Some(partner_idx) = self.tangle_partner_idx()
+        {
+            let sel_tre = self.sel_tre();
+            let sel_tre_id = sel_tre.as_ref().map(|ts| ts.id());
+            self.tangle_tre_opts = self
+                .tre_states
+                .iter()
+                .map(|ts| ts.id())
+                .filter(|&id| Some(id) != sel_tre_id)
+                .collect();
+            let partner = self.tre_states[partner_idx].clone();
+            self.tangle_cnv.set_trees(sel_tre, Some(partner));
+        } else {
+            self.tangle_cnv.set_trees(None, None);
+        }
+    }
+
+    fn show_hide_tree_set(&mut self) {
+        let pane_id_to_split_opt = self.pane_id_to_split();
+        if let Some(pane_grid) = &mut self.pane_grid {
+            if let Some(tree_set_pane_id) = self.tree_set_pane_id {
+                if !self.cfg.show_tree_set {
+                    _ = pane_grid.close(tree_set_pane_id);
+                    self.tree_set_pane_id = None;
+                }
+            } else if self.cfg.show_tree_set
+                && let Some(pane_id_to_split) = pane_id_to_split_opt
+                && let Some((tree_set_pane_id, split)) = pane_grid.split(
+                    PgAxis::Horizontal,
+                    pane_id_to_split,
+                    TreeViewPane::TreeSet,
+                )
+            {
+                pane_grid.resize(split, ONE);
+                self.tree_set_pane_id = Some(tree_set_pane_id);
+            }
+        }
+        self.update_tree_set();
+    }
+
+    fn show_hide_tree_stats(&mut self) {
+        let pane_id_to_split_opt = self.pane_id_to_split();
+        if let Some(pane_grid) = &mut self.pane_grid {
+            if let Some(tree_stats_pane_id) = self.tree_stats_pane_id {
+                if !self.cfg.show_tree_stats {
+                    _ = pane_grid.close(tree_stats_pane_id);
+                    self.tree_stats_pane_id = None;
+                }
+            } else if self.cfg.show_tree_stats
+                && let Some(pane_id_to_split) = pane_id_to_split_opt
+                && let Some((tree_stats_pane_id, split)) = pane_grid.split(
+                    PgAxis::Horizontal,
+                    pane_id_to_split,
+                    TreeViewPane::Statistics,
+                )
+            {
+                pane_grid.resize(split, ONE);
+                self.tree_stats_pane_id = Some(tree_stats_pane_id);
+            }
+        }
+        self.update_tree_stats();
+    }
+
+    /// Statistics of the tree (or subtree) in view.
+    fn sel_tre_stats(&self) -> Option<TreeStats> {
+        let ts = self.sel_tre()?;
+        TreeStats::new(ts.edges()?, ts.has_brlen(), ts.sel_node_ids())
+    }
+
+    /// Recomputes the statistics while the statistics pane is open.
+    fn update_tree_stats(&mut self) {
+        self.tree_stats = match self.tree_stats_pane_id {
+            Some(_) => self.sel_tre_stats(),
+            None => None,
+        };
+    }
+
+    fn show_hide_msa(&mut self) {
+        let can_show = self.msa_cnv.alignment.is_some();
+        if let Some(pane_grid) = &mut self.pane_grid {
+            if let Some(msa_pane_id) = self.msa_pane_id {
+                if !can_show {
+                    _ = pane_grid.close(msa_pane_id);
+                    self.msa_pane_id = None;
+                }
+            } else if can_show
+                && let Some(tre_pane_id) = self.tre_pane_id
+                && let Some((msa_pane_id, split)) = pane_grid.split(
+                    PgAxis::Vertical,
+                    tre_pane_id,
+                    TreeViewPane::Alignment,
+                )
+            {
+                pane_grid.resize(split, ONE / TWO);
+                self.msa_pane_id = Some(msa_pane_id);
+            }
+        }
+        self.update_msa_geom();
+    }
+
+    /// Lines the alignment rows up with the tips of the tree canvas.
+    fn update_msa_geom(&mut self) {
+        if self.msa_pane_id.is_none() {
+            return;
+        }
+        let cnv_h = self.calc_tre_cnv_h(self.tre_scr_h);
+        let (top, bottom) = self.tre_cnv.phygrm_padd_t_b();
+        let y0 = self.tre_cnv.padd_t + top;
+        let h = (cnv_h - y0 - self.tre_cnv.padd_b - bottom).max(ZRO);
+        let col_w = SF * self.msa_col_w_idx as Float;
+        self.msa_cnv.set_geom(MsaGeom { y0, h, col_w });
+    }
+
+    fn update_tree_set(&mut self) {
+        self.tree_set = match self.tree_set_pane_id {
+            Some(_) => TreeSet::new(&self.tre_states, &self.derived_tre_ids),
+            None => TreeSet::default(),
+        };
+        self.update_tree_set_dists();
+    }
+
+    fn update_tree_set_dists(&mut self) {
+        self.tree_set_dists = match self.sel_tre() {
+            Some(ts) => self.tree_set.distances_to(ts.id()),
+            None => Vec::new(),
+        };
+    }
+
+    fn set_tre_unit(&mut self, unit: TreUnit) {
+        self.with_exclusive_config_mut(&mut |cfg| {
+            cfg.tre_unit = unit;
+        });
+
+        let x_axis_is_reversed = self.cfg.x_axis_is_reversed();
+        self.plot_cnv.x_axis_is_reversed = x_axis_is_reversed;
+        self.tre_cnv.height_axis_is_reversed = x_axis_is_reversed;
+
+        self.show_hide_plot();
+
+        self.tre_cnv.clear_cache_cnv_scale_bar();
+        self.tre_cnv.clear_cache_cnv_height_axis();
+        self.plot_cnv.clear_caches_cnv_all();
+    }
+
+    pub(super) fn brlen_transform_param(&self) -> Option<TreeFloat> {
+        self.brlen_transform_param.trim().parse().ok()
+    }
+
     fn update_draw_labs_allowed(&mut self) {
         self.tre_cnv.draw_labs_allowed = match self.cfg.tre_sty {
             TreSty::PhyGrm => {
@@ -1620,20 +2842,56 @@ impl TreeView {
         f: &mut dyn FnMut(&mut TreeState),
     ) {
         self.tre_cnv.tree_state = None;
+        self.tangle_cnv.release_trees();
+        self.msa_cnv.release_tree();
         if let Some(tre) = self.sel_tre_mut() {
             f(tre);
         }
         self.tre_cnv.tree_state = self.sel_tre();
+        self.update_tangle_cnv();
+        self.msa_cnv.set_tree(self.sel_tre());
+    }
+
+    fn with_exclusive_all_tre_mut(
+        &mut self,
+        f: &mut dyn FnMut(&mut TreeState),
+    ) {
+        self.tre_cnv.tree_state = None;
+        self.tangle_cnv.release_trees();
+        self.msa_cnv.release_tree();
+        for ts in &mut self.tre_states {
+            if let Some(ts) = Rc::get_mut(ts) {
+                f(ts);
+            }
+        }
+        self.tre_cnv.tree_state = self.sel_tre();
+        self.update_tangle_cnv();
+        self.msa_cnv.set_tree(self.sel_tre());
     }
 
     pub fn are_any_trees_loaded(&self) -> bool {
         !self.tre_states.is_empty()
     }
 
+    /// Whether the tanglegram pane is open, which the tanglegram is exported
+    /// from.
+    pub fn is_tanglegram_shown(&self) -> bool {
+        self.tangle_pane_id.is_some()
+    }
+
+    pub fn viewport(&self) -> TvViewport {
+        TvViewport {
+            tre_idx: self.tre_state_idx.unwrap_or_default(),
+            cnv_w_idx: self.tre_cnv_w_idx,
+            cnv_h_idx: self.tre_cnv_h_idx,
+            cnv_z_idx: self.tre_cnv_z_idx,
+            x_mid_rel: self.tre_cnv.vis_x_mid_rel,
+            y_mid_rel: self.tre_cnv.vis_y_mid_rel,
+        }
+    }
+
     pub fn newick_string(&self) -> String {
-        let trees: Vec<Tree> =
-            self.tre_states.iter().map(|ts| ts.tree().clone()).collect();
-        write_newick(&trees)
+        write_newick(&self.trees_to_save(false))
     }
 
     pub fn newick_string_subtree(&self) -> String {
@@ -1646,6 +2904,408 @@ impl TreeView {
         }
     }
 
+    pub fn phyloxml_string(&self, subtree: bool) -> String {
+        phyloxml_string(&self.trees_to_save(subtree))
+    }
+
+    pub fn nexml_string(&self, subtree: bool) -> String {
+        nexml_string(&self.trees_to_save(subtree))
+    }
+
+    /// All trees other than derived ones, which are not in the file the
+    /// trees came from, or the subtree in view in the selected tree.
+    fn trees_to_save(&self, subtree: bool) -> Vec<Tree> {
+        match subtree {
+            true => {
+                self.sel_tre().and_then(|ts| ts.subtree()).into_iter().collect()
+            }
+            false => self
+                .tre_states
+                .iter()
+                .filter(|ts| !self.derived_tre_ids.contains(&ts.id()))
+                .map(|ts| ts.tree().clone())
+                .collect(),
+        }
+    }
+
+    /// Treats all loaded trees as a sample (e.g. bootstrap replicates or a
+    /// posterior sample) and annotates the internal nodes of the selected tree
+    /// with the frequency of their splits in that sample.
+    pub fn annotate_clade_support(&mut self) {
+        let tree_set = TreeSet::new(&self.tre_states, &self.derived_tre_ids);
+        let mut tre_opt: Option<Tree> = self
+            .sel_tre()
+            .and_then(|ts| tree_set.annotated_newick(ts.tree()))
+            .and_then(|newick| parse_trees(newick).ok())
+            .and_then(|trees| trees.into_iter().next());
+
+        if tre_opt.is_none() {
+            return;
+        }
+
+        self.with_exclusive_sel_tre_mut(&mut |ts| {
+            if let Some(tre) = tre_opt.take() {
+                ts.replace_tree(tre);
+            }
+        });
+        self.edit_count += 1;
+        self.update_draw_labs_allowed();
+        self.populate_cache_of_edges_sorted_by_field();
+        self.update_tree_set();
+
+        self.tre_cnv.stale_tre_rect = true;
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+    }
+
+    /// Roots copies of every loaded tree using the given method on another
+    /// thread, as minimal ancestor deviation rooting of many large trees takes
+    /// a while. Trees the method cannot root, e.g. ones without any of the
+    /// outgroup tips, are left as they are. The rooted trees arrive as
+    /// [`TvMsg::TreesRooted`].
+    fn root_all_in_background(
+        &mut self,
+        rooting_method: RootingMethod,
+    ) -> Option<Task<TvMsg>> {
+        if self.root_all_pending.is_some() || self.tre_states.is_empty() {
+            return None;
+        }
+        self.root_all_pending = Some(self.edit_count);
+        let trees: Vec<(usize, Tree)> = self
+            .tre_states
+            .iter()
+            .map(|ts| (ts.id(), ts.tree().clone()))
+            .collect();
+        Some(Task::future(async move {
+            let (sender, mut receiver) = unbounded();
+            _ = std::thread::spawn(move || {
+                _ = sender.unbounded_send(rooted_trees(trees, &rooting_method));
+            });
+            TvMsg::TreesRooted(receiver.next().await.unwrap_or_default())
+        }))
+    }
+
+    fn rooted_trees_replaced(&mut self, trees: Vec<(usize, Tree)>) {
+        if trees.is_empty() {
+            return;
+        }
+        let mut trees: HashMap<usize, Tree> = trees.into_iter().collect();
+
+        self.with_exclusive_all_tre_mut(&mut |ts| {
+            if let Some(tre) = trees.remove(&ts.id()) {
+                ts.replace_tree(tre);
+            }
+        });
+        self.edit_count += 1;
+        self.set_plot_data();
+        self.update_draw_labs_allowed();
+        self.populate_cache_of_edges_sorted_by_field();
+        self.update_tree_set();
+
+        self.tre_cnv.clear_cache_cnv_scale_bar();
+        self.tre_cnv.clear_cache_cnv_height_axis();
+        self.tre_cnv.stale_tre_rect = true;
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+    }
+
+    /// Transforms the branch lengths of the selected tree. `param` is the
+    /// value named by [`BrlenTransform::param_name`]. Transformations that
+    /// yield lengths in another unit also switch the distance unit.
+    pub fn transform_brlens(
+        &mut self,
+        transform: BrlenTransform,
+        param: Option<TreeFloat>,
+    ) {
+        let mut transformed = false;
+        self.with_exclusive_sel_tre_mut(&mut |ts| {
+            transformed = ts.transform_brlens(transform, param);
+        });
+
+        if !transformed {
+            return;
+        }
+        self.edit_count += 1;
+
+        if let Some(unit) = transform.tre_unit() {
+            self.set_tre_unit(unit);
+        }
+
+        self.set_plot_data();
+        self.update_draw_labs_allowed();
+        self.populate_cache_of_edges_sorted_by_field();
+        self.update_tree_set();
+
+        self.tre_cnv.clear_cache_cnv_scale_bar();
+        self.tre_cnv.clear_cache_cnv_height_axis();
+        self.tre_cnv.stale_tre_rect = true;
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+        self.plot_cnv.clear_caches_cnv_all();
+    }
+
+    /// Changes the label or branch length of a node of the selected tree.
+    /// The change can be undone with [`TreeView::undo`].
+    pub(super) fn edit_node(&mut self, node_id: NodeId, edit: &NodeEdit) {
+        let mut edited = false;
+        self.with_exclusive_sel_tre_mut(&mut |ts| {
+            edited = ts.edit_node(node_id, edit);
+        });
+        if edited {
+            self.tree_replaced();
+        }
+    }
+
+    /// Sets, removes or renames attributes of the selected tree. The change
+    /// can be undone with [`TreeView::undo`].
+    pub(super) fn edit_attributes(&mut self, edit: &AttributeEdit) {
+        let mut edited = false;
+        self.with_exclusive_sel_tre_mut(&mut |ts| {
+            edited = ts.edit_attributes(edit);
+        });
+        if edited {
+            self.tree_replaced();
+        }
+    }
+
+    /// Restores the selected tree to what it was before its last edit, e.g.
+    /// a reroot, a removed node, a branch length transformation or a change
+    /// made in the nodes table.
+    pub fn undo(&mut self) {
+        let mut undone = false;
+        self.with_exclusive_sel_tre_mut(&mut |ts| {
+            undone = ts.undo();
+        });
+        if undone {
+            self.nodes_table_edit = None;
+            self.attributes_table_edit = None;
+            self.tree_replaced();
+        }
+    }
+
+    /// Whether any of the trees read from the file was edited since
+    /// [`TreeView::mark_saved`] or since it was loaded. Edits that were
+    /// undone do not count.
+    pub fn has_unsaved_edits(&self) -> bool {
+        self.tre_states
+            .iter()
+            .filter(|ts| !self.derived_tre_ids.contains(&ts.id()))
+            .any(|ts| {
+                ts.revision()
+                    != self
+                        .saved_revisions
+                        .get(&ts.id())
+                        .copied()
+                        .unwrap_or_default()
+            })
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.saved_revisions =
+            self.tre_states.iter().map(|ts| (ts.id(), ts.revision())).collect();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.sel_tre().is_some_and(|ts| ts.can_undo())
+    }
+
+    fn tree_replaced(&mut self) {
+        self.edit_count += 1;
+        self.set_plot_data();
+        self.update_draw_labs_allowed();
+        self.populate_cache_of_edges_sorted_by_field();
+        self.update_tree_set();
+
+        self.tre_cnv.clear_cache_cnv_scale_bar();
+        self.tre_cnv.clear_cache_cnv_height_axis();
+        self.tre_cnv.stale_tre_rect = true;
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+        self.plot_cnv.clear_caches_cnv_all();
+    }
+
+    /// Reads tip metadata from CSV or TSV text whose first column holds the
+    /// tip labels. All tracks start hidden; see [`TreeView::set_track_type`].
+    /// Returns `false` if the text has no usable rows.
+    pub fn load_metadata(&mut self, text: &str) -> bool {
+        let Some(metadata) = Metadata::parse(text) else {
+            return false;
+        };
+        self.track_types = vec![TrackType::Off; metadata.columns().len()];
+        self.metadata = Some(metadata);
+        self.set_parsimony_column(None);
+        self.rtt_date_col = None;
+        self.set_rtt_plot_data();
+        self.update_tracks();
+        true
+    }
+
+    fn set_parsimony_column(&mut self, col: Option<usize>) {
+        self.parsimony_col = col;
+        self.parsimony_states = match (&self.metadata, col) {
+            (Some(metadata), Some(col)) => metadata.column_states(col),
+            _ => Vec::new(),
+        };
+    }
+
+    /// Cost matrix for Sankoff parsimony, one row per state in the order of
+    /// `parsimony_states`. Every change costs one step if none is given.
+    pub(super) fn parsimony_cost_matrix(&self) -> Option<Vec<Vec<Float>>> {
+        let n = self.parsimony_states.len();
+        match self.parsimony_costs.trim().is_empty() {
+            true => Some(unit_cost_matrix(n)),
+            false => parse_cost_matrix(&self.parsimony_costs, n),
+        }
+    }
+
+    /// Reconstructs the ancestral states of the chosen metadata column on the
+    /// selected tree. The states are stored as node attributes named after the
+    /// column and the branches along which the state changes are colored.
+    pub fn reconstruct_states(&mut self) {
+        let (Some(metadata), Some(col), Some(ts)) =
+            (&self.metadata, self.parsimony_col, self.sel_tre())
+        else {
+            return;
+        };
+        let costs = match self.parsimony_method {
+            ParsimonyMethod::Fitch => None,
+            ParsimonyMethod::Sankoff => match self.parsimony_cost_matrix() {
+                Some(costs) => Some(costs),
+                None => return,
+            },
+        };
+
+        let states = self.parsimony_states.clone();
+        let tip_states: HashMap<String, usize> = ts
+            .edges_tip_tree()
+            .iter()
+            .filter_map(|edge| {
+                let label = edge.label.as_deref()?;
+                let value = metadata.value(label, col)?;
+                let s = states.binary_search_by(|s| s.as_str().cmp(value));
+                Some((label.to_string(), s.ok()?))
+            })
+            .collect();
+        let attr = state_attr_key(&metadata.columns()[col]);
+        let tre_id = ts.id();
+        drop(ts);
+
+        let mut tree_len: Option<Float> = None;
+        self.with_exclusive_sel_tre_mut(&mut |ts| {
+            tree_len = ts.reconstruct_states(
+                &attr,
+                &states,
+                &|tip| tip_states.get(tip).copied(),
+                costs.as_deref(),
+            );
+        });
+        let Some(tree_len) = tree_len else {
+            return;
+        };
+        self.edit_count += 1;
+
+        self.parsimony = Some(Parsimony {
+            tre_id,
+            attr: attr.clone(),
+            method: self.parsimony_method,
+            tree_len,
+        });
+        self.tre_cnv.state_change_attr = Some(attr);
+
+        self.update_draw_labs_allowed();
+        self.populate_cache_of_edges_sorted_by_field();
+        self.update_tree_set();
+
+        self.tre_cnv.stale_tre_rect = true;
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+    }
+
+    /// Result of the last reconstruction if it was made on the selected tree.
+    pub(super) fn sel_tre_parsimony(&self) -> Option<&Parsimony> {
+        let tre_id = self.sel_tre()?.id();
+        self.parsimony.as_ref().filter(|p| p.tre_id == tre_id)
+    }
+
+    fn set_state_change_attr(&mut self, attr: Option<String>) {
+        if self.tre_cnv.state_change_attr == attr {
+            return;
+        }
+        self.tre_cnv.state_change_attr = attr;
+
+        for ts in &self.tre_states {
+            ts.clear_cache_cnv_state_changes();
+        }
+
+        self.tre_cnv.clear_cache_cnv_legend();
+    }
+
+    /// Sets how the metadata column with index `col` is drawn.
+    pub fn set_track_type(&mut self, col: usize, track_type: TrackType) {
+        if let Some(tt) = self.track_types.get_mut(col) {
+            *tt = track_type;
+            self.update_tracks();
+        }
+    }
+
+    fn update_tracks(&mut self) {
+        self.tre_cnv.tracks = match &self.metadata {
+            Some(metadata) => self
+                .track_types
+                .iter()
+                .enumerate()
+                .filter_map(|(col, &tt)| Track::new(metadata, col, tt, col))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        for ts in &self.tre_states {
+            ts.clear_cache_cnv_tracks();
+        }
+
+        self.tre_cnv.stale_tre_rect = true;
+        self.clear_caches_cnv_ts_all();
+        self.tre_cnv.clear_caches_cnv_all();
+    }
+
+    fn set_node_glyphs(&mut self, glyphs: NodeGlyphs) {
+        if self.tre_cnv.node_glyphs == glyphs {
+            return;
+        }
+        self.tre_cnv.node_glyphs = glyphs;
+
+        for ts in &self.tre_states {
+            ts.clear_cache_cnv_node_glyphs();
+        }
+
+        self.tre_cnv.clear_cache_cnv_legend();
+    }
+
+    fn set_placements_view(&mut self, view: PlacementsView) {
+        if self.tre_cnv.placements_view == view {
+            return;
+        }
+        self.tre_cnv.placements_view = view;
+
+        for ts in &self.tre_states {
+            ts.clear_cache_cnv_placements();
+        }
+
+        self.tre_cnv.clear_cache_cnv_legend();
+    }
+
+    /// Reads a FASTA, PHYLIP or NEXUS alignment and shows it beside the tree.
+    /// Returns `false` if the text holds no sequences.
+    pub fn load_alignment(&mut self, text: &str) -> bool {
+        let Some(alignment) = Alignment::parse(text) else {
+            return false;
+        };
+        self.msa_cnv.set_tree(self.sel_tre());
+        self.msa_cnv.set_alignment(Some(alignment));
+        self.show_hide_msa();
+        true
+    }
+
     pub fn toggle_draw_debug(&mut self) {
         self.tre_cnv.draw_debug = !self.tre_cnv.draw_debug;
         self.plot_cnv.draw_debug = self.tre_cnv.draw_debug;
@@ -1755,6 +3415,10 @@ pub(crate) enum TreeViewPane {
     Tree,
     Plot,
     NodesTable,
+    Tanglegram,
+    TreeSet,
+    Alignment,
+    Statistics,
 }
 
 impl Display for TreeViewPane {
@@ -1763,6 +3427,17 @@ impl Display for TreeViewPane {
     }
 }
 
+/// Writes exported text to a file. A failure is passed on to the app as
+/// [`TvMsg::ExportFailed`].
+fn write_export(
+    path_buf: std::path::PathBuf,
+    text: String,
+) -> Option<Task<TvMsg>> {
+    std::fs::write(&path_buf, text)
+        .err()
+        .map(|e| Task::done(TvMsg::ExportFailed(path_buf, e.to_string())))
+}
+
 impl From<TreeViewPane> for String {
     fn from(value: TreeViewPane) -> Self {
         (&value).into()
@@ -1775,6 +3450,10 @@ impl From<&TreeViewPane> for String {
             TreeViewPane::Tree => String::from("Tree"),
             TreeViewPane::Plot => String::from("Plot"),
             TreeViewPane::NodesTable => String::from("NodesTable"),
+            TreeViewPane::Tanglegram => String::from("Tanglegram"),
+            TreeViewPane::TreeSet => String::from("TreeSet"),
+            TreeViewPane::Alignment => String::from("Alignment"),
+            TreeViewPane::Statistics => String::from("Statistics"),
         }
     }
 }
@@ -4,6 +4,7 @@ mod scrollables;
 mod tables;
 mod togglers;
 
+use crate::legend::fmt_num;
 use crate::*;
 
 use buttons::*;
@@ -77,6 +78,10 @@ fn content<'a>(tv: &'a TreeView) -> Element<'a, TvMsg> {
                 TreeViewPane::Tree => sty_pane_body,
                 TreeViewPane::Plot => sty_pane_body_plot,
                 TreeViewPane::NodesTable => sty_pane_body,
+                TreeViewPane::Tanglegram => sty_pane_body,
+                TreeViewPane::TreeSet => sty_pane_body,
+                TreeViewPane::Alignment => sty_pane_body,
+                TreeViewPane::Statistics => sty_pane_body,
             })
         })
         .style(sty_pane_grid)
@@ -106,7 +111,9 @@ fn pane_content<'a>(
         }
         TreeViewPane::Plot => {
             let mut cnv_w = cnv_w;
-            if tv.cfg.tre_sty == TreSty::Fan {
+            if tv.cfg.tre_sty == TreSty::Fan
+                || !tv.plot_cnv.plot_type.is_tree_aligned()
+            {
                 cnv_w = w;
             }
             let cnv =
@@ -120,22 +127,56 @@ fn pane_content<'a>(
             let mut pane_row: Row<TvMsg> = Row::new();
             let mut psc: Column<TvMsg> = Column::new();
 
-            psc = psc.push(toggler_gts(
-                tv.cfg.tre_unit == TreUnit::MillionYears,
-                tv.plot_cnv.cfg.draw_gts,
-            ));
+            psc = psc.push(pick_list_plot_type(tv.plot_cnv.plot_type));
 
-            psc = psc.push(toggler_ltt(
-                tv.cfg.tre_unit != TreUnit::Unitless,
-                tv.plot_cnv.cfg.draw_ltt,
-            ));
+            match tv.plot_cnv.plot_type {
+                PlotType::Ltt => {
+                    psc = psc.push(toggler_gts(
+                        tv.cfg.tre_unit == TreUnit::MillionYears,
+                        tv.plot_cnv.cfg.draw_gts,
+                    ));
 
-            if tv.plot_cnv.cfg.draw_ltt {
-                psc = psc.push(pick_list_plot_y_axis_scale_type(
-                    tv.plot_cnv.y_axis_scale_type,
-                ));
+                    psc = psc.push(toggler_ltt(
+                        tv.cfg.tre_unit != TreUnit::Unitless,
+                        tv.plot_cnv.cfg.draw_ltt,
+                    ));
+
+                    if tv.plot_cnv.cfg.draw_ltt {
+                        psc = psc.push(pick_list_plot_y_axis_scale_type(
+                            tv.plot_cnv.y_axis_scale_type,
+                        ));
+                    }
+                }
+                PlotType::LttAll => {
+                    psc = psc.push(toggler_gts(
+                        tv.cfg.tre_unit == TreUnit::MillionYears,
+                        tv.plot_cnv.cfg.draw_gts,
+                    ));
+                    psc = psc.push(pick_list_plot_y_axis_scale_type(
+                        tv.plot_cnv.y_axis_scale_type,
+                    ));
+                }
+                PlotType::RootToTip => psc = psc.push(root_to_tip(tv)),
+                PlotType::BrlenHistogram
+                | PlotType::DepthHistogram
+                | PlotType::AttrHistogram => {
+                    if tv.plot_cnv.plot_type == PlotType::AttrHistogram {
+                        psc = psc.push(pick_list_plot_attr(
+                            tv.sel_tre_num_node_attr_keys(),
+                            tv.plot_attr.as_ref(),
+                        ));
+                    }
+                    psc = psc.push(pick_list_plot_y_axis_scale_type(
+                        tv.plot_cnv.y_axis_scale_type,
+                    ));
+                }
             }
 
+            psc = psc.push(btn_export_data(
+                DataExport::Plot,
+                tv.sel_tre().is_some(),
+            ));
+
             psc = psc.spacing(PADDING + SF * TWO).padding(PADDING);
 
             pane_row = pane_row.push(scrl);
@@ -143,6 +184,181 @@ fn pane_content<'a>(
 
             pane_row.into()
         }
+        TreeViewPane::Tanglegram => {
+            let cnv = Cnv::new(&tv.tangle_cnv).width(w - SIDE_BAR_W).height(h);
+            let mut pane_row: Row<TvMsg> = Row::new();
+            let mut psc: Column<TvMsg> = Column::new();
+
+            psc = psc.push(pick_list_tangle_tre(
+                &tv.tangle_tre_opts,
+                tv.tangle_partner_id(),
+            ));
+
+            psc = psc.push(
+                iced_row![
+                    txt("Crossings").width(Length::FillPortion(9)),
+                    txt_usize(tv.tangle_cnv.crossings)
+                        .width(Length::FillPortion(10))
+                ]
+                .align_y(Vertical::Center),
+            );
+
+            psc = psc.spacing(PADDING + SF * TWO).padding(PADDING);
+
+            pane_row = pane_row.push(cnv);
+            pane_row = pane_row.push(psc.clip(true));
+
+            pane_row.into()
+        }
+        TreeViewPane::TreeSet => {
+            let mut pane_row: Row<TvMsg> = Row::new();
+            let mut psc: Column<TvMsg> = Column::new();
+            let enabled = tv.tree_set.input_count() > 1;
+
+            psc = psc.push(iced_row![
+                iced_col![txt("Taxa"), txt("Trees"), txt("Rooted")]
+                    .width(Length::Fill),
+                iced_col![
+                    txt_usize(tv.tree_set.taxon_count()),
+                    txt_usize(tv.tree_set.input_count()),
+                    txt_bool(tv.tree_set.is_rooted()),
+                ]
+                .align_x(Horizontal::Right)
+            ]);
+            psc = psc.push(rule_h(SF));
+            psc = psc.push(btn_consensus(ConsensusType::MajorityRule, enabled));
+            psc = psc.push(btn_consensus(ConsensusType::Strict, enabled));
+            psc = psc.push(rule_h(SF));
+            psc = psc.push(btn_annotate_clade_support(enabled));
+
+            psc = psc.spacing(PADDING + SF * TWO).padding(PADDING);
+
+            pane_row = pane_row.push(table_tree_set_dists(tv, w - SIDE_BAR_W));
+            pane_row = pane_row.push(psc.width(SIDE_BAR_W).clip(true));
+
+            pane_row.into()
+        }
+        TreeViewPane::Alignment => {
+            let mut pane_row: Row<TvMsg> = Row::new();
+            let mut psc: Column<TvMsg> = Column::new();
+            let msa_w = w - SIDE_BAR_W;
+
+            match tv.cfg.tre_sty {
+                TreSty::PhyGrm => {
+                    let cnv = Cnv::new(&tv.msa_cnv)
+                        .width(tv.msa_cnv.width().max(msa_w))
+                        .height(cnv_h);
+                    pane_row = pane_row.push(scrollable_cnv_msa(
+                        tv.msa_scrollable_id, cnv, msa_w, h,
+                    ));
+                }
+                TreSty::Fan => {
+                    pane_row = pane_row.push(
+                        center(txt("Shown in the phylogram layout only"))
+                            .width(msa_w)
+                            .height(h),
+                    );
+                }
+            }
+
+            if let Some(aln) = &tv.msa_cnv.alignment {
+                let (c0, c1) = tv.msa_cnv.vis_cols();
+                psc = psc.push(iced_row![
+                    iced_col![txt("Sequences"), txt("Columns"), txt("In View")]
+                        .width(Length::Fill),
+                    iced_col![
+                        txt_usize(aln.nrow()),
+                        txt_usize(aln.ncol()),
+                        txt(format!("{}-{}", (c0 + 1).min(c1), c1)),
+                    ]
+                    .align_x(Horizontal::Right)
+                ]);
+            }
+            psc = psc.push(iced_col![
+                txt("Column Width"),
+                space_v(ONE, PADDING / TWO),
+                slider(
+                    None,
+                    MSA_COL_W_IDX_MIN,
+                    MSA_COL_W_IDX_MAX,
+                    tv.msa_col_w_idx,
+                    1,
+                    2,
+                    TvMsg::MsaColWidthChanged,
+                ),
+            ]);
+            psc = psc.push(btn_clear_alignment());
+
+            let mut missing: Column<TvMsg> = Column::new();
+            missing = missing.spacing(PADDING / TWO);
+            missing =
+                missing.padding(Padding { right: PADDING, ..Padding::ZERO });
+            for (title, names) in [
+                ("Tips Without Sequence", &tv.msa_cnv.tips_missing),
+                ("Sequences Not in Tree", &tv.msa_cnv.seqs_missing),
+            ] {
+                if names.is_empty() {
+                    continue;
+                }
+                missing = missing.push(rule_h(SF));
+                missing =
+                    missing.push(txt(format!("{title} ({})", names.len())));
+                for name in names.iter().take(MSA_MISSING_NAMES_MAX) {
+                    missing = missing.push(txt(name.as_str()));
+                }
+                if names.len() > MSA_MISSING_NAMES_MAX {
+                    missing = missing.push(txt(format!(
+                        "... {} more",
+                        names.len() - MSA_MISSING_NAMES_MAX
+                    )));
+                }
+            }
+            psc = psc.push(scrollable_v(missing, Length::Fill, Length::Fill));
+
+            psc = psc.spacing(PADDING + SF * TWO).padding(PADDING);
+
+            pane_row = pane_row.push(psc.width(SIDE_BAR_W).clip(true));
+
+            pane_row.into()
+        }
+        TreeViewPane::Statistics => {
+            let mut pane_row: Row<TvMsg> = Row::new();
+            let mut psc: Column<TvMsg> = Column::new();
+
+            let mut labels: Column<TvMsg> = Column::new();
+            let mut values: Column<TvMsg> = Column::new();
+            if let Some(stats) = &tv.tree_stats {
+                for (label, value) in stats.rows() {
+                    labels = labels.push(txt(label));
+                    values = values.push(txt(value));
+                }
+            }
+            let table = iced_row![
+                labels.width(Length::Fill),
+                values.align_x(Horizontal::Right)
+            ]
+            .spacing(PADDING)
+            .padding(PADDING);
+            pane_row = pane_row.push(scrollable_v(
+                table,
+                w - SIDE_BAR_W,
+                Length::Fill,
+            ));
+
+            let subtree =
+                tv.sel_tre().is_some_and(|ts| ts.is_subtree_view_active());
+            psc = psc.push(txt(match subtree {
+                true => "Subtree in View",
+                false => "Whole Tree",
+            }));
+            psc = psc.push(btn_copy_tree_stats(tv.tree_stats.is_some()));
+
+            psc = psc.spacing(PADDING + SF * TWO).padding(PADDING);
+
+            pane_row = pane_row.push(psc.width(SIDE_BAR_W).clip(true));
+
+            pane_row.into()
+        }
         // TreeViewPane::NodesTable => table_nodes(tv, w, h),
         // TreeViewPane::NodesTable => table_attributes(tv, w, h),
         TreeViewPane::NodesTable => table_node_data(tv, w, h),
@@ -155,7 +371,26 @@ fn toolbar<'a>(tv: &'a TreeView, ts: Rc<TreeState>) -> Container<'a, TvMsg> {
 
     tb_row = tb_row.push(
         center(
-            iced_row![btn_unroot(ts.clone()), btn_root(ts.clone())].spacing(SF),
+            iced_row![
+                btn_unroot(ts.clone()),
+                btn_root(ts.clone()),
+                btn_root_all(ts.clone(), tv.root_all_pending.is_none()),
+                match tv.root_all_pending {
+                    Some(_) => Element::from(
+                        center(txt("Rooting...")).width(BTN_H1 * 5e0),
+                    ),
+                    None => pick_list_root_all().into(),
+                },
+                txt_input(
+                    "Outgroup Tips",
+                    &tv.outgroup_string,
+                    tv.outgroup_text_input_id,
+                    TvMsg::OutgroupChanged,
+                )
+                .on_submit(TvMsg::RootAllOnOutgroup)
+                .width(BTN_H1 * 6e0)
+            ]
+            .spacing(SF),
         )
         .width(Length::Shrink)
         .height(Length::Shrink),
@@ -181,6 +416,17 @@ fn toolbar<'a>(tv: &'a TreeView, ts: Rc<TreeState>) -> Container<'a, TvMsg> {
 
     tb_row = tb_row.push(space_h(Length::Fill, Length::Shrink));
 
+    if let Some(reloaded_at) = tv.reloaded_at {
+        tb_row = tb_row.push(
+            center(txt(format!(
+                "Reloaded {}",
+                fmt_elapsed(reloaded_at.elapsed().as_secs())
+            )))
+            .width(Length::Shrink)
+            .height(Length::Shrink),
+        );
+    }
+
     tb_row = tb_row.push(
         center(
             iced_row![
@@ -206,6 +452,27 @@ fn toolbar<'a>(tv: &'a TreeView, ts: Rc<TreeState>) -> Container<'a, TvMsg> {
                     Some(TvMsg::ToggleNodesTable),
                     tv.cfg.show_nodes_table,
                 ),
+                btn_svg_stateful(
+                    Icon::Tanglegram,
+                    Icon::Tanglegram,
+                    match tv.tre_states.len() > 1 {
+                        true => Some(TvMsg::ToggleTanglegram),
+                        false => None,
+                    },
+                    tv.cfg.show_tanglegram && tv.tre_states.len() > 1,
+                ),
+                btn_svg_stateful(
+                    Icon::TreeSet,
+                    Icon::TreeSet,
+                    Some(TvMsg::ToggleTreeSet),
+                    tv.cfg.show_tree_set,
+                ),
+                btn_svg_stateful(
+                    Icon::TreeStats,
+                    Icon::TreeStats,
+                    Some(TvMsg::ToggleTreeStats),
+                    tv.cfg.show_tree_stats,
+                ),
             ]
             .spacing(SF),
         )
@@ -224,6 +491,15 @@ fn toolbar<'a>(tv: &'a TreeView, ts: Rc<TreeState>) -> Container<'a, TvMsg> {
         .align_y(Vertical::Center)
 }
 
+fn fmt_elapsed(secs: u64) -> String {
+    match secs {
+        0..5 => String::from("just now"),
+        5..60 => format!("{secs} s ago"),
+        60..3_600 => format!("{} min ago", secs / 60),
+        _ => format!("{} h ago", secs / 3_600),
+    }
+}
+
 fn tree_switcher<'a>(
     tv: &'a TreeView,
     ts: Rc<TreeState>,
@@ -437,6 +713,131 @@ fn stats(ts: Rc<TreeState>) -> Row<'static, TvMsg> {
     stats_row
 }
 
+fn brlen_transform<'a>(
+    tv: &'a TreeView,
+    ts: Rc<TreeState>,
+) -> Column<'a, TvMsg> {
+    let transform = tv.brlen_transform;
+    let param = tv.brlen_transform_param();
+    let enabled = (ts.has_brlen() || !transform.needs_brlen())
+        && (transform.param_name().is_none()
+            || param.is_some_and(|param| param > 0e0));
+
+    let mut row: Row<TvMsg> = Row::new();
+    row = row.spacing(SF * TWO);
+    row = row.align_y(Vertical::Center);
+    row = match transform.param_name() {
+        Some(param_name) => row.push(txt_input(
+            param_name,
+            &tv.brlen_transform_param,
+            "brlen_transform_param",
+            TvMsg::BrlenTransformParamChanged,
+        )),
+        None => row.push(space_h(Length::Fill, Length::Shrink)),
+    };
+    row = row.push(btn_transform_brlens(enabled));
+
+    iced_col![pick_list_brlen_transform(transform), row].spacing(PADDING)
+}
+
+fn root_to_tip<'a>(tv: &'a TreeView) -> Column<'a, TvMsg> {
+    let columns: &[String] = match &tv.metadata {
+        Some(metadata) => metadata.columns(),
+        None => &[],
+    };
+    let mut sources: Vec<String> = vec![RTT_DATE_SOURCE_LABELS.to_string()];
+    sources.extend(columns.iter().cloned());
+    let source = match tv.rtt_date_col.and_then(|c| columns.get(c)) {
+        Some(column) => column.clone(),
+        None => RTT_DATE_SOURCE_LABELS.to_string(),
+    };
+
+    let mut col: Column<TvMsg> = Column::new();
+    col = col.spacing(PADDING);
+    col = col.push(pick_list_rtt_date_source(sources, source));
+    if tv.rtt_date_col.is_none() {
+        col = col.push(txt_input(
+            "Pattern, e.g. *|{date}",
+            &tv.rtt_date_pattern,
+            "rtt_date_pattern",
+            TvMsg::RttDatePatternChanged,
+        ));
+    }
+
+    match &tv.plot_cnv.rtt {
+        Some(rtt) => {
+            let tmrca = match rtt.x_intercept() {
+                Some(x_intercept) => fmt_num(x_intercept),
+                None => String::from("-"),
+            };
+            for (label, value) in [
+                ("Dated Tips", rtt.dates.len().to_string()),
+                ("Rate (Slope)", fmt_num(rtt.slope)),
+                ("TMRCA (X-Intercept)", tmrca),
+                ("R²", fmt_num(rtt.r2)),
+            ] {
+                col = col.push(iced_row![
+                    txt(label),
+                    space_h(Length::Fill, Length::Shrink),
+                    txt(value),
+                ]);
+            }
+        }
+        None => col = col.push(txt("Fewer than two tips with distinct dates")),
+    }
+    col
+}
+
+fn ancestral_states<'a>(
+    tv: &'a TreeView,
+    metadata: &'a Metadata,
+) -> Column<'a, TvMsg> {
+    let sankoff = tv.parsimony_method == ParsimonyMethod::Sankoff;
+    let enabled = !tv.parsimony_states.is_empty()
+        && (!sankoff || tv.parsimony_cost_matrix().is_some());
+
+    let mut col: Column<TvMsg> = Column::new();
+    col = col.spacing(PADDING);
+    col = col.push(txt("Ancestral States"));
+    col = col.push(pick_list_parsimony_column(
+        metadata.columns(),
+        tv.parsimony_col.and_then(|c| metadata.columns().get(c)),
+    ));
+    col = col.push(pick_list_parsimony_method(tv.parsimony_method));
+    if sankoff && !tv.parsimony_states.is_empty() {
+        col = col
+            .push(txt(format!("States: {}", tv.parsimony_states.join(", "))));
+        col = col.push(txt_input(
+            "Costs, e.g. 0 1; 1 0",
+            &tv.parsimony_costs,
+            "parsimony_costs",
+            TvMsg::ParsimonyCostsChanged,
+        ));
+    }
+
+    let tree_len = match tv.sel_tre_parsimony() {
+        Some(parsimony) => format!(
+            "{} Length: {}",
+            parsimony.method,
+            fmt_num(parsimony.tree_len)
+        ),
+        None => String::new(),
+    };
+    col = col.push(
+        iced_row![
+            txt(tree_len),
+            space_h(Length::Fill, Length::Shrink),
+            btn_reconstruct_states(enabled),
+        ]
+        .align_y(Vertical::Center),
+    );
+    col = col.push(toggler_state_changes(
+        tv.parsimony.is_some(),
+        tv.tre_cnv.state_change_attr.is_some(),
+    ));
+    col
+}
+
 fn side_bar_main<'a>(
     tv: &'a TreeView,
     ts: Rc<TreeState>,
@@ -453,6 +854,8 @@ fn side_bar_main<'a>(
         sb = sb.push(pick_list_tree_unit(tv.cfg.tre_unit));
         sb = sb.push(rule_h(SF));
     }
+    sb = sb.push(brlen_transform(tv, ts.clone()));
+    sb = sb.push(rule_h(SF));
     sb = sb.push(pick_list_tre_sty(tv.cfg.tre_sty));
     sb = sb.push(pick_list_node_ordering(tv.cfg.node_ord_opt));
     sb = sb.push(rule_h(SF));
@@ -674,6 +1077,82 @@ fn side_bar_annotations<'a>(
         ));
     }
 
+    let num_node_attr_keys = tv.sel_tre_num_node_attr_keys();
+    if !num_node_attr_keys.is_empty() {
+        let glyphs = &tv.tre_cnv.node_glyphs;
+        sb = sb.push(rule_h(SF));
+        sb = sb.push(
+            iced_row![
+                txt("Node Glyphs"),
+                space_h(Length::Fill, Length::Shrink),
+                btn_clear_node_glyphs(!glyphs.is_empty()),
+            ]
+            .align_y(Vertical::Center),
+        );
+        let mut pies: Column<TvMsg> = Column::new();
+        pies = pies.spacing(PADDING / TWO);
+        pies = pies.push(txt("Pie Charts"));
+        for key in num_node_attr_keys {
+            pies = pies.push(checkbox(
+                key,
+                glyphs.pie_attrs.contains(key),
+                |state| TvMsg::PieAttrToggled(key.clone(), state),
+            ));
+        }
+        sb = sb.push(pies);
+        sb = sb.push(pick_list_symbol_attr(
+            num_node_attr_keys,
+            glyphs.symbol_attr.as_ref(),
+        ));
+        if glyphs.symbol_attr.is_some() {
+            sb = sb.push(pick_list_symbol_shape(glyphs.symbol_shape));
+            sb = sb.push(pick_list_symbol_encoding(glyphs.symbol_encoding));
+        }
+    }
+
+    if let Some(placements) = ts.placements() {
+        let view = &tv.tre_cnv.placements_view;
+        sb = sb.push(rule_h(SF));
+        sb = sb.push(txt(format!(
+            "Placements ({} queries)",
+            placements.pquery_count()
+        )));
+        sb = sb.push(toggler_placement_circles(view.draw_circles));
+        if view.draw_circles {
+            sb = sb.push(pick_list_placement_size(view.size));
+        }
+        sb = sb.push(toggler_placement_density(view.draw_density));
+        sb = sb.push(txt_input(
+            "Filter by name",
+            &view.name_filter,
+            "placement_name_filter",
+            TvMsg::PlacementNameFilterChanged,
+        ));
+    }
+
+    if let Some(metadata) = &tv.metadata {
+        sb = sb.push(rule_h(SF));
+        sb = sb.push(
+            iced_row![
+                txt(format!("Metadata ({} rows)", metadata.row_count())),
+                space_h(Length::Fill, Length::Shrink),
+                btn_clear_metadata(),
+            ]
+            .align_y(Vertical::Center),
+        );
+        sb = sb.push(ancestral_states(tv, metadata));
+        sb = sb.push(rule_h(SF));
+        let mut tracks: Column<TvMsg> = Column::new();
+        tracks = tracks.spacing(PADDING / TWO);
+        tracks = tracks.padding(Padding { right: PADDING, ..Padding::ZERO });
+        for (col, (column, &track_type)) in
+            metadata.columns().iter().zip(&tv.track_types).enumerate()
+        {
+            tracks = tracks.push(pick_list_track_type(column, col, track_type));
+        }
+        sb = sb.push(scrollable_v(tracks, Length::Fill, Length::Fill));
+    }
+
     container(sb.clip(true))
         .style(sty_cont_bottom_right)
         .padding(PADDING)
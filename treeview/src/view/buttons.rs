@@ -90,6 +90,28 @@ pub(super) fn btn_root<'a>(sel_tre: Rc<TreeState>) -> Button<'a, TvMsg> {
     .width(BTN_H1 * TWO)
 }
 
+/// Roots all trees on the most recent common ancestor of the tips under the
+/// selected nodes.
+pub(super) fn btn_root_all<'a>(
+    sel_tre: Rc<TreeState>,
+    enabled: bool,
+) -> Button<'a, TvMsg> {
+    let labels =
+        sel_tre.clade_tip_labels(sel_tre.sel_node_ids().iter().copied());
+    btn_txt(
+        "Root All",
+        match enabled
+            && !sel_tre.is_subtree_view_active()
+            && !labels.is_empty()
+            && labels.len() < sel_tre.tip_count()
+        {
+            true => Some(TvMsg::RootAll(RootingMethod::Outgroup(labels))),
+            false => None,
+        },
+    )
+    .width(BTN_H1 * 3e0)
+}
+
 pub(super) fn btn_unroot<'a>(sel_tre: Rc<TreeState>) -> Button<'a, TvMsg> {
     btn_txt(
         "Unroot",
@@ -131,3 +153,119 @@ pub(super) fn btn_clear_subtree_view<'a>(
     )
     .width(BTN_H1 * 3.0)
 }
+
+pub(super) fn btn_consensus<'a>(
+    consensus_type: ConsensusType,
+    enabled: bool,
+) -> Button<'a, TvMsg> {
+    btn_txt(
+        match consensus_type {
+            ConsensusType::MajorityRule => "Majority-Rule Consensus",
+            ConsensusType::Strict => "Strict Consensus",
+        },
+        match enabled {
+            true => Some(TvMsg::AddConsensusTree(consensus_type)),
+            false => None,
+        },
+    )
+    .width(Length::Fill)
+}
+
+pub(super) fn btn_annotate_clade_support<'a>(
+    enabled: bool,
+) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Annotate Support",
+        match enabled {
+            true => Some(TvMsg::AnnotateCladeSupport),
+            false => None,
+        },
+    )
+    .width(Length::Fill)
+}
+
+pub(super) fn btn_transform_brlens<'a>(enabled: bool) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Transform",
+        match enabled {
+            true => Some(TvMsg::TransformBrlens),
+            false => None,
+        },
+    )
+    .width(BTN_H1 * 4.0)
+}
+
+pub(super) fn btn_clear_metadata<'a>() -> Button<'a, TvMsg> {
+    btn_txt("Clear", Some(TvMsg::ClearMetadata)).width(BTN_H1 * 3.0)
+}
+
+pub(super) fn btn_clear_alignment<'a>() -> Button<'a, TvMsg> {
+    btn_txt("Close", Some(TvMsg::ClearAlignment)).width(BTN_H1 * 3.0)
+}
+
+pub(super) fn btn_clear_node_glyphs<'a>(enabled: bool) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Clear",
+        match enabled {
+            true => Some(TvMsg::ClearNodeGlyphs),
+            false => None,
+        },
+    )
+    .width(BTN_H1 * 3.0)
+}
+
+pub(super) fn btn_reconstruct_states<'a>(enabled: bool) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Reconstruct",
+        match enabled {
+            true => Some(TvMsg::ReconstructStates),
+            false => None,
+        },
+    )
+    .width(BTN_H1 * 4.0)
+}
+
+pub(super) fn btn_export_data<'a>(
+    data: DataExport,
+    enabled: bool,
+) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Export...",
+        match enabled {
+            true => Some(TvMsg::ExportDataRequested(data)),
+            false => None,
+        },
+    )
+    .width(Length::Fill)
+}
+
+pub(super) fn btn_add_attribute<'a>(enabled: bool) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Add",
+        match enabled {
+            true => Some(TvMsg::AddAttributeToSelected),
+            false => None,
+        },
+    )
+}
+
+pub(super) fn btn_remove_attribute<'a>(enabled: bool) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Remove",
+        match enabled {
+            true => Some(TvMsg::RemoveAttributeFromSelected),
+            false => None,
+        },
+    )
+}
+
+pub(super) fn btn_copy_tree_stats<'a>(enabled: bool) -> Button<'a, TvMsg> {
+    btn_txt(
+        "Copy",
+        match enabled {
+            true => Some(TvMsg::CopyTreeStats),
+            false => None,
+        },
+    )
+    .width(Length::Fill)
+}
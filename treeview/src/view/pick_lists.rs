@@ -37,6 +37,50 @@ pub(super) fn pick_list_plot_y_axis_scale_type<'a>(
         .align_y(Vertical::Center)
 }
 
+pub(super) fn pick_list_plot_type<'a>(plot_type: PlotType) -> Row<'a, TvMsg> {
+    let mut pl: PickList<PlotType, &[PlotType], PlotType, TvMsg> =
+        PickList::new(&PLOT_TYPE_OPTS, Some(plot_type), TvMsg::PlotTypeChanged);
+    pl = pick_list_common(pl);
+    iced_row![txt("Plot").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_plot_attr<'a>(
+    keys: &'a [String],
+    key: Option<&'a String>,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<String, &[String], String, TvMsg> =
+        PickList::new(keys, key.cloned(), TvMsg::PlotAttrChanged);
+    pl = pick_list_common(pl);
+    pl = pl.placeholder("None");
+    iced_row![txt("Attribute").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_nodes_table_attr<'a>(
+    keys: &[String],
+    key: Option<&String>,
+) -> PickList<'a, String, Vec<String>, String, TvMsg> {
+    let opts: Vec<String> = std::iter::once(NODES_TABLE_NO_ATTR.to_string())
+        .chain(keys.iter().cloned())
+        .collect();
+    let mut pl: PickList<String, Vec<String>, String, TvMsg> =
+        PickList::new(opts, key.cloned(), TvMsg::NodesTableAttrChanged);
+    pl = pick_list_common(pl);
+    pl.placeholder("Attribute")
+}
+
+pub(super) fn pick_list_rtt_date_source<'a>(
+    sources: Vec<String>,
+    source: String,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<String, Vec<String>, String, TvMsg> =
+        PickList::new(sources, Some(source), TvMsg::RttDateSourceChanged);
+    pl = pick_list_common(pl);
+    iced_row![txt("Dates").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
 pub(super) fn pick_list_node_ordering<'a>(
     node_ord: TreNodeOrd,
 ) -> Row<'a, TvMsg> {
@@ -66,3 +110,152 @@ pub(super) fn pick_list_tree_unit<'a>(tre_units: TreUnit) -> Row<'a, TvMsg> {
     iced_row![txt("Distance Unit").width(Length::FillPortion(9)), pl]
         .align_y(Vertical::Center)
 }
+
+pub(super) fn pick_list_tangle_tre<'a>(
+    tre_ids: &'a [usize],
+    tre_id: Option<usize>,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<usize, &[usize], usize, TvMsg> =
+        PickList::new(tre_ids, tre_id, TvMsg::TanglegramTreeChanged);
+    pl = pick_list_common(pl);
+    iced_row![txt("Compare With").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_root_all<'a>()
+-> PickList<'a, RootingMethod, &'a [RootingMethod], RootingMethod, TvMsg> {
+    let mut pl: PickList<
+        RootingMethod,
+        &[RootingMethod],
+        RootingMethod,
+        TvMsg,
+    > = PickList::new(&ROOTING_METHOD_OPTS, None, TvMsg::RootAll);
+    pl = pick_list_common(pl);
+    pl.placeholder("Auto Root All").width(BTN_H1 * 5.0)
+}
+
+pub(super) fn pick_list_brlen_transform<'a>(
+    transform: BrlenTransform,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<
+        BrlenTransform,
+        &[BrlenTransform],
+        BrlenTransform,
+        TvMsg,
+    > = PickList::new(
+        &BRLEN_TRANSFORM_OPTS,
+        Some(transform),
+        TvMsg::BrlenTransformChanged,
+    );
+    pl = pick_list_common(pl);
+    iced_row![txt("Branch Lengths").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_track_type<'a>(
+    column: &'a str,
+    col: usize,
+    track_type: TrackType,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<TrackType, &[TrackType], TrackType, TvMsg> =
+        PickList::new(&TRACK_TYPE_OPTS, Some(track_type), move |tt| {
+            TvMsg::TrackTypeChanged(col, tt)
+        });
+    pl = pick_list_common(pl);
+    iced_row![txt(column).width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_symbol_attr<'a>(
+    keys: &'a [String],
+    symbol_attr: Option<&'a String>,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<String, &[String], String, TvMsg> =
+        PickList::new(keys, symbol_attr.cloned(), |key| {
+            TvMsg::SymbolAttrChanged(Some(key))
+        });
+    pl = pick_list_common(pl);
+    pl = pl.placeholder("None");
+    iced_row![txt("Symbols").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_symbol_shape<'a>(
+    symbol_shape: SymbolShape,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<SymbolShape, &[SymbolShape], SymbolShape, TvMsg> =
+        PickList::new(
+            &SYMBOL_SHAPE_OPTS,
+            Some(symbol_shape),
+            TvMsg::SymbolShapeChanged,
+        );
+    pl = pick_list_common(pl);
+    iced_row![txt("Shape").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_symbol_encoding<'a>(
+    symbol_encoding: SymbolEncoding,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<
+        SymbolEncoding,
+        &[SymbolEncoding],
+        SymbolEncoding,
+        TvMsg,
+    > = PickList::new(
+        &SYMBOL_ENCODING_OPTS,
+        Some(symbol_encoding),
+        TvMsg::SymbolEncodingChanged,
+    );
+    pl = pick_list_common(pl);
+    iced_row![txt("Value Shown As").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_placement_size<'a>(
+    size: PlacementSize,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<
+        PlacementSize,
+        &[PlacementSize],
+        PlacementSize,
+        TvMsg,
+    > = PickList::new(
+        &PLACEMENT_SIZE_OPTS,
+        Some(size),
+        TvMsg::PlacementSizeChanged,
+    );
+    pl = pick_list_common(pl);
+    iced_row![txt("Circle Size").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_parsimony_column<'a>(
+    columns: &'a [String],
+    column: Option<&'a String>,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<String, &[String], String, TvMsg> =
+        PickList::new(columns, column.cloned(), TvMsg::ParsimonyColumnChanged);
+    pl = pick_list_common(pl);
+    pl = pl.placeholder("None");
+    iced_row![txt("Trait").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
+
+pub(super) fn pick_list_parsimony_method<'a>(
+    method: ParsimonyMethod,
+) -> Row<'a, TvMsg> {
+    let mut pl: PickList<
+        ParsimonyMethod,
+        &[ParsimonyMethod],
+        ParsimonyMethod,
+        TvMsg,
+    > = PickList::new(
+        &PARSIMONY_METHOD_OPTS,
+        Some(method),
+        TvMsg::ParsimonyMethodChanged,
+    );
+    pl = pick_list_common(pl);
+    iced_row![txt("Method").width(Length::FillPortion(9)), pl]
+        .align_y(Vertical::Center)
+}
@@ -1,3 +1,8 @@
+use super::buttons::{
+    btn_add_attribute, btn_export_data, btn_remove_attribute,
+};
+use super::pick_lists::pick_list_nodes_table_attr;
+use crate::newick::is_valid_attribute_key;
 use crate::*;
 
 pub(super) fn table_node_data<'a>(
@@ -6,9 +11,59 @@ pub(super) fn table_node_data<'a>(
     h: Float,
 ) -> Element<'a, TvMsg> {
     if let Some(ts) = tv.sel_tre() {
+        let bar_h = BTN_H1.max(TEXT_INPUT_H) + PADDING * TWO;
+        let nodes_bar = iced_row![
+            btn_export_data(DataExport::NodesTable, true).width(SIDE_BAR_W),
+            txt_input(
+                "Filter",
+                &tv.nodes_table_filter,
+                "nodes_table_filter",
+                TvMsg::NodesTableFilterChanged,
+            ),
+            pick_list_nodes_table_attr(
+                tv.sel_tre_node_attr_keys(),
+                tv.nodes_table_attr.as_ref(),
+            )
+            .width(SIDE_BAR_W),
+        ]
+        .spacing(PADDING)
+        .align_y(Vertical::Center);
+
+        // New attributes are added to the selected nodes; removing takes the
+        // attribute off both the nodes and their branches.
+        let can_edit = !ts.sel_node_ids().is_empty()
+            && is_valid_attribute_key(tv.new_attribute_key.trim());
+        let attributes_bar = iced_row![
+            btn_export_data(DataExport::AttributesTable, true)
+                .width(SIDE_BAR_W),
+            txt_input(
+                "Name",
+                &tv.new_attribute_key,
+                "new_attribute_key",
+                TvMsg::NewAttributeKeyChanged,
+            ),
+            txt_input(
+                "Value",
+                &tv.new_attribute_value,
+                "new_attribute_value",
+                TvMsg::NewAttributeValueChanged,
+            )
+            .on_submit_maybe(can_edit.then_some(TvMsg::AddAttributeToSelected)),
+            btn_add_attribute(can_edit),
+            btn_remove_attribute(can_edit),
+        ]
+        .spacing(PADDING)
+        .align_y(Vertical::Center);
+
         iced_row![
-            nodes_table(tv, ts.clone(), w / TWO, h),
-            attributes_table(tv, ts.clone(), w / TWO, h)
+            iced_col![
+                container(nodes_bar).padding(PADDING).height(bar_h),
+                nodes_table(tv, ts.clone(), w / TWO, h - bar_h)
+            ],
+            iced_col![
+                container(attributes_bar).padding(PADDING).height(bar_h),
+                attributes_table(tv, ts.clone(), w / TWO, h - bar_h)
+            ]
         ]
         .into()
     } else {
@@ -16,6 +71,42 @@ pub(super) fn table_node_data<'a>(
     }
 }
 
+pub(super) fn table_tree_set_dists<'a>(
+    tv: &'a TreeView,
+    w: Float,
+) -> Element<'a, TvMsg> {
+    let cell =
+        |t: Text<'a>| t.size(TABLE_TXT_SIZE).width(Length::FillPortion(1));
+    let opt_cell = |t: Option<Text<'a>>| cell(t.unwrap_or_else(|| txt("-")));
+    let has_brlen = tv.tree_set.has_brlen();
+
+    let mut header: Row<TvMsg> =
+        iced_row![cell(txt("Tree")), cell(txt("RF")), cell(txt("Norm. RF"))];
+    if has_brlen {
+        header = header.push(cell(txt("Weighted RF")));
+    }
+
+    let mut rows: Column<TvMsg> = Column::new();
+    for dist in &tv.tree_set_dists {
+        let mut row: Row<TvMsg> = iced_row![
+            cell(txt_usize(dist.tre_id)),
+            opt_cell(dist.rf.map(txt_usize)),
+            opt_cell(dist.rf_norm.map(|rf_norm| txt_float(rf_norm, 3))),
+        ];
+        if has_brlen {
+            row = row.push(opt_cell(dist.wrf.map(|wrf| txt_float(wrf, 3))));
+        }
+        rows = rows.push(row);
+    }
+
+    iced_col![
+        header.padding(PADDING),
+        rule_h(SF),
+        scrollable_v(rows.padding(PADDING).spacing(SF), w, Length::Fill),
+    ]
+    .into()
+}
+
 // pub(super) fn table_nodes<'a>(
 //     tv: &'a TreeView,
 //     w: Float,
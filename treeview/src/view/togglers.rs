@@ -148,3 +148,28 @@ pub(super) fn toggler_gts<'a>(
     }
     tglr
 }
+
+pub(super) fn toggler_state_changes<'a>(
+    enabled: bool,
+    draw_state_changes: bool,
+) -> Toggler<'a, TvMsg> {
+    let mut tglr = toggler("Color State Changes", draw_state_changes);
+    if enabled {
+        tglr = tglr.on_toggle(TvMsg::StateChangesVisChanged);
+    }
+    tglr
+}
+
+pub(super) fn toggler_placement_circles<'a>(
+    draw_circles: bool,
+) -> Toggler<'a, TvMsg> {
+    toggler("Placement Circles", draw_circles)
+        .on_toggle(TvMsg::PlacementCirclesVisChanged)
+}
+
+pub(super) fn toggler_placement_density<'a>(
+    draw_density: bool,
+) -> Toggler<'a, TvMsg> {
+    toggler("Placement Density", draw_density)
+        .on_toggle(TvMsg::PlacementDensityVisChanged)
+}